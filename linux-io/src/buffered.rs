@@ -0,0 +1,119 @@
+use core::{mem, ptr};
+
+use heapless::{ArrayLength, Vec};
+use linux_sys::Error;
+
+use crate::Write;
+
+/// Wraps a writer and buffers its output
+///
+/// The buffer (`N` bytes) is written to the underlying writer when it gets full, when `flush` is
+/// called and when the `BufWriter` is dropped. Exiting the process doesn't run destructors so
+/// register a function with `process::at_exit` to flush a `BufWriter` that must outlive the program
+/// logic (e.g. one stored in a `static` variable)
+pub struct BufWriter<W, N>
+where
+    W: Write,
+    N: ArrayLength<u8>,
+{
+    buf: Vec<u8, N>,
+    inner: W,
+}
+
+impl<W, N> BufWriter<W, N>
+where
+    W: Write,
+    N: ArrayLength<u8>,
+{
+    /// Creates a new `BufWriter`
+    pub fn new(inner: W) -> Self {
+        BufWriter {
+            buf: Vec::new(),
+            inner,
+        }
+    }
+
+    /// Gets a reference to the underlying writer
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying writer
+    ///
+    /// NOTE writing directly to the underlying writer bypasses the buffer
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Returns a slice of the buffered data
+    pub fn buffer(&self) -> &[u8] {
+        &self.buf
+    }
+
+    /// Flushes the buffer and returns the underlying writer
+    pub fn into_inner(mut self) -> Result<W, Error> {
+        self.flush_buf()?;
+
+        unsafe {
+            // NOTE `buf` holds no resources (the data is `u8`s)
+            let inner = ptr::read(&self.inner);
+            mem::forget(self);
+            Ok(inner)
+        }
+    }
+
+    fn flush_buf(&mut self) -> Result<(), Error> {
+        let res = self.inner.write_all(&self.buf);
+        // NOTE on error the data is dropped rather than retried
+        self.buf.clear();
+        res
+    }
+}
+
+impl<W, N> Write for BufWriter<W, N>
+where
+    W: Write,
+    N: ArrayLength<u8>,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        if self.buf.len() + buf.len() > self.buf.capacity() {
+            self.flush_buf()?;
+        }
+
+        if buf.len() >= self.buf.capacity() {
+            // too large to buffer
+            self.inner.write(buf)
+        } else {
+            // NOTE can't fail; there's enough space left
+            self.buf.extend_from_slice(buf).ok();
+            Ok(buf.len())
+        }
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        self.flush_buf()?;
+        self.inner.flush()
+    }
+}
+
+impl<W, N> ufmt::uWrite for BufWriter<W, N>
+where
+    W: Write,
+    N: ArrayLength<u8>,
+{
+    type Error = Error;
+
+    fn write_str(&mut self, s: &str) -> Result<(), Error> {
+        self.write_all(s.as_bytes())
+    }
+}
+
+impl<W, N> Drop for BufWriter<W, N>
+where
+    W: Write,
+    N: ArrayLength<u8>,
+{
+    fn drop(&mut self) {
+        self.flush().ok();
+    }
+}
@@ -0,0 +1,65 @@
+use core::convert::Infallible;
+
+use cty::c_uint;
+pub use ufmt;
+
+// `$ getconf PIPE_BUF /`; the largest write to a pipe that the kernel performs atomically
+const PIPE_BUF: usize = 4096;
+
+// Formatting buffer of the `print!` family of macros
+pub struct Buffer {
+    bytes: [u8; PIPE_BUF],
+    len: usize,
+    fd: c_uint,
+}
+
+impl Buffer {
+    fn flush(&mut self) {
+        crate::write_all(self.fd, &self.bytes[..self.len]).ok();
+        self.len = 0;
+    }
+}
+
+impl ufmt::uWrite for Buffer {
+    type Error = Infallible;
+
+    fn write_str(&mut self, s: &str) -> Result<(), Infallible> {
+        let mut bytes = s.as_bytes();
+
+        while !bytes.is_empty() {
+            if self.len == PIPE_BUF {
+                // too much output; this breaks atomicity
+                self.flush();
+            }
+
+            let n = (PIPE_BUF - self.len).min(bytes.len());
+            self.bytes[self.len..self.len + n].copy_from_slice(&bytes[..n]);
+            self.len += n;
+            bytes = &bytes[n..];
+        }
+
+        Ok(())
+    }
+}
+
+pub fn print(fd: c_uint, f: impl FnOnce(&mut Buffer) -> Result<(), Infallible>) {
+    let mut buffer = Buffer {
+        bytes: [0; PIPE_BUF],
+        len: 0,
+        fd,
+    };
+    f(&mut buffer).ok();
+    buffer.flush();
+}
+
+// Called by the runtimes (`linux-rt` and `linux-rtfm`) when the program returns
+pub fn run_at_exit() {
+    crate::process::run_at_exit()
+}
+
+// Reports an unrecoverable runtime error and terminates the process
+pub fn fatal(msg: &str) -> ! {
+    // NOTE *single* `write` system call
+    crate::Stderr.write(msg.as_bytes()).ok();
+    linux_sys::exit_group(101)
+}
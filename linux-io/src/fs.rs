@@ -0,0 +1,385 @@
+//! Filesystem manipulation operations
+//!
+//! Paths are byte strings. They are passed to the kernel as they are if they end with a null byte;
+//! otherwise they are copied into a (null-terminated) stack buffer of `PATH_MAX` bytes first
+
+use cty::{c_char, c_int, c_uint};
+use linux_sys::{umode_t, Error, AT_FDCWD};
+
+use crate::{Read, Write};
+
+// `$ getconf PATH_MAX /`
+const PATH_MAX: usize = 4096;
+
+/// An object providing access to an open file on the filesystem
+///
+/// The file is closed when this value is dropped
+pub struct File {
+    fd: c_uint,
+}
+
+impl File {
+    /// Attempts to open a file in read-only mode
+    pub fn open<P>(path: P) -> Result<File, Error>
+    where
+        P: AsRef<[u8]>,
+    {
+        OpenOptions::new().read(true).open(path)
+    }
+
+    /// Opens a file in write-only mode
+    ///
+    /// This function will create the file if it doesn't exist, and will truncate it if it does
+    pub fn create<P>(path: P) -> Result<File, Error>
+    where
+        P: AsRef<[u8]>,
+    {
+        OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)
+    }
+
+    /// Returns the underlying file descriptor
+    pub fn as_raw_fd(&self) -> c_uint {
+        self.fd
+    }
+
+    /// Pull some bytes from this file into the specified buffer, returning how many bytes were
+    /// read
+    pub fn read(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        crate::read(self.fd, buf)
+    }
+
+    /// Write a buffer into this file, returning how many bytes were written
+    pub fn write(&self, buf: &[u8]) -> Result<usize, Error> {
+        crate::write(self.fd, buf)
+    }
+
+    /// Attempts to write an entire buffer into this file
+    pub fn write_all(&self, buf: &[u8]) -> Result<(), Error> {
+        crate::write_all(self.fd, buf)
+    }
+
+    /// Reads a number of bytes starting from the given offset without changing the file offset
+    pub fn read_at(&self, buf: &mut [u8], offset: u64) -> Result<usize, Error> {
+        unsafe { linux_sys::pread(self.fd, buf, offset as i64) }
+    }
+
+    /// Writes a number of bytes starting from the given offset without changing the file offset
+    pub fn write_at(&self, buf: &[u8], offset: u64) -> Result<usize, Error> {
+        unsafe { linux_sys::pwrite(self.fd, buf, offset as i64) }
+    }
+
+    /// Seek to an offset, in bytes, in the file
+    ///
+    /// Returns the new position from the start of the file
+    pub fn seek(&self, pos: SeekFrom) -> Result<u64, Error> {
+        let (offset, whence) = match pos {
+            SeekFrom::Start(n) => (n as i64, linux_sys::SEEK_SET),
+            SeekFrom::End(n) => (n, linux_sys::SEEK_END),
+            SeekFrom::Current(n) => (n, linux_sys::SEEK_CUR),
+        };
+
+        unsafe { linux_sys::lseek(self.fd, offset, whence).map(|pos| pos as u64) }
+    }
+
+    /// Attempts to sync all the data and metadata of this file to disk
+    pub fn sync_all(&self) -> Result<(), Error> {
+        linux_sys::fsync(self.fd)
+    }
+
+    /// Queries metadata about the underlying file
+    pub fn metadata(&self) -> Result<Metadata, Error> {
+        linux_sys::fstat(self.fd).map(|stat| Metadata {
+            len: stat.st_size as u64,
+            mode: stat.st_mode as umode_t,
+        })
+    }
+}
+
+impl Drop for File {
+    fn drop(&mut self) {
+        // NOTE the file descriptor is released even if `close` reports an error
+        unsafe {
+            linux_sys::close(self.fd).ok();
+        }
+    }
+}
+
+impl Read for File {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        crate::read(self.fd, buf)
+    }
+}
+
+impl Read for &'_ File {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        crate::read(self.fd, buf)
+    }
+}
+
+impl Write for File {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        crate::write(self.fd, buf)
+    }
+}
+
+impl Write for &'_ File {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        crate::write(self.fd, buf)
+    }
+}
+
+impl ufmt::uWrite for File {
+    type Error = Error;
+
+    fn write_str(&mut self, s: &str) -> Result<(), Error> {
+        crate::write_all(self.fd, s.as_bytes())
+    }
+}
+
+impl ufmt::uWrite for &'_ File {
+    type Error = Error;
+
+    fn write_str(&mut self, s: &str) -> Result<(), Error> {
+        crate::write_all(self.fd, s.as_bytes())
+    }
+}
+
+/// Options and flags which can be used to configure how a file is opened
+#[derive(Clone, Copy)]
+pub struct OpenOptions {
+    read: bool,
+    write: bool,
+    append: bool,
+    truncate: bool,
+    create: bool,
+    create_new: bool,
+    mode: umode_t,
+}
+
+impl OpenOptions {
+    /// Creates a blank new set of options ready for configuration
+    ///
+    /// All options are initially set to `false`; the mode of created files is `0o666` (before
+    /// the process umask is applied)
+    pub fn new() -> Self {
+        OpenOptions {
+            read: false,
+            write: false,
+            append: false,
+            truncate: false,
+            create: false,
+            create_new: false,
+            mode: 0o666,
+        }
+    }
+
+    /// Sets the option for read access
+    pub fn read(&mut self, read: bool) -> &mut Self {
+        self.read = read;
+        self
+    }
+
+    /// Sets the option for write access
+    pub fn write(&mut self, write: bool) -> &mut Self {
+        self.write = write;
+        self
+    }
+
+    /// Sets the option for the append mode
+    ///
+    /// All writes will append to the file instead of overwriting its contents. This implies write
+    /// access
+    pub fn append(&mut self, append: bool) -> &mut Self {
+        self.append = append;
+        self
+    }
+
+    /// Sets the option for truncating a previous file
+    ///
+    /// The file must be opened with write access for truncate to work
+    pub fn truncate(&mut self, truncate: bool) -> &mut Self {
+        self.truncate = truncate;
+        self
+    }
+
+    /// Sets the option to create a new file if it doesn't already exist
+    pub fn create(&mut self, create: bool) -> &mut Self {
+        self.create = create;
+        self
+    }
+
+    /// Sets the option to create a new file, failing (`EEXIST`) if it already exists
+    pub fn create_new(&mut self, create_new: bool) -> &mut Self {
+        self.create_new = create_new;
+        self
+    }
+
+    /// Sets the mode bits that a new file will be created with
+    pub fn mode(&mut self, mode: umode_t) -> &mut Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Opens a file at `path` with the options specified by `self`
+    ///
+    /// Returns `EINVAL` if the combination of options is invalid
+    pub fn open<P>(&self, path: P) -> Result<File, Error>
+    where
+        P: AsRef<[u8]>,
+    {
+        let flags = self.flags()?;
+
+        with_c_path(path.as_ref(), |path| unsafe {
+            linux_sys::openat(AT_FDCWD, path, flags, self.mode).map(|fd| File { fd })
+        })
+    }
+
+    fn flags(&self) -> Result<c_int, Error> {
+        let access = match (self.read, self.write || self.append) {
+            (true, false) => linux_sys::O_RDONLY,
+            (false, true) => linux_sys::O_WRONLY,
+            (true, true) => linux_sys::O_RDWR,
+            (false, false) => return Err(Error::EINVAL),
+        };
+
+        let mut flags = access | linux_sys::O_CLOEXEC;
+        if self.append {
+            flags |= linux_sys::O_APPEND;
+        }
+
+        if self.truncate {
+            if !self.write || self.append {
+                return Err(Error::EINVAL);
+            }
+
+            flags |= linux_sys::O_TRUNC;
+        }
+
+        if self.create_new {
+            flags |= linux_sys::O_CREAT | linux_sys::O_EXCL;
+        } else if self.create {
+            flags |= linux_sys::O_CREAT;
+        }
+
+        Ok(flags)
+    }
+}
+
+/// Enumeration of possible methods to seek within a file
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub enum SeekFrom {
+    /// Sets the offset to the provided number of bytes
+    Start(u64),
+
+    /// Sets the offset to the size of the file plus the specified number of bytes
+    End(i64),
+
+    /// Sets the offset to the current position plus the specified number of bytes
+    Current(i64),
+}
+
+/// Metadata information about a file
+#[derive(Clone, Copy)]
+pub struct Metadata {
+    len: u64,
+    mode: umode_t,
+}
+
+impl Metadata {
+    /// Returns the size of the file, in bytes
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns `true` if this metadata is for a directory
+    pub fn is_dir(&self) -> bool {
+        self.mode & linux_sys::S_IFMT == linux_sys::S_IFDIR
+    }
+
+    /// Returns `true` if this metadata is for a regular file
+    pub fn is_file(&self) -> bool {
+        self.mode & linux_sys::S_IFMT == linux_sys::S_IFREG
+    }
+
+    /// Returns the permission bits of the file (e.g. `0o644`)
+    pub fn permissions(&self) -> umode_t {
+        self.mode & !linux_sys::S_IFMT
+    }
+}
+
+/// Queries the filesystem for metadata about the file at `path`; symbolic links are followed
+pub fn metadata<P>(path: P) -> Result<Metadata, Error>
+where
+    P: AsRef<[u8]>,
+{
+    with_c_path(path.as_ref(), |path| unsafe {
+        linux_sys::statx(
+            AT_FDCWD,
+            path,
+            0,
+            linux_sys::STATX_TYPE | linux_sys::STATX_MODE | linux_sys::STATX_SIZE,
+        )
+        .map(|statx| Metadata {
+            len: statx.stx_size,
+            mode: statx.stx_mode,
+        })
+    })
+}
+
+/// Creates a new, empty directory at `path`
+pub fn create_dir<P>(path: P) -> Result<(), Error>
+where
+    P: AsRef<[u8]>,
+{
+    with_c_path(path.as_ref(), |path| unsafe {
+        linux_sys::mkdirat(AT_FDCWD, path, 0o777)
+    })
+}
+
+/// Removes the file at `path`
+pub fn remove_file<P>(path: P) -> Result<(), Error>
+where
+    P: AsRef<[u8]>,
+{
+    with_c_path(path.as_ref(), |path| unsafe {
+        linux_sys::unlinkat(AT_FDCWD, path, 0)
+    })
+}
+
+/// Removes the empty directory at `path`
+pub fn remove_dir<P>(path: P) -> Result<(), Error>
+where
+    P: AsRef<[u8]>,
+{
+    with_c_path(path.as_ref(), |path| unsafe {
+        linux_sys::unlinkat(AT_FDCWD, path, linux_sys::AT_REMOVEDIR)
+    })
+}
+
+// Calls `f` with a null-terminated version of `path`
+fn with_c_path<T>(
+    path: &[u8],
+    f: impl FnOnce(*const c_char) -> Result<T, Error>,
+) -> Result<T, Error> {
+    match path.iter().position(|b| *b == 0) {
+        // already null-terminated
+        Some(pos) if pos == path.len() - 1 => f(path.as_ptr() as *const c_char),
+
+        // interior null byte
+        Some(_) => Err(Error::EINVAL),
+
+        None => {
+            if path.len() >= PATH_MAX {
+                return Err(Error::ENAMETOOLONG);
+            }
+
+            let mut buf = [0; PATH_MAX];
+            buf[..path.len()].copy_from_slice(path);
+            f(buf.as_ptr() as *const c_char)
+        }
+    }
+}
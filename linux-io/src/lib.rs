@@ -8,11 +8,135 @@
 
 use core::slice;
 
-use cty::c_uint;
+use cty::{c_uint, c_ulong};
+use heapless::{ArrayLength, Vec};
 use linux_sys::Error;
 
+pub use crate::buffered::BufWriter;
+
+mod buffered;
+#[doc(hidden)]
+pub mod export;
+pub mod fs;
+mod macros;
+pub mod net;
+pub mod pipe;
 pub mod process;
+mod socket;
 pub mod time;
+pub mod unix;
+
+/// The `Read` trait allows for reading bytes from a source
+pub trait Read {
+    /// Pull some bytes from this source into the specified buffer, returning how many bytes were
+    /// read
+    ///
+    /// A return value of `0` means that the end of the source (e.g. end of file) was reached
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error>;
+}
+
+/// A trait for objects which are byte-oriented sinks
+pub trait Write {
+    /// Write a buffer into this writer, returning how many bytes were written
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error>;
+
+    /// Attempts to write an entire buffer into this writer
+    ///
+    /// Writes interrupted by signal handlers (`EINTR`) are retried. Returns `EIO` if `write`
+    /// returns `0` before the whole buffer has been written
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Error> {
+        write_all_with(buf, |buf| self.write(buf))
+    }
+
+    /// Flush this output stream, ensuring that all intermediately buffered contents reach their
+    /// destination
+    fn flush(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// *Unbuffered* standard input singleton
+pub struct Stdin;
+
+impl Stdin {
+    const FILENO: c_uint = 0;
+
+    /// Pull some bytes from standard input into the specified buffer, returning how many bytes
+    /// were read
+    ///
+    /// This blocks until some input is available unless standard input is in non-blocking mode;
+    /// in that case `EAGAIN` is returned when there's no input
+    pub fn read(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        read(Self::FILENO, buf)
+    }
+
+    /// Attempts to read some bytes without blocking
+    ///
+    /// Returns `WouldBlock` if no input is available. Standard input must be in non-blocking
+    /// mode (see `set_nonblocking`) or this will block like `read`
+    pub fn try_read(&self, buf: &mut [u8]) -> nb::Result<usize, Error> {
+        read(Self::FILENO, buf).map_err(|e| {
+            if e == Error::EAGAIN {
+                nb::Error::WouldBlock
+            } else {
+                nb::Error::Other(e)
+            }
+        })
+    }
+
+    /// Reads all bytes until a newline (`\n`), which is included, and appends them to `buf`
+    ///
+    /// Returns the number of bytes read; `0` means that the end of the input was reached. If `buf`
+    /// gets full before a newline is found the partial line is left in it and the rest of the
+    /// line can be read with another call
+    ///
+    /// NOTE this reads one byte at a time so that no input past the newline is consumed
+    pub fn read_line<N>(&self, buf: &mut Vec<u8, N>) -> Result<usize, Error>
+    where
+        N: ArrayLength<u8>,
+    {
+        let mut n = 0;
+        while buf.len() < buf.capacity() {
+            let mut byte = 0;
+            match self.read(slice::from_mut(&mut byte)) {
+                Ok(0) => break,
+                Ok(_) => {}
+                Err(Error::EINTR) => continue,
+                Err(e) => return Err(e),
+            }
+
+            // NOTE can't fail because of the loop condition
+            buf.push(byte).ok();
+            n += 1;
+
+            if byte == b'\n' {
+                break;
+            }
+        }
+
+        Ok(n)
+    }
+
+    /// Moves standard input into or out of non-blocking mode
+    ///
+    /// NOTE this sets `O_NONBLOCK` on the *open file description*, which may be shared with other
+    /// processes (e.g. the parent shell)
+    pub fn set_nonblocking(&self, nonblocking: bool) -> Result<(), Error> {
+        set_nonblocking(Self::FILENO, nonblocking)
+    }
+}
+
+impl Read for &'_ Stdin {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        read(Stdin::FILENO, buf)
+    }
+}
+
+impl Read for Stdin {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        read(Self::FILENO, buf)
+    }
+}
 
 /// *Unbuffered* standard output singleton
 pub struct Stdout;
@@ -47,6 +171,18 @@ impl ufmt::uWrite for Stdout {
     }
 }
 
+impl Write for &'_ Stdout {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        write(Stdout::FILENO, buf)
+    }
+}
+
+impl Write for Stdout {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        write(Self::FILENO, buf)
+    }
+}
+
 /// *Unbuffered* standard error singleton
 pub struct Stderr;
 
@@ -80,16 +216,62 @@ impl ufmt::uWrite for Stderr {
     }
 }
 
+impl Write for &'_ Stderr {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        write(Stderr::FILENO, buf)
+    }
+}
+
+impl Write for Stderr {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        write(Self::FILENO, buf)
+    }
+}
+
+fn read(fd: c_uint, buf: &mut [u8]) -> Result<usize, Error> {
+    unsafe { Ok(linux_sys::read(fd, buf)?) }
+}
+
 fn write(fd: c_uint, buf: &[u8]) -> Result<usize, Error> {
     unsafe { Ok(linux_sys::write(fd, buf)?) }
 }
 
-fn write_all(fd: c_uint, mut buf: &[u8]) -> Result<(), Error> {
-    while !buf.is_empty() {
-        let n = write(fd, buf)?;
+fn write_all(fd: c_uint, buf: &[u8]) -> Result<(), Error> {
+    write_all_with(buf, |buf| write(fd, buf))
+}
 
-        buf = unsafe { slice::from_raw_parts(buf.as_ptr().add(n), buf.len() - n) };
+// Calls `write` until all of `buf` has been written
+pub(crate) fn write_all_with(
+    mut buf: &[u8],
+    mut write: impl FnMut(&[u8]) -> Result<usize, Error>,
+) -> Result<(), Error> {
+    while !buf.is_empty() {
+        match write(buf) {
+            // no progress; retrying could loop forever
+            Ok(0) => return Err(Error::EIO),
+            Ok(n) => buf = &buf[n..],
+            // interrupted by a signal handler before anything was written
+            Err(Error::EINTR) => {}
+            Err(e) => return Err(e),
+        }
     }
 
     Ok(())
 }
+
+fn set_nonblocking(fd: c_uint, nonblocking: bool) -> Result<(), Error> {
+    unsafe {
+        let flags = linux_sys::fcntl(fd, linux_sys::F_GETFL, 0)?;
+        let new = if nonblocking {
+            flags | linux_sys::O_NONBLOCK
+        } else {
+            flags & !linux_sys::O_NONBLOCK
+        };
+
+        if new != flags {
+            linux_sys::fcntl(fd, linux_sys::F_SETFL, new as c_ulong)?;
+        }
+
+        Ok(())
+    }
+}
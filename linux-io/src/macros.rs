@@ -0,0 +1,53 @@
+/// Prints to the standard output
+///
+/// The arguments are formatted, using `ufmt::uwrite!`, into a stack buffer of `PIPE_BUF` bytes and
+/// then written with a *single* `write` system call so the output of different threads or
+/// processes doesn't interleave. Only output larger than `PIPE_BUF` takes more than one `write`.
+/// Errors are ignored
+#[macro_export]
+macro_rules! print {
+    ($($tt:tt)*) => {{
+        // NOTE `uwrite!` expands to `ufmt::*` paths, which must resolve in the caller's scope
+        use $crate::export::ufmt;
+        $crate::export::print(1, |w| $crate::export::ufmt::uwrite!(w, $($tt)*))
+    }};
+}
+
+/// Prints to the standard output, with a newline
+///
+/// See `print!` for more details
+#[macro_export]
+macro_rules! println {
+    () => {
+        $crate::print!("\n")
+    };
+    ($($tt:tt)*) => {{
+        use $crate::export::ufmt;
+        $crate::export::print(1, |w| $crate::export::ufmt::uwriteln!(w, $($tt)*))
+    }};
+}
+
+/// Prints to the standard error
+///
+/// See `print!` for more details
+#[macro_export]
+macro_rules! eprint {
+    ($($tt:tt)*) => {{
+        use $crate::export::ufmt;
+        $crate::export::print(2, |w| $crate::export::ufmt::uwrite!(w, $($tt)*))
+    }};
+}
+
+/// Prints to the standard error, with a newline
+///
+/// See `print!` for more details
+#[macro_export]
+macro_rules! eprintln {
+    () => {
+        $crate::eprint!("\n")
+    };
+    ($($tt:tt)*) => {{
+        use $crate::export::ufmt;
+        $crate::export::print(2, |w| $crate::export::ufmt::uwriteln!(w, $($tt)*))
+    }};
+}
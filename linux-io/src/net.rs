@@ -0,0 +1,672 @@
+//! Networking primitives for TCP / UDP communication over IPv4 and IPv6
+//!
+//! Writing to a TCP stream whose peer has closed the connection returns `EPIPE`; `SIGPIPE` is never
+//! raised
+
+use core::{mem, time::Duration};
+
+use cty::{c_int, c_uint, c_void};
+use linux_sys::{
+    cmsghdr, in6_addr, in_addr, sa_family_t, sockaddr, sockaddr_in, sockaddr_in6, socklen_t,
+    timespec, Error,
+};
+use ufmt::{uDisplay, uWrite, uwrite, Formatter};
+
+use crate::{socket::Socket, Read, Write};
+
+/// An IPv4 address
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub struct Ipv4Addr {
+    octets: [u8; 4],
+}
+
+impl Ipv4Addr {
+    /// The address of the loopback interface: `127.0.0.1`
+    pub const LOCALHOST: Self = Ipv4Addr::new(127, 0, 0, 1);
+
+    /// The "any" address: `0.0.0.0`
+    pub const UNSPECIFIED: Self = Ipv4Addr::new(0, 0, 0, 0);
+
+    /// The broadcast address: `255.255.255.255`
+    pub const BROADCAST: Self = Ipv4Addr::new(255, 255, 255, 255);
+
+    /// Creates a new IPv4 address from four eight-bit octets
+    pub const fn new(a: u8, b: u8, c: u8, d: u8) -> Self {
+        Ipv4Addr {
+            octets: [a, b, c, d],
+        }
+    }
+
+    /// Returns the four eight-bit integers that make up this address
+    pub fn octets(&self) -> [u8; 4] {
+        self.octets
+    }
+}
+
+impl uDisplay for Ipv4Addr {
+    fn fmt<W>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: uWrite + ?Sized,
+    {
+        let [a, b, c, d] = self.octets;
+        uwrite!(f, "{}.{}.{}.{}", a, b, c, d)
+    }
+}
+
+/// An IPv6 address
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub struct Ipv6Addr {
+    octets: [u8; 16],
+}
+
+impl Ipv6Addr {
+    /// The address of the loopback interface: `::1`
+    pub const LOCALHOST: Self = Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 1);
+
+    /// The "any" address: `::`
+    pub const UNSPECIFIED: Self = Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 0);
+
+    /// Creates a new IPv6 address from eight 16-bit segments
+    #[allow(clippy::too_many_arguments)]
+    pub const fn new(a: u16, b: u16, c: u16, d: u16, e: u16, f: u16, g: u16, h: u16) -> Self {
+        Ipv6Addr {
+            octets: [
+                (a >> 8) as u8,
+                a as u8,
+                (b >> 8) as u8,
+                b as u8,
+                (c >> 8) as u8,
+                c as u8,
+                (d >> 8) as u8,
+                d as u8,
+                (e >> 8) as u8,
+                e as u8,
+                (f >> 8) as u8,
+                f as u8,
+                (g >> 8) as u8,
+                g as u8,
+                (h >> 8) as u8,
+                h as u8,
+            ],
+        }
+    }
+
+    /// Returns the sixteen eight-bit integers that make up this address
+    pub fn octets(&self) -> [u8; 16] {
+        self.octets
+    }
+
+    /// Returns the eight 16-bit segments that make up this address
+    pub fn segments(&self) -> [u16; 8] {
+        let mut segments = [0; 8];
+        for (segment, pair) in segments.iter_mut().zip(self.octets.chunks(2)) {
+            *segment = u16::from(pair[0]) << 8 | u16::from(pair[1]);
+        }
+        segments
+    }
+}
+
+// NOTE all the segments are printed; runs of zeros are not compressed into `::`
+impl uDisplay for Ipv6Addr {
+    fn fmt<W>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: uWrite + ?Sized,
+    {
+        const HEX: &[u8; 16] = b"0123456789abcdef";
+
+        for (i, segment) in self.segments().iter().enumerate() {
+            if i != 0 {
+                f.write_str(":")?;
+            }
+
+            // lowercase hexadecimal without leading zeros
+            let mut buf = [0; 4];
+            let mut start = buf.len();
+            let mut n = *segment;
+            loop {
+                start -= 1;
+                buf[start] = HEX[usize::from(n & 0xf)];
+                n >>= 4;
+
+                if n == 0 {
+                    break;
+                }
+            }
+
+            // NOTE the buffer only contains ASCII characters
+            f.write_str(unsafe { core::str::from_utf8_unchecked(&buf[start..]) })?;
+        }
+
+        Ok(())
+    }
+}
+
+/// An IP address, either IPv4 or IPv6
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub enum IpAddr {
+    /// An IPv4 address
+    V4(Ipv4Addr),
+
+    /// An IPv6 address
+    V6(Ipv6Addr),
+}
+
+impl From<Ipv4Addr> for IpAddr {
+    fn from(ip: Ipv4Addr) -> Self {
+        IpAddr::V4(ip)
+    }
+}
+
+impl From<Ipv6Addr> for IpAddr {
+    fn from(ip: Ipv6Addr) -> Self {
+        IpAddr::V6(ip)
+    }
+}
+
+impl uDisplay for IpAddr {
+    fn fmt<W>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: uWrite + ?Sized,
+    {
+        match self {
+            IpAddr::V4(ip) => uDisplay::fmt(ip, f),
+            IpAddr::V6(ip) => uDisplay::fmt(ip, f),
+        }
+    }
+}
+
+/// An IP address plus a port number
+///
+/// Anything that converts into it (e.g. `(Ipv4Addr::LOCALHOST, 8080)`) can be used where a socket
+/// address is expected
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub struct SocketAddr {
+    ip: IpAddr,
+    port: u16,
+}
+
+impl SocketAddr {
+    /// Creates a new socket address from an IP address and a port number
+    pub fn new(ip: IpAddr, port: u16) -> Self {
+        SocketAddr { ip, port }
+    }
+
+    /// Returns the IP address
+    pub fn ip(&self) -> IpAddr {
+        self.ip
+    }
+
+    /// Returns the port number
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    fn family(&self) -> c_int {
+        match self.ip {
+            IpAddr::V4(_) => linux_sys::AF_INET,
+            IpAddr::V6(_) => linux_sys::AF_INET6,
+        }
+    }
+
+    fn to_raw(&self) -> (RawAddr, socklen_t) {
+        match self.ip {
+            IpAddr::V4(ip) => (
+                RawAddr {
+                    v4: sockaddr_in {
+                        sin_family: linux_sys::AF_INET as sa_family_t,
+                        sin_port: self.port.to_be(),
+                        sin_addr: in_addr {
+                            s_addr: u32::from_ne_bytes(ip.octets),
+                        },
+                        sin_zero: [0; 8],
+                    },
+                },
+                mem::size_of::<sockaddr_in>() as socklen_t,
+            ),
+
+            IpAddr::V6(ip) => (
+                RawAddr {
+                    v6: sockaddr_in6 {
+                        sin6_family: linux_sys::AF_INET6 as sa_family_t,
+                        sin6_port: self.port.to_be(),
+                        sin6_flowinfo: 0,
+                        sin6_addr: in6_addr { s6_addr: ip.octets },
+                        sin6_scope_id: 0,
+                    },
+                },
+                mem::size_of::<sockaddr_in6>() as socklen_t,
+            ),
+        }
+    }
+
+    fn from_raw(raw: &RawAddr) -> Result<Self, Error> {
+        unsafe {
+            match c_int::from(raw.v4.sin_family) {
+                linux_sys::AF_INET => Ok(SocketAddr {
+                    ip: IpAddr::V4(Ipv4Addr {
+                        octets: raw.v4.sin_addr.s_addr.to_ne_bytes(),
+                    }),
+                    port: u16::from_be(raw.v4.sin_port),
+                }),
+
+                linux_sys::AF_INET6 => Ok(SocketAddr {
+                    ip: IpAddr::V6(Ipv6Addr {
+                        octets: raw.v6.sin6_addr.s6_addr,
+                    }),
+                    port: u16::from_be(raw.v6.sin6_port),
+                }),
+
+                _ => Err(Error::EAFNOSUPPORT),
+            }
+        }
+    }
+}
+
+impl<I> From<(I, u16)> for SocketAddr
+where
+    I: Into<IpAddr>,
+{
+    fn from((ip, port): (I, u16)) -> Self {
+        SocketAddr::new(ip.into(), port)
+    }
+}
+
+impl uDisplay for SocketAddr {
+    fn fmt<W>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: uWrite + ?Sized,
+    {
+        match self.ip {
+            IpAddr::V4(ip) => uwrite!(f, "{}:{}", ip, self.port),
+            IpAddr::V6(ip) => uwrite!(f, "[{}]:{}", ip, self.port),
+        }
+    }
+}
+
+// Storage for either kind of IP socket address
+#[derive(Clone, Copy)]
+#[repr(C)]
+union RawAddr {
+    v4: sockaddr_in,
+    v6: sockaddr_in6,
+}
+
+impl RawAddr {
+    fn as_ptr(&self) -> *const sockaddr {
+        self as *const RawAddr as *const sockaddr
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut sockaddr {
+        self as *mut RawAddr as *mut sockaddr
+    }
+}
+
+// Room for a single `SCM_TIMESTAMPNS` control message
+#[repr(C)]
+struct Timestamp {
+    header: cmsghdr,
+    timespec: timespec,
+}
+
+/// A UDP socket
+///
+/// The socket is closed when this value is dropped
+pub struct UdpSocket {
+    inner: Socket,
+}
+
+impl UdpSocket {
+    /// Creates a UDP socket bound to the given address
+    ///
+    /// Binding to port `0` picks an unused port; use `local_addr` to find out which one
+    pub fn bind<A>(addr: A) -> Result<UdpSocket, Error>
+    where
+        A: Into<SocketAddr>,
+    {
+        let addr = addr.into();
+        let inner = Socket::new(addr.family(), linux_sys::SOCK_DGRAM)?;
+        let (raw, len) = addr.to_raw();
+        unsafe { inner.bind(raw.as_ptr(), len)? }
+        Ok(UdpSocket { inner })
+    }
+
+    /// Connects the socket to the given address
+    ///
+    /// `send` sends datagrams to this address and `recv` only receives datagrams from it
+    pub fn connect<A>(&self, addr: A) -> Result<(), Error>
+    where
+        A: Into<SocketAddr>,
+    {
+        let (raw, len) = addr.into().to_raw();
+        unsafe { self.inner.connect(raw.as_ptr(), len) }
+    }
+
+    /// Returns the address the socket is bound to
+    pub fn local_addr(&self) -> Result<SocketAddr, Error> {
+        local_addr(&self.inner)
+    }
+
+    /// Returns the underlying file descriptor
+    pub fn as_raw_fd(&self) -> c_uint {
+        self.inner.as_raw_fd()
+    }
+
+    /// Sends a datagram to the address the socket is connected to
+    pub fn send(&self, buf: &[u8]) -> Result<usize, Error> {
+        self.inner.send(buf, 0)
+    }
+
+    /// Sends a datagram to the given address
+    pub fn send_to<A>(&self, buf: &[u8], addr: A) -> Result<usize, Error>
+    where
+        A: Into<SocketAddr>,
+    {
+        let (raw, len) = addr.into().to_raw();
+        unsafe { self.inner.send_to(buf, 0, raw.as_ptr(), len) }
+    }
+
+    /// Receives a datagram, returning its size
+    ///
+    /// If the datagram doesn't fit in `buf` the excess bytes are discarded
+    pub fn recv(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        self.inner.recv(buf, 0)
+    }
+
+    /// Receives a datagram, returning its size and the address of the sender
+    ///
+    /// If the datagram doesn't fit in `buf` the excess bytes are discarded
+    pub fn recv_from(&self, buf: &mut [u8]) -> Result<(usize, SocketAddr), Error> {
+        let mut raw: RawAddr = unsafe { mem::zeroed() };
+        let (n, _) = unsafe {
+            self.inner.recv_msg(
+                buf,
+                0,
+                raw.as_mut_ptr(),
+                mem::size_of::<RawAddr>() as socklen_t,
+                core::ptr::null_mut(),
+                0,
+            )?
+        };
+
+        Ok((n, SocketAddr::from_raw(&raw)?))
+    }
+
+    /// Like `recv_from` but also returns the time at which the datagram was received, as a
+    /// duration since the Unix epoch (`CLOCK_REALTIME`)
+    ///
+    /// The timestamp is `None` unless timestamping has been enabled with `set_timestamps`
+    pub fn recv_from_timestamped(
+        &self,
+        buf: &mut [u8],
+    ) -> Result<(usize, SocketAddr, Option<Duration>), Error> {
+        let mut raw: RawAddr = unsafe { mem::zeroed() };
+        let mut control: Timestamp = unsafe { mem::zeroed() };
+        let (n, msg) = unsafe {
+            self.inner.recv_msg(
+                buf,
+                0,
+                raw.as_mut_ptr(),
+                mem::size_of::<RawAddr>() as socklen_t,
+                &mut control as *mut Timestamp as *mut c_void,
+                mem::size_of::<Timestamp>(),
+            )?
+        };
+
+        let timestamp = if msg.msg_controllen >= mem::size_of::<Timestamp>()
+            && control.header.cmsg_level == linux_sys::SOL_SOCKET
+            && control.header.cmsg_type == linux_sys::SCM_TIMESTAMPNS
+        {
+            Some(Duration::new(
+                control.timespec.tv_sec as u64,
+                control.timespec.tv_nsec as u32,
+            ))
+        } else {
+            None
+        };
+
+        Ok((n, SocketAddr::from_raw(&raw)?, timestamp))
+    }
+
+    /// Sets the size of the receive buffer (`SO_RCVBUF`)
+    ///
+    /// NOTE the kernel doubles the requested size to account for its bookkeeping overhead;
+    /// `recv_buffer_size` reports the doubled value
+    pub fn set_recv_buffer_size(&self, size: usize) -> Result<(), Error> {
+        set_recv_buffer_size(&self.inner, size)
+    }
+
+    /// Returns the size of the receive buffer (`SO_RCVBUF`)
+    pub fn recv_buffer_size(&self) -> Result<usize, Error> {
+        self.inner
+            .option(linux_sys::SOL_SOCKET, linux_sys::SO_RCVBUF)
+            .map(|size| size as usize)
+    }
+
+    /// Sets the priority of the datagrams sent on this socket (`SO_PRIORITY`)
+    ///
+    /// Priorities above `6` require the `CAP_NET_ADMIN` capability
+    pub fn set_priority(&self, priority: u32) -> Result<(), Error> {
+        set_priority(&self.inner, priority)
+    }
+
+    /// Enables or disables reception timestamps (`SO_TIMESTAMPNS`)
+    ///
+    /// See `recv_from_timestamped`
+    pub fn set_timestamps(&self, on: bool) -> Result<(), Error> {
+        self.inner.set_option(
+            linux_sys::SOL_SOCKET,
+            linux_sys::SO_TIMESTAMPNS,
+            on as c_int,
+        )
+    }
+
+    /// Moves the socket into or out of non-blocking mode
+    ///
+    /// In non-blocking mode the receive methods return `EAGAIN` instead of blocking
+    pub fn set_nonblocking(&self, nonblocking: bool) -> Result<(), Error> {
+        self.inner.set_nonblocking(nonblocking)
+    }
+}
+
+/// A TCP socket server, listening for connections
+///
+/// The socket is closed when this value is dropped
+pub struct TcpListener {
+    inner: Socket,
+}
+
+impl TcpListener {
+    /// Creates a new `TcpListener` bound to the specified address
+    ///
+    /// Binding to port `0` picks an unused port; use `local_addr` to find out which one
+    pub fn bind<A>(addr: A) -> Result<TcpListener, Error>
+    where
+        A: Into<SocketAddr>,
+    {
+        let addr = addr.into();
+        let inner = Socket::new(addr.family(), linux_sys::SOCK_STREAM)?;
+        // allow re-binding the address while old connections linger in the `TIME_WAIT` state
+        inner.set_option(linux_sys::SOL_SOCKET, linux_sys::SO_REUSEADDR, 1)?;
+        let (raw, len) = addr.to_raw();
+        unsafe { inner.bind(raw.as_ptr(), len)? }
+        inner.listen()?;
+        Ok(TcpListener { inner })
+    }
+
+    /// Accepts a new incoming connection, returning the stream and the address of the peer
+    ///
+    /// This blocks until a client connects unless the listener is in non-blocking mode; in that
+    /// case `EAGAIN` is returned when there are no pending connections
+    ///
+    /// The address is `None` if the kernel reports it in a family other than IPv4 or IPv6; the
+    /// connection is returned regardless so it's not dropped on the floor
+    pub fn accept(&self) -> Result<(TcpStream, Option<SocketAddr>), Error> {
+        let mut raw: RawAddr = unsafe { mem::zeroed() };
+        let mut len = mem::size_of::<RawAddr>() as socklen_t;
+        let inner = unsafe { self.inner.accept(raw.as_mut_ptr(), &mut len)? };
+        Ok((TcpStream { inner }, SocketAddr::from_raw(&raw).ok()))
+    }
+
+    /// Returns the address the listener is bound to
+    pub fn local_addr(&self) -> Result<SocketAddr, Error> {
+        local_addr(&self.inner)
+    }
+
+    /// Returns the underlying file descriptor
+    pub fn as_raw_fd(&self) -> c_uint {
+        self.inner.as_raw_fd()
+    }
+
+    /// Moves the listener into or out of non-blocking mode
+    pub fn set_nonblocking(&self, nonblocking: bool) -> Result<(), Error> {
+        self.inner.set_nonblocking(nonblocking)
+    }
+}
+
+/// A TCP stream between a local and a remote socket
+///
+/// The socket is closed when this value is dropped
+pub struct TcpStream {
+    inner: Socket,
+}
+
+impl TcpStream {
+    /// Opens a TCP connection to the given address
+    pub fn connect<A>(addr: A) -> Result<TcpStream, Error>
+    where
+        A: Into<SocketAddr>,
+    {
+        let addr = addr.into();
+        let inner = Socket::new(addr.family(), linux_sys::SOCK_STREAM)?;
+        let (raw, len) = addr.to_raw();
+        unsafe { inner.connect(raw.as_ptr(), len)? }
+        Ok(TcpStream { inner })
+    }
+
+    /// Returns the local address of this connection
+    pub fn local_addr(&self) -> Result<SocketAddr, Error> {
+        local_addr(&self.inner)
+    }
+
+    /// Returns the underlying file descriptor
+    pub fn as_raw_fd(&self) -> c_uint {
+        self.inner.as_raw_fd()
+    }
+
+    /// Pull some bytes from this stream into the specified buffer, returning how many bytes were
+    /// read
+    ///
+    /// A return value of `0` means that the peer closed the connection
+    pub fn read(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        self.inner.recv(buf, 0)
+    }
+
+    /// Write a buffer into this stream, returning how many bytes were written
+    pub fn write(&self, buf: &[u8]) -> Result<usize, Error> {
+        self.inner.send(buf, 0)
+    }
+
+    /// Attempts to write an entire buffer into this stream
+    pub fn write_all(&self, buf: &[u8]) -> Result<(), Error> {
+        self.inner.write_all(buf)
+    }
+
+    /// Enables or disables Nagle's algorithm (`TCP_NODELAY`)
+    ///
+    /// When enabled (`true`) small writes are sent right away instead of being coalesced
+    pub fn set_nodelay(&self, nodelay: bool) -> Result<(), Error> {
+        self.inner.set_option(
+            linux_sys::IPPROTO_TCP,
+            linux_sys::TCP_NODELAY,
+            nodelay as c_int,
+        )
+    }
+
+    /// Sets the size of the receive buffer (`SO_RCVBUF`)
+    ///
+    /// NOTE the kernel doubles the requested size to account for its bookkeeping overhead
+    pub fn set_recv_buffer_size(&self, size: usize) -> Result<(), Error> {
+        set_recv_buffer_size(&self.inner, size)
+    }
+
+    /// Sets the priority of the packets sent on this stream (`SO_PRIORITY`)
+    ///
+    /// Priorities above `6` require the `CAP_NET_ADMIN` capability
+    pub fn set_priority(&self, priority: u32) -> Result<(), Error> {
+        set_priority(&self.inner, priority)
+    }
+
+    /// Moves the stream into or out of non-blocking mode
+    ///
+    /// In non-blocking mode `read` and `write` return `EAGAIN` instead of blocking
+    pub fn set_nonblocking(&self, nonblocking: bool) -> Result<(), Error> {
+        self.inner.set_nonblocking(nonblocking)
+    }
+}
+
+impl Read for TcpStream {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        self.inner.recv(buf, 0)
+    }
+}
+
+impl Read for &'_ TcpStream {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        self.inner.recv(buf, 0)
+    }
+}
+
+impl Write for TcpStream {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        self.inner.send(buf, 0)
+    }
+}
+
+impl Write for &'_ TcpStream {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        self.inner.send(buf, 0)
+    }
+}
+
+impl ufmt::uWrite for TcpStream {
+    type Error = Error;
+
+    fn write_str(&mut self, s: &str) -> Result<(), Error> {
+        self.inner.write_all(s.as_bytes())
+    }
+}
+
+impl ufmt::uWrite for &'_ TcpStream {
+    type Error = Error;
+
+    fn write_str(&mut self, s: &str) -> Result<(), Error> {
+        self.inner.write_all(s.as_bytes())
+    }
+}
+
+fn local_addr(socket: &Socket) -> Result<SocketAddr, Error> {
+    let mut raw: RawAddr = unsafe { mem::zeroed() };
+    let mut len = mem::size_of::<RawAddr>() as socklen_t;
+    unsafe { socket.local_addr(raw.as_mut_ptr(), &mut len)? }
+    SocketAddr::from_raw(&raw)
+}
+
+fn set_recv_buffer_size(socket: &Socket, size: usize) -> Result<(), Error> {
+    if size > c_int::max_value() as usize {
+        return Err(Error::EINVAL);
+    }
+
+    socket.set_option(linux_sys::SOL_SOCKET, linux_sys::SO_RCVBUF, size as c_int)
+}
+
+fn set_priority(socket: &Socket, priority: u32) -> Result<(), Error> {
+    if priority > c_int::max_value() as u32 {
+        return Err(Error::EINVAL);
+    }
+
+    socket.set_option(
+        linux_sys::SOL_SOCKET,
+        linux_sys::SO_PRIORITY,
+        priority as c_int,
+    )
+}
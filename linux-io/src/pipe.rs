@@ -0,0 +1,136 @@
+//! Anonymous pipes
+//!
+//! NOTE writing to a pipe whose read end has been closed raises `SIGPIPE`, which terminates the
+//! process unless the signal is ignored or handled; in the latter cases `EPIPE` is returned
+
+use cty::c_uint;
+use linux_sys::Error;
+
+use crate::{Read, Write};
+
+/// Creates an anonymous pipe
+///
+/// Returns the read end and the write end of the pipe. Data written to the write end can be read
+/// from the read end; the read end reports end of file (`Ok(0)`) once the write end is dropped
+pub fn pipe() -> Result<(PipeReader, PipeWriter), Error> {
+    linux_sys::pipe2(linux_sys::O_CLOEXEC)
+        .map(|[reader, writer]| (PipeReader { fd: reader }, PipeWriter { fd: writer }))
+}
+
+/// Read end of a pipe
+///
+/// The file descriptor is closed when this value is dropped
+pub struct PipeReader {
+    fd: c_uint,
+}
+
+impl PipeReader {
+    /// Returns the underlying file descriptor
+    pub fn as_raw_fd(&self) -> c_uint {
+        self.fd
+    }
+
+    /// Pull some bytes from this pipe into the specified buffer, returning how many bytes were
+    /// read
+    ///
+    /// This blocks until some data is available unless the pipe is in non-blocking mode; in that
+    /// case `EAGAIN` is returned when the pipe is empty
+    pub fn read(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        crate::read(self.fd, buf)
+    }
+
+    /// Moves this end of the pipe into or out of non-blocking mode
+    pub fn set_nonblocking(&self, nonblocking: bool) -> Result<(), Error> {
+        crate::set_nonblocking(self.fd, nonblocking)
+    }
+}
+
+impl Drop for PipeReader {
+    fn drop(&mut self) {
+        // NOTE the file descriptor is released even if `close` reports an error
+        unsafe {
+            linux_sys::close(self.fd).ok();
+        }
+    }
+}
+
+impl Read for PipeReader {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        crate::read(self.fd, buf)
+    }
+}
+
+impl Read for &'_ PipeReader {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        crate::read(self.fd, buf)
+    }
+}
+
+/// Write end of a pipe
+///
+/// The file descriptor is closed when this value is dropped
+pub struct PipeWriter {
+    fd: c_uint,
+}
+
+impl PipeWriter {
+    /// Returns the underlying file descriptor
+    pub fn as_raw_fd(&self) -> c_uint {
+        self.fd
+    }
+
+    /// Write a buffer into this pipe, returning how many bytes were written
+    ///
+    /// Writes of up to `PIPE_BUF` (4096) bytes are atomic: their data won't be interleaved with
+    /// the data of other writers
+    pub fn write(&self, buf: &[u8]) -> Result<usize, Error> {
+        crate::write(self.fd, buf)
+    }
+
+    /// Attempts to write an entire buffer into this pipe
+    pub fn write_all(&self, buf: &[u8]) -> Result<(), Error> {
+        crate::write_all(self.fd, buf)
+    }
+
+    /// Moves this end of the pipe into or out of non-blocking mode
+    pub fn set_nonblocking(&self, nonblocking: bool) -> Result<(), Error> {
+        crate::set_nonblocking(self.fd, nonblocking)
+    }
+}
+
+impl Drop for PipeWriter {
+    fn drop(&mut self) {
+        // NOTE the file descriptor is released even if `close` reports an error
+        unsafe {
+            linux_sys::close(self.fd).ok();
+        }
+    }
+}
+
+impl Write for PipeWriter {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        crate::write(self.fd, buf)
+    }
+}
+
+impl Write for &'_ PipeWriter {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        crate::write(self.fd, buf)
+    }
+}
+
+impl ufmt::uWrite for PipeWriter {
+    type Error = Error;
+
+    fn write_str(&mut self, s: &str) -> Result<(), Error> {
+        crate::write_all(self.fd, s.as_bytes())
+    }
+}
+
+impl ufmt::uWrite for &'_ PipeWriter {
+    type Error = Error;
+
+    fn write_str(&mut self, s: &str) -> Result<(), Error> {
+        crate::write_all(self.fd, s.as_bytes())
+    }
+}
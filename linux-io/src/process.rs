@@ -1,2 +1,470 @@
 //! A module for working with processes
-pub use linux_sys::exit_group as exit;
+
+use core::{
+    mem, ptr,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use cty::{c_char, c_int, c_ulong};
+use heapless::{consts, Vec};
+use linux_sys::{pid_t, sigaction, siginfo_t, Error, SigSet};
+use ufmt::{uDisplay, uWrite, uwrite, Formatter};
+
+// maximum number of `at_exit` functions
+const MAX_AT_EXIT: usize = 8;
+
+static AT_EXIT: [AtomicUsize; MAX_AT_EXIT] = [
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+];
+static NEXT: AtomicUsize = AtomicUsize::new(0);
+
+/// Registers a function that will be called before the process terminates
+///
+/// The functions run when `exit` is called and when the `#[entry]` function or the RTFM
+/// application returns, but not when the program panics or aborts. Use this, for example, to flush
+/// a `BufWriter` stored in a `static` variable. The functions run in reverse order of
+/// registration. Returns the function back if there's no space left for it
+/// (at most 8 functions can be registered)
+pub fn at_exit(f: fn()) -> Result<(), fn()> {
+    let i = NEXT.fetch_add(1, Ordering::Relaxed);
+
+    if i < MAX_AT_EXIT {
+        AT_EXIT[i].store(f as usize, Ordering::Release);
+        Ok(())
+    } else {
+        NEXT.store(MAX_AT_EXIT, Ordering::Relaxed);
+        Err(f)
+    }
+}
+
+/// Terminates the current process (i.e. all its threads) with the specified exit code
+///
+/// The functions registered with `at_exit` are called first. Destructors on the stack of this or
+/// any other thread are *not* run
+pub fn exit(code: u8) -> ! {
+    run_at_exit();
+
+    linux_sys::exit_group(code)
+}
+
+pub(crate) fn run_at_exit() {
+    for hook in AT_EXIT.iter().rev() {
+        // NOTE each function runs at most once, even if it calls `exit`
+        let f = hook.swap(0, Ordering::Acquire);
+        if f != 0 {
+            unsafe { mem::transmute::<usize, fn()>(f)() }
+        }
+    }
+}
+
+/// Returns the ID of the calling process
+pub fn getpid() -> pid_t {
+    linux_sys::getpid()
+}
+
+/// Returns the ID of the calling thread
+pub fn gettid() -> pid_t {
+    linux_sys::gettid()
+}
+
+/// Returns the ID of the parent process
+pub fn getppid() -> pid_t {
+    linux_sys::getppid()
+}
+
+/// Terminates the process abnormally with a `SIGABRT` signal
+///
+/// The default disposition of `SIGABRT` is restored and the signal is unblocked first so that it
+/// can't be handled or ignored. The functions registered with `at_exit` are *not* called
+pub fn abort() -> ! {
+    unsafe {
+        // NOTE all-zeros is `SIG_DFL` with no flags and an empty mask
+        let dfl: sigaction = mem::zeroed();
+        linux_sys::rt_sigaction(linux_sys::SIGABRT, &dfl, ptr::null_mut()).ok();
+
+        let mut set = SigSet::empty();
+        set.add(linux_sys::SIGABRT);
+        linux_sys::rt_sigprocmask(linux_sys::SIG_UNBLOCK, &set, ptr::null_mut()).ok();
+
+        linux_sys::tgkill(getpid(), gettid(), linux_sys::SIGABRT).ok();
+    }
+
+    // only reachable if the signal couldn't be sent
+    linux_sys::exit_group(127)
+}
+
+/// Creates a new process by duplicating the calling process
+///
+/// Returns `Some(child)` to the parent and `None` to the child
+///
+/// # Safety
+///
+/// Only the calling thread is duplicated. Locks held by other threads, e.g. inside a `BufWriter`
+/// or a `Mutex`, remain locked forever in the child so the child should restrict itself to
+/// system calls (e.g. `execve`) and then `exit`. Prefer `Command`, which doesn't duplicate the
+/// address space
+pub unsafe fn fork() -> Result<Option<Child>, Error> {
+    linux_sys::fork().map(|pid| if pid == 0 { None } else { Some(Child { pid }) })
+}
+
+// maximum number of arguments, including the program, and environment variables
+type MaxArgs = consts::U16;
+// size of the buffer that holds the null-terminated arguments and environment variables
+const ARG_MAX: usize = 2048;
+// size of the stack the child uses until it calls `execve`
+const SPAWN_STACK_SIZE: usize = 4096;
+
+/// A process builder
+///
+/// The program is executed directly (`execve`): `PATH` is not searched and no shell is involved.
+/// The child starts with an *empty* environment; use `env` to add variables to it. The child
+/// inherits the file descriptors of the parent except for the ones opened by this crate, which
+/// are all close-on-exec
+pub struct Command<'a> {
+    program: &'a [u8],
+    args: Vec<&'a [u8], MaxArgs>,
+    env: Vec<(&'a [u8], &'a [u8]), MaxArgs>,
+    overflow: bool,
+}
+
+impl<'a> Command<'a> {
+    /// Creates a builder for launching the program at path `program`
+    ///
+    /// `program` is also passed to the program as its first argument (`argv[0]`)
+    pub fn new<P>(program: &'a P) -> Self
+    where
+        P: AsRef<[u8]> + ?Sized,
+    {
+        let program = program.as_ref();
+        let mut args = Vec::new();
+        // NOTE can't fail; the vector is empty
+        args.push(program).ok();
+
+        Command {
+            program,
+            args,
+            env: Vec::new(),
+            overflow: false,
+        }
+    }
+
+    /// Adds an argument to pass to the program
+    ///
+    /// At most 15 arguments can be added; `spawn` returns `E2BIG` if more were added
+    pub fn arg<A>(&mut self, arg: &'a A) -> &mut Self
+    where
+        A: AsRef<[u8]> + ?Sized,
+    {
+        if self.args.push(arg.as_ref()).is_err() {
+            self.overflow = true;
+        }
+        self
+    }
+
+    /// Adds an environment variable to the environment of the child
+    ///
+    /// At most 16 variables can be added; `spawn` returns `E2BIG` if more were added
+    pub fn env<K, V>(&mut self, key: &'a K, value: &'a V) -> &mut Self
+    where
+        K: AsRef<[u8]> + ?Sized,
+        V: AsRef<[u8]> + ?Sized,
+    {
+        if self.env.push((key.as_ref(), value.as_ref())).is_err() {
+            self.overflow = true;
+        }
+        self
+    }
+
+    /// Executes the program as a child process, returning a handle to it
+    ///
+    /// The parent is suspended until the child has started executing the program. If the program
+    /// can't be executed the error reported by `execve` (e.g. `ENOENT`) is returned. The program
+    /// starts with no signals blocked, whatever the signal mask of the caller
+    ///
+    /// Returns `EINVAL` if the program, an argument or an environment variable contains a null
+    /// byte and `E2BIG` if they don't fit in the internal buffer (2 KiB)
+    ///
+    /// NOTE this uses about 7 KiB of stack
+    pub fn spawn(&self) -> Result<Child, Error> {
+        if self.overflow {
+            return Err(Error::E2BIG);
+        }
+
+        let mut strings = Strings {
+            buf: [0; ARG_MAX],
+            len: 0,
+        };
+        let mut argv = [ptr::null(); 17];
+        let mut envp = [ptr::null(); 17];
+
+        let path = strings.push(&[self.program])?;
+        for (argv, arg) in argv.iter_mut().zip(self.args.iter()) {
+            *argv = strings.push(&[arg])?;
+        }
+        for (envp, (key, value)) in envp.iter_mut().zip(self.env.iter()) {
+            *envp = strings.push(&[key, b"=", value])?;
+        }
+
+        unsafe {
+            // block all signals so that no signal handler runs in the child while it shares the
+            // address space with the parent
+            let mut mask = SigSet::empty();
+            linux_sys::rt_sigprocmask(linux_sys::SIG_SETMASK, &SigSet::full(), &mut mask)?;
+
+            let mut ctx = Spawn {
+                path,
+                argv: argv.as_ptr(),
+                envp: envp.as_ptr(),
+                error: 0,
+            };
+
+            let mut stack = SpawnStack([0; SPAWN_STACK_SIZE]);
+            let res = linux_sys::x86_64_clone_with_arg(
+                linux_sys::CLONE_VM | linux_sys::CLONE_VFORK | linux_sys::SIGCHLD as c_ulong,
+                stack.0.as_mut_ptr().add(SPAWN_STACK_SIZE) as *mut _,
+                ptr::null_mut(),
+                spawn,
+                &mut ctx as *mut Spawn as usize,
+            );
+
+            linux_sys::rt_sigprocmask(linux_sys::SIG_SETMASK, &mask, ptr::null_mut()).ok();
+
+            let pid = res?;
+            // NOTE the child has either started executing the program or exited at this point
+            let error = ptr::read_volatile(&ctx.error);
+            if error != 0 {
+                // reap the child
+                Child { pid }.wait().ok();
+                Err(Error::from_raw(error))
+            } else {
+                Ok(Child { pid })
+            }
+        }
+    }
+
+    /// Executes the program as a child process, waits for it to finish and returns its exit
+    /// status
+    pub fn status(&self) -> Result<ExitStatus, Error> {
+        self.spawn()?.wait()
+    }
+}
+
+// null-terminated strings
+struct Strings {
+    buf: [u8; ARG_MAX],
+    len: usize,
+}
+
+impl Strings {
+    // appends the concatenation of `parts` plus a null byte
+    fn push(&mut self, parts: &[&[u8]]) -> Result<*const c_char, Error> {
+        let start = self.len;
+        for part in parts {
+            if part.contains(&0) {
+                return Err(Error::EINVAL);
+            }
+
+            let end = self.len + part.len();
+            if end >= ARG_MAX {
+                return Err(Error::E2BIG);
+            }
+
+            self.buf[self.len..end].copy_from_slice(part);
+            self.len = end;
+        }
+
+        // NOTE the buffer is zeroed; this leaves a null byte behind
+        self.len += 1;
+        Ok(self.buf[start..].as_ptr() as *const c_char)
+    }
+}
+
+#[repr(align(16))]
+struct SpawnStack([u8; SPAWN_STACK_SIZE]);
+
+// shared by the parent and the child
+struct Spawn {
+    path: *const c_char,
+    argv: *const *const c_char,
+    envp: *const *const c_char,
+    // `execve` error reported by the child
+    error: u8,
+}
+
+// the child runs this function in the address space of the parent, while the parent is suspended
+extern "C" fn spawn(ctx: usize) -> ! {
+    unsafe {
+        let ctx = &mut *(ctx as *mut Spawn);
+
+        // the child has its own copy of the signal dispositions; reset the handlers so that none
+        // of them runs before `execve` completes
+        let dfl: sigaction = mem::zeroed();
+        for sig in 1..=linux_sys::SIGRTMAX {
+            let mut old: sigaction = mem::zeroed();
+            if linux_sys::rt_sigaction(sig, ptr::null(), &mut old).is_ok() {
+                let handler = *(&old.sa_ as *const _ as *const usize);
+                if handler != linux_sys::SIG_DFL && handler != linux_sys::SIG_IGN {
+                    linux_sys::rt_sigaction(sig, &dfl, ptr::null_mut()).ok();
+                }
+            }
+        }
+
+        // the program starts with no signals blocked, regardless of the mask of the caller (e.g.
+        // the real-time signals blocked by an RTFM task or critical section)
+        linux_sys::rt_sigprocmask(linux_sys::SIG_SETMASK, &SigSet::empty(), ptr::null_mut()).ok();
+
+        let error = linux_sys::execve(ctx.path, ctx.argv, ctx.envp);
+        ptr::write_volatile(&mut ctx.error, error.code());
+        linux_sys::exit_group(127)
+    }
+}
+
+/// A child process
+///
+/// NOTE dropping this handle neither kills nor waits for the child; a child that has exited
+/// remains a "zombie" until it's waited for
+pub struct Child {
+    pid: pid_t,
+}
+
+impl Child {
+    /// Returns the process ID of the child
+    pub fn id(&self) -> pid_t {
+        self.pid
+    }
+
+    /// Forces the child to exit by sending it a `SIGKILL` signal
+    pub fn kill(&self) -> Result<(), Error> {
+        unsafe { linux_sys::kill(self.pid, linux_sys::SIGKILL) }
+    }
+
+    /// Waits for the child to exit and returns its exit status
+    ///
+    /// Returns `ECHILD` if the child has already been waited for
+    pub fn wait(&self) -> Result<ExitStatus, Error> {
+        loop {
+            match linux_sys::wait4(self.pid, 0) {
+                Ok((_, status)) => return Ok(ExitStatus::from_raw(status)),
+                Err(Error::EINTR) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Returns the exit status of the child if it has exited; doesn't block
+    pub fn try_wait(&self) -> Result<Option<ExitStatus>, Error> {
+        linux_sys::waitid(
+            linux_sys::P_PID,
+            self.pid,
+            linux_sys::WEXITED | linux_sys::WNOHANG,
+        )
+        .map(|info| ExitStatus::from_siginfo(&info))
+    }
+}
+
+/// Waits for any child process to exit and returns its process ID and exit status
+///
+/// Returns `ECHILD` if the calling process has no children left to wait for
+pub fn wait() -> Result<(pid_t, ExitStatus), Error> {
+    loop {
+        match linux_sys::wait4(-1, 0) {
+            Ok((pid, status)) => return Ok((pid, ExitStatus::from_raw(status))),
+            Err(Error::EINTR) => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Returns the process ID and exit status of a child process that has exited, if any; doesn't
+/// block
+///
+/// Call this repeatedly after receiving a `SIGCHLD` signal: several exits may be reported by a
+/// single signal
+pub fn try_wait() -> Result<Option<(pid_t, ExitStatus)>, Error> {
+    linux_sys::waitid(linux_sys::P_ALL, 0, linux_sys::WEXITED | linux_sys::WNOHANG).map(|info| {
+        let pid = info.pid().unwrap_or(0);
+        ExitStatus::from_siginfo(&info).map(|status| (pid, status))
+    })
+}
+
+/// The status of a child process that has exited
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub struct ExitStatus {
+    inner: Status,
+}
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum Status {
+    Exited(u8),
+    Signaled(c_int),
+}
+
+impl ExitStatus {
+    // from a `wait4` status
+    fn from_raw(status: c_int) -> Self {
+        let signal = status & 0x7f;
+        ExitStatus {
+            inner: if signal == 0 {
+                Status::Exited((status >> 8) as u8)
+            } else {
+                Status::Signaled(signal)
+            },
+        }
+    }
+
+    // from `waitid` information; `None` if no child has exited
+    fn from_siginfo(info: &siginfo_t) -> Option<Self> {
+        if info.pid().unwrap_or(0) == 0 {
+            return None;
+        }
+
+        let status = info.status()?;
+        Some(ExitStatus {
+            inner: if info.si_code == linux_sys::CLD_EXITED {
+                Status::Exited(status as u8)
+            } else {
+                Status::Signaled(status)
+            },
+        })
+    }
+
+    /// Returns `true` if the process exited with exit code `0`
+    pub fn success(&self) -> bool {
+        self.inner == Status::Exited(0)
+    }
+
+    /// Returns the exit code of the process, if it exited normally
+    pub fn code(&self) -> Option<u8> {
+        match self.inner {
+            Status::Exited(code) => Some(code),
+            Status::Signaled(_) => None,
+        }
+    }
+
+    /// Returns the signal that terminated the process, if it was terminated by a signal
+    pub fn signal(&self) -> Option<c_int> {
+        match self.inner {
+            Status::Exited(_) => None,
+            Status::Signaled(signal) => Some(signal),
+        }
+    }
+}
+
+impl uDisplay for ExitStatus {
+    fn fmt<W>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: uWrite + ?Sized,
+    {
+        match self.inner {
+            Status::Exited(code) => uwrite!(f, "exit code: {}", code),
+            Status::Signaled(signal) => uwrite!(f, "signal: {}", signal),
+        }
+    }
+}
@@ -0,0 +1,180 @@
+//! Socket file descriptors
+
+use core::{mem, ptr};
+
+use cty::{c_int, c_uint, c_void};
+use linux_sys::{iovec, msghdr, sockaddr, socklen_t, Error};
+
+// number of pending connections `listen` allows; same value as in `std`
+const BACKLOG: c_int = 128;
+
+/// An owned socket; closed when dropped
+pub(crate) struct Socket {
+    fd: c_uint,
+}
+
+impl Socket {
+    pub(crate) fn new(family: c_int, ty: c_int) -> Result<Self, Error> {
+        linux_sys::socket(family, ty | linux_sys::SOCK_CLOEXEC, 0).map(|fd| Socket { fd })
+    }
+
+    pub(crate) fn pair(family: c_int, ty: c_int) -> Result<(Self, Self), Error> {
+        linux_sys::socketpair(family, ty | linux_sys::SOCK_CLOEXEC, 0)
+            .map(|[a, b]| (Socket { fd: a }, Socket { fd: b }))
+    }
+
+    pub(crate) fn as_raw_fd(&self) -> c_uint {
+        self.fd
+    }
+
+    /// `addr` must point to a socket address that's `len` bytes long
+    pub(crate) unsafe fn bind(&self, addr: *const sockaddr, len: socklen_t) -> Result<(), Error> {
+        linux_sys::bind(self.fd, addr, len)
+    }
+
+    /// `addr` must point to a socket address that's `len` bytes long
+    pub(crate) unsafe fn connect(
+        &self,
+        addr: *const sockaddr,
+        len: socklen_t,
+    ) -> Result<(), Error> {
+        linux_sys::connect(self.fd, addr, len)
+    }
+
+    pub(crate) fn listen(&self) -> Result<(), Error> {
+        linux_sys::listen(self.fd, BACKLOG)
+    }
+
+    /// If `addr` is not null it must point to a buffer of `*len` bytes; the address of the peer is
+    /// written there
+    pub(crate) unsafe fn accept(
+        &self,
+        addr: *mut sockaddr,
+        len: *mut socklen_t,
+    ) -> Result<Socket, Error> {
+        loop {
+            match linux_sys::accept4(self.fd, addr, len, linux_sys::SOCK_CLOEXEC) {
+                Ok(fd) => return Ok(Socket { fd }),
+                Err(Error::EINTR) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// `addr` must point to a buffer of `*len` bytes
+    pub(crate) unsafe fn local_addr(
+        &self,
+        addr: *mut sockaddr,
+        len: *mut socklen_t,
+    ) -> Result<(), Error> {
+        linux_sys::getsockname(self.fd, addr, len)
+    }
+
+    pub(crate) fn recv(&self, buf: &mut [u8], flags: c_int) -> Result<usize, Error> {
+        unsafe {
+            self.recv_msg(buf, flags, ptr::null_mut(), 0, ptr::null_mut(), 0)
+                .map(|(n, _)| n)
+        }
+    }
+
+    /// If `addr` (`control`) is not null it must point to a buffer of `addrlen` (`controllen`)
+    /// bytes; the address of the sender (ancillary data) is written there
+    ///
+    /// Returns the number of bytes received and the message header as updated by the kernel
+    pub(crate) unsafe fn recv_msg(
+        &self,
+        buf: &mut [u8],
+        flags: c_int,
+        addr: *mut sockaddr,
+        addrlen: socklen_t,
+        control: *mut c_void,
+        controllen: usize,
+    ) -> Result<(usize, msghdr), Error> {
+        let mut iov = iovec {
+            iov_base: buf.as_mut_ptr() as *mut c_void,
+            iov_len: buf.len(),
+        };
+        let mut msg = msghdr(&mut iov, addr as *mut c_void, addrlen);
+        msg.msg_control = control;
+        msg.msg_controllen = controllen;
+        linux_sys::recvmsg(self.fd, &mut msg, flags).map(|n| (n, msg))
+    }
+
+    pub(crate) fn send(&self, buf: &[u8], flags: c_int) -> Result<usize, Error> {
+        unsafe { self.send_to(buf, flags, ptr::null(), 0) }
+    }
+
+    /// If `addr` is not null it must point to a socket address that's `len` bytes long
+    ///
+    /// `SIGPIPE` is never raised; writing to a socket whose peer has been closed returns `EPIPE`
+    pub(crate) unsafe fn send_to(
+        &self,
+        buf: &[u8],
+        flags: c_int,
+        addr: *const sockaddr,
+        len: socklen_t,
+    ) -> Result<usize, Error> {
+        let mut iov = iovec {
+            iov_base: buf.as_ptr() as *mut c_void,
+            iov_len: buf.len(),
+        };
+        let msg = msghdr(&mut iov, addr as *mut c_void, len);
+        linux_sys::sendmsg(self.fd, &msg, flags | linux_sys::MSG_NOSIGNAL)
+    }
+
+    pub(crate) fn write_all(&self, buf: &[u8]) -> Result<(), Error> {
+        crate::write_all_with(buf, |buf| self.send(buf, 0))
+    }
+
+    /// Sets an `int` socket option
+    pub(crate) fn set_option(&self, level: c_int, name: c_int, value: c_int) -> Result<(), Error> {
+        unsafe {
+            linux_sys::setsockopt(
+                self.fd,
+                level,
+                name,
+                &value as *const c_int as *const c_void,
+                mem::size_of::<c_int>() as socklen_t,
+            )
+        }
+    }
+
+    /// Gets an `int` socket option
+    pub(crate) fn option(&self, level: c_int, name: c_int) -> Result<c_int, Error> {
+        let mut value: c_int = 0;
+        let mut len = mem::size_of::<c_int>() as socklen_t;
+        unsafe {
+            linux_sys::getsockopt(
+                self.fd,
+                level,
+                name,
+                &mut value as *mut c_int as *mut c_void,
+                &mut len,
+            )?;
+        }
+        Ok(value)
+    }
+
+    pub(crate) fn set_nonblocking(&self, nonblocking: bool) -> Result<(), Error> {
+        crate::set_nonblocking(self.fd, nonblocking)
+    }
+}
+
+impl Drop for Socket {
+    fn drop(&mut self) {
+        // NOTE the file descriptor is released even if `close` reports an error
+        unsafe {
+            linux_sys::close(self.fd).ok();
+        }
+    }
+}
+
+// A message header with a single data buffer and no ancillary data
+fn msghdr(iov: &mut iovec, name: *mut c_void, namelen: socklen_t) -> msghdr {
+    let mut msg: msghdr = unsafe { mem::zeroed() };
+    msg.msg_name = name;
+    msg.msg_namelen = namelen;
+    msg.msg_iov = iov;
+    msg.msg_iovlen = 1;
+    msg
+}
@@ -2,85 +2,141 @@
 
 use core::{convert::TryFrom, hint, ops, time::Duration};
 
-use linux_sys::timespec;
+use linux_sys::{clockid_t, timespec};
 
-/// A measurement of a monotonically nondecreasing clock. Opaque and useful only with `Duration`
+const NANOS_IN_ONE_SEC: i64 = 1_000_000_000;
+
+// Defines a measurement of the monotonic clock `$clock`
+macro_rules! instant {
+    ($(#[$attr:meta])* $name:ident, $clock:expr) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, Eq, Ord, PartialEq, PartialOrd)]
+        pub struct $name {
+            ts: timespec,
+        }
+
+        impl $name {
+            /// Returns an instant corresponding to "now".
+            pub fn now() -> Self {
+                Self { ts: now($clock) }
+            }
+
+            /// Returns `Some(t)` where t is the time `self + duration` if t can be represented as
+            /// an instant (which means it's inside the bounds of the underlying data structure),
+            /// `None` otherwise.
+            pub fn checked_add(&self, dur: Duration) -> Option<Self> {
+                checked_add(self.ts, dur).map(|ts| Self { ts })
+            }
+
+            /// Returns the amount of time elapsed from another instant to this one, or `None` if
+            /// that instant is earlier than this one.
+            pub fn checked_duration_since(&self, earlier: Self) -> Option<Duration> {
+                checked_duration_since(self.ts, earlier.ts)
+            }
+
+            /// Returns the amount of time elapsed from another instant to this one, or zero
+            /// duration if that instant is earlier than this one.
+            pub fn saturating_duration_since(&self, earlier: Self) -> Duration {
+                self.checked_duration_since(earlier)
+                    .unwrap_or(Duration::new(0, 0))
+            }
+        }
+
+        impl ops::Add<Duration> for $name {
+            type Output = Self;
+
+            fn add(self, dur: Duration) -> Self {
+                self.checked_add(dur).unwrap()
+            }
+        }
+
+        impl From<$name> for timespec {
+            fn from(i: $name) -> timespec {
+                i.ts
+            }
+        }
+    };
+}
+
+instant!(
+    /// A measurement of a monotonically nondecreasing clock. Opaque and useful only with `Duration`
+    Instant,
+    linux_sys::CLOCK_MONOTONIC
+);
+
+instant!(
+    /// Like `Instant` but this clock also counts the time the system spends suspended
+    /// (`CLOCK_BOOTTIME`)
+    BootInstant,
+    linux_sys::CLOCK_BOOTTIME
+);
+
+instant!(
+    /// A measurement of International Atomic Time (`CLOCK_TAI`)
+    ///
+    /// Unlike `SystemTime` this clock doesn't repeat nor skip seconds around leap seconds. NOTE
+    /// it's derived from `CLOCK_REALTIME` so it does jump when the system time is set
+    TaiInstant,
+    linux_sys::CLOCK_TAI
+);
+
+/// A measurement of the system (wall-clock) time
+///
+/// Unlike `Instant` this clock is not monotonic: it jumps when the system time is set, e.g. by NTP
+/// or by an administrator
 #[derive(Clone, Copy, Eq, Ord, PartialEq, PartialOrd)]
-pub struct Instant {
+pub struct SystemTime {
     ts: timespec,
 }
 
-impl Instant {
-    /// Returns an instant corresponding to "now".
+/// 1970-01-01 00:00:00 UTC
+pub const UNIX_EPOCH: SystemTime = SystemTime {
+    ts: timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    },
+};
+
+impl SystemTime {
+    /// An anchor in time: 1970-01-01 00:00:00 UTC
+    pub const UNIX_EPOCH: SystemTime = UNIX_EPOCH;
+
+    /// Returns the system time corresponding to "now"
     pub fn now() -> Self {
         Self {
-            ts: linux_sys::clock_gettime(linux_sys::CLOCK_MONOTONIC).unwrap_or_else(|_| unsafe {
-                if cfg!(debug_assertions) {
-                    panic!()
-                } else {
-                    hint::unreachable_unchecked()
-                }
-            }),
+            ts: now(linux_sys::CLOCK_REALTIME),
         }
     }
 
-    /// Returns `Some(t)` where t is the time `self + duration` if t can be represented as `Instant`
-    /// (which means it's inside the bounds of the underlying data structure), `None` otherwise.
-    pub fn checked_add(&self, dur: Duration) -> Option<Instant> {
-        const NANOS_IN_ONE_SEC: i64 = 1_000_000_000;
-
-        let mut secs = self
-            .ts
-            .tv_sec
-            .checked_add(i64::try_from(dur.as_secs()).ok()?)?;
-        let mut nanos = self.ts.tv_nsec.wrapping_add(i64::from(dur.subsec_nanos()));
-
-        if nanos > NANOS_IN_ONE_SEC {
-            nanos -= NANOS_IN_ONE_SEC;
-            secs = secs.checked_add(1)?;
-        }
+    /// Returns the amount of time elapsed from an earlier point in time
+    ///
+    /// Returns an error, which contains the difference, if `earlier` is later than `self`
+    pub fn duration_since(&self, earlier: SystemTime) -> Result<Duration, SystemTimeError> {
+        checked_duration_since(self.ts, earlier.ts)
+            .ok_or_else(|| SystemTimeError(checked_duration_since(earlier.ts, self.ts).unwrap()))
+    }
 
-        Some(Instant {
-            ts: timespec {
-                tv_sec: secs,
-                tv_nsec: nanos,
-            },
-        })
+    /// Returns the amount of time elapsed since this system time was created
+    ///
+    /// This fails if the system time has been set backwards in the meantime
+    pub fn elapsed(&self) -> Result<Duration, SystemTimeError> {
+        SystemTime::now().duration_since(*self)
     }
 
-    /// Returns the amount of time elapsed from another instant to this one, or `None` if that
-    /// instant is earlier than this one.
-    pub fn checked_duration_since(&self, earlier: Self) -> Option<Duration> {
-        if self < &earlier {
-            None
-        } else {
-            let (sec, nsec) = if self.ts.tv_nsec >= earlier.ts.tv_nsec {
-                (
-                    self.ts.tv_sec - earlier.ts.tv_sec,
-                    self.ts.tv_nsec - earlier.ts.tv_nsec,
-                )
-            } else {
-                (
-                    self.ts.tv_sec - 1 - earlier.ts.tv_sec,
-                    self.ts.tv_nsec + 1_000_000_000 - earlier.ts.tv_nsec,
-                )
-            };
-
-            // NOTE `nsec` is always less than `1_000_000_000`
-            // NOTE `sec` is always positive
-            Some(Duration::new(sec as u64, nsec as u32))
-        }
+    /// Returns `Some(t)` where t is the time `self + duration` if t can be represented as
+    /// `SystemTime`, `None` otherwise
+    pub fn checked_add(&self, dur: Duration) -> Option<SystemTime> {
+        checked_add(self.ts, dur).map(|ts| SystemTime { ts })
     }
 
-    /// Returns the amount of time elapsed from another instant to this one, or zero duration if
-    /// that instant is earlier than this one.
-    pub fn saturating_duration_since(&self, earlier: Self) -> Duration {
-        self.checked_duration_since(earlier)
-            .unwrap_or(Duration::new(0, 0))
+    /// Returns `Some(t)` where t is the time `self - duration` if t can be represented as
+    /// `SystemTime`, `None` otherwise
+    pub fn checked_sub(&self, dur: Duration) -> Option<SystemTime> {
+        checked_sub(self.ts, dur).map(|ts| SystemTime { ts })
     }
 }
 
-impl ops::Add<Duration> for Instant {
+impl ops::Add<Duration> for SystemTime {
     type Output = Self;
 
     fn add(self, dur: Duration) -> Self {
@@ -88,8 +144,87 @@ impl ops::Add<Duration> for Instant {
     }
 }
 
-impl From<Instant> for timespec {
-    fn from(i: Instant) -> timespec {
-        i.ts
+impl ops::Sub<Duration> for SystemTime {
+    type Output = Self;
+
+    fn sub(self, dur: Duration) -> Self {
+        self.checked_sub(dur).unwrap()
+    }
+}
+
+impl From<SystemTime> for timespec {
+    fn from(t: SystemTime) -> timespec {
+        t.ts
+    }
+}
+
+/// Error returned from `SystemTime::duration_since` and `SystemTime::elapsed` when the second
+/// system time is later than the first one
+#[derive(Clone, Copy, Debug)]
+pub struct SystemTimeError(Duration);
+
+impl SystemTimeError {
+    /// Returns how far the second system time is ahead of the first one
+    pub fn duration(&self) -> Duration {
+        self.0
+    }
+}
+
+fn now(clock: clockid_t) -> timespec {
+    linux_sys::clock_gettime(clock).unwrap_or_else(|_| unsafe {
+        if cfg!(debug_assertions) {
+            panic!()
+        } else {
+            hint::unreachable_unchecked()
+        }
+    })
+}
+
+fn checked_add(ts: timespec, dur: Duration) -> Option<timespec> {
+    let mut secs = ts.tv_sec.checked_add(i64::try_from(dur.as_secs()).ok()?)?;
+    let mut nanos = ts.tv_nsec + i64::from(dur.subsec_nanos());
+
+    if nanos >= NANOS_IN_ONE_SEC {
+        nanos -= NANOS_IN_ONE_SEC;
+        secs = secs.checked_add(1)?;
+    }
+
+    Some(timespec {
+        tv_sec: secs,
+        tv_nsec: nanos,
+    })
+}
+
+fn checked_sub(ts: timespec, dur: Duration) -> Option<timespec> {
+    let mut secs = ts.tv_sec.checked_sub(i64::try_from(dur.as_secs()).ok()?)?;
+    let mut nanos = ts.tv_nsec - i64::from(dur.subsec_nanos());
+
+    if nanos < 0 {
+        nanos += NANOS_IN_ONE_SEC;
+        secs = secs.checked_sub(1)?;
+    }
+
+    Some(timespec {
+        tv_sec: secs,
+        tv_nsec: nanos,
+    })
+}
+
+fn checked_duration_since(ts: timespec, earlier: timespec) -> Option<Duration> {
+    if ts < earlier {
+        None
+    } else {
+        let (sec, nsec) = if ts.tv_nsec >= earlier.tv_nsec {
+            (ts.tv_sec - earlier.tv_sec, ts.tv_nsec - earlier.tv_nsec)
+        } else {
+            (
+                ts.tv_sec - 1 - earlier.tv_sec,
+                ts.tv_nsec + NANOS_IN_ONE_SEC - earlier.tv_nsec,
+            )
+        };
+
+        // NOTE `nsec` is always less than `1_000_000_000`
+        // NOTE `sec` is always positive
+        Some(Duration::new(sec as u64, nsec as u32))
     }
 }
@@ -0,0 +1,318 @@
+//! Unix domain sockets
+//!
+//! Socket addresses are byte strings: either a filesystem path, which may or may not end with a
+//! null byte, or a name in the abstract namespace, which starts with a null byte (e.g.
+//! `b"\0supervisor"`). Names in the abstract namespace don't create files and go away when the
+//! last socket bound to them is closed
+//!
+//! Writing to a socket whose peer has been closed returns `EPIPE`; `SIGPIPE` is never raised
+
+use core::{mem, ptr};
+
+use cty::{c_char, c_uint};
+use heapless::{consts, Vec};
+use linux_sys::{sa_family_t, sockaddr, sockaddr_un, socklen_t, Error};
+
+use crate::{socket::Socket, Read, Write};
+
+/// A Unix socket server, listening for connections
+///
+/// The socket is closed when this value is dropped. NOTE dropping the listener doesn't remove the
+/// socket file from the filesystem; use `fs::remove_file` for that
+pub struct UnixListener {
+    inner: Socket,
+}
+
+impl UnixListener {
+    /// Creates a new `UnixListener` bound to the specified address
+    pub fn bind<P>(path: P) -> Result<UnixListener, Error>
+    where
+        P: AsRef<[u8]>,
+    {
+        let (addr, len) = sockaddr_un(path.as_ref())?;
+        let inner = Socket::new(linux_sys::AF_UNIX, linux_sys::SOCK_STREAM)?;
+        unsafe { inner.bind(&addr as *const sockaddr_un as *const sockaddr, len)? }
+        inner.listen()?;
+        Ok(UnixListener { inner })
+    }
+
+    /// Accepts a new incoming connection to this listener
+    ///
+    /// This blocks until a client connects unless the listener is in non-blocking mode; in that
+    /// case `EAGAIN` is returned when there are no pending connections
+    pub fn accept(&self) -> Result<UnixStream, Error> {
+        unsafe {
+            self.inner
+                .accept(ptr::null_mut(), ptr::null_mut())
+                .map(|inner| UnixStream { inner })
+        }
+    }
+
+    /// Returns the underlying file descriptor
+    pub fn as_raw_fd(&self) -> c_uint {
+        self.inner.as_raw_fd()
+    }
+
+    /// Moves the listener into or out of non-blocking mode
+    pub fn set_nonblocking(&self, nonblocking: bool) -> Result<(), Error> {
+        self.inner.set_nonblocking(nonblocking)
+    }
+}
+
+/// A Unix stream socket
+///
+/// The socket is closed when this value is dropped
+pub struct UnixStream {
+    inner: Socket,
+}
+
+impl UnixStream {
+    /// Connects to the socket named by `path`
+    pub fn connect<P>(path: P) -> Result<UnixStream, Error>
+    where
+        P: AsRef<[u8]>,
+    {
+        let (addr, len) = sockaddr_un(path.as_ref())?;
+        let inner = Socket::new(linux_sys::AF_UNIX, linux_sys::SOCK_STREAM)?;
+        unsafe { inner.connect(&addr as *const sockaddr_un as *const sockaddr, len)? }
+        Ok(UnixStream { inner })
+    }
+
+    /// Creates an unnamed pair of connected sockets
+    pub fn pair() -> Result<(UnixStream, UnixStream), Error> {
+        Socket::pair(linux_sys::AF_UNIX, linux_sys::SOCK_STREAM)
+            .map(|(a, b)| (UnixStream { inner: a }, UnixStream { inner: b }))
+    }
+
+    /// Returns the underlying file descriptor
+    pub fn as_raw_fd(&self) -> c_uint {
+        self.inner.as_raw_fd()
+    }
+
+    /// Pull some bytes from this socket into the specified buffer, returning how many bytes were
+    /// read
+    ///
+    /// A return value of `0` means that the peer closed the connection
+    pub fn read(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        self.inner.recv(buf, 0)
+    }
+
+    /// Write a buffer into this socket, returning how many bytes were written
+    pub fn write(&self, buf: &[u8]) -> Result<usize, Error> {
+        self.inner.send(buf, 0)
+    }
+
+    /// Attempts to write an entire buffer into this socket
+    pub fn write_all(&self, buf: &[u8]) -> Result<(), Error> {
+        self.inner.write_all(buf)
+    }
+
+    /// Moves the socket into or out of non-blocking mode
+    ///
+    /// In non-blocking mode `read` and `write` return `EAGAIN` instead of blocking
+    pub fn set_nonblocking(&self, nonblocking: bool) -> Result<(), Error> {
+        self.inner.set_nonblocking(nonblocking)
+    }
+}
+
+impl Read for UnixStream {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        self.inner.recv(buf, 0)
+    }
+}
+
+impl Read for &'_ UnixStream {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        self.inner.recv(buf, 0)
+    }
+}
+
+impl Write for UnixStream {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        self.inner.send(buf, 0)
+    }
+}
+
+impl Write for &'_ UnixStream {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        self.inner.send(buf, 0)
+    }
+}
+
+impl ufmt::uWrite for UnixStream {
+    type Error = Error;
+
+    fn write_str(&mut self, s: &str) -> Result<(), Error> {
+        self.inner.write_all(s.as_bytes())
+    }
+}
+
+impl ufmt::uWrite for &'_ UnixStream {
+    type Error = Error;
+
+    fn write_str(&mut self, s: &str) -> Result<(), Error> {
+        self.inner.write_all(s.as_bytes())
+    }
+}
+
+/// A Unix datagram socket
+///
+/// Message boundaries are preserved: each `recv` returns (at most) one message. The socket is
+/// closed when this value is dropped
+pub struct UnixDatagram {
+    inner: Socket,
+}
+
+impl UnixDatagram {
+    /// Creates a socket bound to the specified address
+    pub fn bind<P>(path: P) -> Result<UnixDatagram, Error>
+    where
+        P: AsRef<[u8]>,
+    {
+        let (addr, len) = sockaddr_un(path.as_ref())?;
+        let inner = Socket::new(linux_sys::AF_UNIX, linux_sys::SOCK_DGRAM)?;
+        unsafe { inner.bind(&addr as *const sockaddr_un as *const sockaddr, len)? }
+        Ok(UnixDatagram { inner })
+    }
+
+    /// Creates a socket which is not bound to any address
+    pub fn unbound() -> Result<UnixDatagram, Error> {
+        Socket::new(linux_sys::AF_UNIX, linux_sys::SOCK_DGRAM).map(|inner| UnixDatagram { inner })
+    }
+
+    /// Creates an unnamed pair of connected sockets
+    pub fn pair() -> Result<(UnixDatagram, UnixDatagram), Error> {
+        Socket::pair(linux_sys::AF_UNIX, linux_sys::SOCK_DGRAM)
+            .map(|(a, b)| (UnixDatagram { inner: a }, UnixDatagram { inner: b }))
+    }
+
+    /// Connects the socket to the specified address
+    ///
+    /// `send` sends messages to this address and `recv` only receives messages from it
+    pub fn connect<P>(&self, path: P) -> Result<(), Error>
+    where
+        P: AsRef<[u8]>,
+    {
+        let (addr, len) = sockaddr_un(path.as_ref())?;
+        unsafe {
+            self.inner
+                .connect(&addr as *const sockaddr_un as *const sockaddr, len)
+        }
+    }
+
+    /// Returns the underlying file descriptor
+    pub fn as_raw_fd(&self) -> c_uint {
+        self.inner.as_raw_fd()
+    }
+
+    /// Receives a message from the socket, returning its size
+    ///
+    /// If the message doesn't fit in `buf` the excess bytes are discarded
+    pub fn recv(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        self.inner.recv(buf, 0)
+    }
+
+    /// Receives a message from the socket, returning its size and the address of the sender
+    ///
+    /// The address has the format described in the module documentation; it's empty if the sender
+    /// is not bound to an address (e.g. an `unbound` socket). If the message doesn't fit in `buf`
+    /// the excess bytes are discarded
+    pub fn recv_from(&self, buf: &mut [u8]) -> Result<(usize, Vec<u8, consts::U108>), Error> {
+        let mut addr: sockaddr_un = unsafe { mem::zeroed() };
+        let (n, msg) = unsafe {
+            self.inner.recv_msg(
+                buf,
+                0,
+                &mut addr as *mut sockaddr_un as *mut sockaddr,
+                mem::size_of::<sockaddr_un>() as socklen_t,
+                ptr::null_mut(),
+                0,
+            )?
+        };
+
+        Ok((n, path(&addr, msg.msg_namelen)))
+    }
+
+    /// Sends a message to the address the socket is connected to
+    pub fn send(&self, buf: &[u8]) -> Result<usize, Error> {
+        self.inner.send(buf, 0)
+    }
+
+    /// Sends a message to the specified address
+    pub fn send_to<P>(&self, buf: &[u8], path: P) -> Result<usize, Error>
+    where
+        P: AsRef<[u8]>,
+    {
+        let (addr, len) = sockaddr_un(path.as_ref())?;
+        unsafe {
+            self.inner
+                .send_to(buf, 0, &addr as *const sockaddr_un as *const sockaddr, len)
+        }
+    }
+
+    /// Moves the socket into or out of non-blocking mode
+    ///
+    /// In non-blocking mode `recv` and `send` return `EAGAIN` instead of blocking
+    pub fn set_nonblocking(&self, nonblocking: bool) -> Result<(), Error> {
+        self.inner.set_nonblocking(nonblocking)
+    }
+}
+
+// Converts `path` into a socket address
+fn sockaddr_un(path: &[u8]) -> Result<(sockaddr_un, socklen_t), Error> {
+    let mut addr = sockaddr_un {
+        sun_family: linux_sys::AF_UNIX as sa_family_t,
+        sun_path: [0; 108],
+    };
+
+    let (path, terminator) = if path.first() == Some(&0) {
+        // abstract namespace: the name is not null-terminated and may contain null bytes
+        (path, 0)
+    } else {
+        let path = match path.split_last() {
+            Some((0, path)) => path,
+            _ => path,
+        };
+
+        if path.is_empty() || path.contains(&0) {
+            return Err(Error::EINVAL);
+        }
+
+        (path, 1)
+    };
+
+    if path.len() + terminator > addr.sun_path.len() {
+        return Err(Error::ENAMETOOLONG);
+    }
+
+    for (to, from) in addr.sun_path.iter_mut().zip(path) {
+        *to = *from as c_char;
+    }
+
+    let len = mem::size_of::<sa_family_t>() + path.len() + terminator;
+    Ok((addr, len as socklen_t))
+}
+
+// Converts a socket address of `len` bytes, as returned by the kernel, into a path
+fn path(addr: &sockaddr_un, len: socklen_t) -> Vec<u8, consts::U108> {
+    let len = (len as usize)
+        .saturating_sub(mem::size_of::<sa_family_t>())
+        .min(addr.sun_path.len());
+    let path = &addr.sun_path[..len];
+
+    let path = if path.first() == Some(&0) {
+        // abstract namespace: every byte is part of the name
+        path
+    } else {
+        // filesystem path: the kernel may include the null terminator in the length
+        let end = path.iter().position(|b| *b == 0).unwrap_or(len);
+        &path[..end]
+    };
+
+    let mut v = Vec::new();
+    for b in path {
+        // NOTE `path` is at most 108 bytes long so this can't fail
+        v.push(*b as u8).ok();
+    }
+    v
+}
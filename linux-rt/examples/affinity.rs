@@ -5,15 +5,11 @@
 #![no_main]
 #![no_std]
 
-use linux_io::Stdout;
+use linux_io::println;
 use panic_stderr as _;
-use ufmt::uwriteln;
-use ufmt_utils::{consts, Ignore, LineBuffered};
 
 #[linux_rt::entry]
 fn main() {
-    let mut stdout = LineBuffered::<_, consts::U100>::new(Ignore::new(Stdout));
-
     // schedule this thread on the first core
     unsafe {
         linux_sys::sched_setaffinity(0, &[1, 0, 0, 0, 0, 0, 0, 0]).unwrap_or_else(|_| panic!());
@@ -22,5 +18,5 @@ fn main() {
     let mut cpu = 0;
     linux_sys::getcpu(Some(&mut cpu), None);
 
-    uwriteln!(&mut stdout, "cpu={}", cpu).ok();
+    println!("cpu={}", cpu);
 }
@@ -0,0 +1,31 @@
+//! Prints the command line arguments, the `HOME` environment variable and the page size
+
+#![deny(rust_2018_compatibility)]
+#![deny(rust_2018_idioms)]
+#![deny(warnings)]
+#![feature(proc_macro_hygiene)]
+#![no_main]
+#![no_std]
+
+use linux_io::{println, Stdout};
+use linux_sys::AT_PAGESZ;
+use panic_stderr as _;
+
+#[linux_rt::entry]
+fn main() {
+    println!("argc={}", linux_rt::args().len());
+    for arg in linux_rt::args() {
+        Stdout.write(arg).ok();
+        Stdout.write(b"\n").ok();
+    }
+
+    if let Some(home) = linux_rt::env::var("HOME") {
+        Stdout.write(b"HOME=").ok();
+        Stdout.write(home).ok();
+        Stdout.write(b"\n").ok();
+    }
+
+    if let Some(size) = linux_rt::auxv::get(AT_PAGESZ) {
+        println!("page size: {}", size);
+    }
+}
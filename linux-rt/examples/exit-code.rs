@@ -0,0 +1,23 @@
+//! Reports an error from `main`: prints `Error: EINVAL` and exits with code 1
+//!
+//! `exit_group` makes returning from `main` terminate all the threads of the process rather than
+//! only the main one
+
+#![deny(rust_2018_compatibility)]
+#![deny(rust_2018_idioms)]
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+use linux_sys::{Error, CLOCK_MONOTONIC};
+use panic_stderr as _;
+
+#[linux_rt::entry(exit_group)]
+fn main() -> Result<(), Error> {
+    linux_sys::clock_gettime(CLOCK_MONOTONIC)?;
+
+    // there's no clock with this ID
+    linux_sys::clock_gettime(-1)?;
+
+    Ok(())
+}
@@ -5,11 +5,9 @@
 #![no_main]
 #![no_std]
 
-use linux_io::Stdout;
+use linux_io::println;
 use linux_sys::timespec;
 use panic_stderr as _;
-use ufmt::uwriteln;
-use ufmt_utils::{consts, Ignore, LineBuffered};
 
 #[linux_rt::entry]
 fn main() {
@@ -25,8 +23,6 @@ fn main() {
         // )
         // .unwrap_or_else(|_| panic!());
 
-        let mut stdout = LineBuffered::<_, consts::U100>::new(Ignore::new(Stdout));
-
         // samples = 1,024
         // quartiles(SCHED_NORMAL) = [1,694; 16,449; 16,636]
         // quartiles(SCHED_FIFO) = [361; 373; 16,534]
@@ -52,7 +48,7 @@ fn main() {
             }
         }
 
-        uwriteln!(&mut stdout, "{} {}", min, max).ok();
+        println!("{} {}", min, max);
     }
 }
 
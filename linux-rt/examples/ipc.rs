@@ -0,0 +1,81 @@
+//! Exchanges messages over a pipe and over Unix domain sockets
+
+#![deny(rust_2018_compatibility)]
+#![deny(rust_2018_idioms)]
+#![deny(warnings)]
+#![feature(proc_macro_hygiene)]
+#![no_main]
+#![no_std]
+
+use linux_io::{
+    pipe, println,
+    unix::{UnixDatagram, UnixListener, UnixStream},
+};
+use linux_sys::Error;
+use panic_stderr as _;
+use ufmt::uwrite;
+
+// names in the abstract namespace; no file is created
+const SUPERVISOR: &[u8] = b"\0linux-rt-ipc-example";
+const MONITOR: &[u8] = b"\0linux-rt-ipc-example-monitor";
+const WORKER: &[u8] = b"\0linux-rt-ipc-example-worker";
+
+#[linux_rt::entry]
+fn main() -> Result<(), Error> {
+    let mut buf = [0; 64];
+
+    // pipe
+    let (reader, writer) = pipe::pipe()?;
+    writer.write_all(b"through the pipe")?;
+    drop(writer);
+    let n = reader.read(&mut buf)?;
+    let eof = reader.read(&mut buf)?;
+    println!("pipe: {} bytes; then {} bytes (EOF)", n, eof);
+
+    // connected stream sockets
+    let listener = UnixListener::bind(SUPERVISOR)?;
+    let mut client = UnixStream::connect(SUPERVISOR)?;
+    let server = listener.accept()?;
+
+    uwrite!(&mut client, "status={}", 0)?;
+    let n = server.read(&mut buf)?;
+    println!("stream: {} bytes", n);
+    server.write_all(b"ack")?;
+    let n = client.read(&mut buf)?;
+    println!("stream: {} bytes", n);
+
+    drop(server);
+    if client.write(b"anyone?") == Err(Error::EPIPE) {
+        println!("stream: peer closed");
+    }
+
+    // datagram sockets
+    let (a, b) = UnixDatagram::pair()?;
+    a.send(b"one")?;
+    a.send(b"two")?;
+    let n = b.recv(&mut buf)?;
+    let m = b.recv(&mut buf)?;
+    println!("datagram: {} bytes, {} bytes", n, m);
+
+    b.set_nonblocking(true)?;
+    if b.recv(&mut buf) == Err(Error::EAGAIN) {
+        println!("datagram: no more messages");
+    }
+
+    // the monitor replies to whoever sent the message
+    let monitor = UnixDatagram::bind(MONITOR)?;
+    let worker = UnixDatagram::bind(WORKER)?;
+    worker.send_to(b"heartbeat", MONITOR)?;
+    let (n, from) = monitor.recv_from(&mut buf)?;
+    println!("datagram: {} bytes from the worker? {}", n, &from[..] == WORKER);
+    monitor.send_to(b"ack", &from[..])?;
+    let (n, from) = worker.recv_from(&mut buf)?;
+    println!("datagram: {} bytes from the monitor? {}", n, &from[..] == MONITOR);
+
+    let anonymous = UnixDatagram::unbound()?;
+    anonymous.send_to(b"?", MONITOR)?;
+    let (n, from) = monitor.recv_from(&mut buf)?;
+    println!("datagram: {} bytes from an unnamed socket? {}", n, from.is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+//! Splits a sum across two threads and joins them
+
+#![deny(rust_2018_compatibility)]
+#![deny(rust_2018_idioms)]
+#![deny(warnings)]
+#![feature(proc_macro_hygiene)]
+#![no_main]
+#![no_std]
+
+use linux_io::println;
+use linux_rt::thread::{self, Builder};
+use panic_stderr as _;
+
+const STACK_SIZE: usize = 16 * 1024;
+
+#[linux_rt::entry]
+fn main() -> Result<(), linux_sys::Error> {
+    let low = thread::spawn(STACK_SIZE, || (0..500u32).sum::<u32>())?;
+    // pinned to the first core
+    let high = Builder::new(STACK_SIZE)
+        .affinity([1, 0, 0, 0, 0, 0, 0, 0])
+        .spawn(|| (500..1_000u32).sum::<u32>())?;
+
+    println!(
+        "threads {} and {} spawned from {}",
+        low.tid(),
+        high.tid(),
+        linux_sys::gettid()
+    );
+
+    let sum = low.join() + high.join();
+    println!("sum = {}", sum);
+
+    Ok(())
+}
@@ -10,7 +10,7 @@
 use core::ptr;
 
 use linux_io::Stderr;
-use linux_sys::{cty::c_void, sigaction, sighandler_t, siginfo_t, SIGRTMIN};
+use linux_sys::{cty::c_void, sigaction, sighandler_t, siginfo_t, SigSet, SIGRTMIN};
 use panic_stderr as _;
 
 #[linux_rt::entry]
@@ -30,31 +30,23 @@ fn main() {
                 sa_: sighandler_t { sigaction },
                 sa_flags: linux_sys::SA_RESTORER | linux_sys::SA_SIGINFO,
                 sa_restorer: Some(__restorer),
-                sa_mask: 0,
+                sa_mask: SigSet::empty(),
             },
             ptr::null_mut(),
         )
         .unwrap_or_else(|_| panic!());
 
         // block the first real-time signal
-        linux_sys::rt_sigprocmask(
-            linux_sys::SIG_BLOCK,
-            &(1 << (SIGRTMIN - 1)),
-            ptr::null_mut(),
-        )
-        .unwrap_or_else(|_| panic!());
+        linux_sys::rt_sigprocmask(linux_sys::SIG_BLOCK, &SigSet::rt(0..1), ptr::null_mut())
+            .unwrap_or_else(|_| panic!());
 
         // raise the first real-time signal *twice*
         linux_sys::kill(0, SIGRTMIN).unwrap_or_else(|_| panic!());
         linux_sys::kill(0, SIGRTMIN).unwrap_or_else(|_| panic!());
 
         // unblock the first real-time signal
-        linux_sys::rt_sigprocmask(
-            linux_sys::SIG_UNBLOCK,
-            &(1 << (SIGRTMIN - 1)),
-            ptr::null_mut(),
-        )
-        .unwrap_or_else(|_| panic!());
+        linux_sys::rt_sigprocmask(linux_sys::SIG_UNBLOCK, &SigSet::rt(0..1), ptr::null_mut())
+            .unwrap_or_else(|_| panic!());
     }
 }
 
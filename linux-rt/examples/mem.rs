@@ -0,0 +1,139 @@
+//! Checks `memcpy`, `memmove`, `memset`, `memcmp` and `bcmp` against reference implementations
+//!
+//! Run it with and without the `fast-mem` feature; it exits with a non-zero code on mismatch
+
+#![deny(rust_2018_compatibility)]
+#![deny(rust_2018_idioms)]
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+use linux_io::{process, Stderr, Stdout};
+use panic_stderr as _;
+
+extern "C" {
+    fn bcmp(lhs: *const u8, rhs: *const u8, count: usize) -> i32;
+    fn memcmp(lhs: *const u8, rhs: *const u8, count: usize) -> i32;
+    fn memcpy(dest: *mut u8, src: *const u8, count: usize) -> *mut u8;
+    fn memmove(dest: *mut u8, src: *const u8, count: usize) -> *mut u8;
+    fn memset(dest: *mut u8, ch: i32, count: usize) -> *mut u8;
+}
+
+const LEN: usize = 128;
+
+#[linux_rt::entry]
+fn main() {
+    let mut a = [0; LEN];
+    let mut b = [0; LEN];
+
+    // cover all the (mis)alignments of source and destination
+    for x in 0..16 {
+        for y in 0..16 {
+            for count in 0..64 {
+                // non-overlapping copy
+                fill(&mut a, 7);
+                fill(&mut b, 3);
+                let mut expected = b;
+                copy(&mut expected, y, &a, x, count);
+                let ret = unsafe { memcpy(b.as_mut_ptr().add(y), a.as_ptr().add(x), count) };
+                check(
+                    compare(&b, &expected) == 0 && ret == unsafe { b.as_mut_ptr().add(y) },
+                    "memcpy",
+                );
+
+                // overlapping copy, in both directions
+                fill(&mut a, 13);
+                let src = a;
+                let mut expected = a;
+                copy(&mut expected, 24 + y, &src, 24 + x, count);
+                let ret = unsafe {
+                    let p = a.as_mut_ptr();
+                    memmove(p.add(24 + y), p.add(24 + x), count)
+                };
+                check(
+                    compare(&a, &expected) == 0 && ret == unsafe { a.as_mut_ptr().add(24 + y) },
+                    "memmove",
+                );
+
+                // NOTE only the low byte of `ch` is used
+                fill(&mut a, 1);
+                let mut expected = a;
+                for byte in &mut expected[y..y + count] {
+                    *byte = x as u8;
+                }
+                let ret = unsafe { memset(a.as_mut_ptr().add(y), 0x100 + x as i32, count) };
+                check(
+                    compare(&a, &expected) == 0 && ret == unsafe { a.as_mut_ptr().add(y) },
+                    "memset",
+                );
+
+                // equal buffers and buffers that differ at position `x`
+                fill(&mut a, 1);
+                fill(&mut b, 1);
+                for &delta in &[0, 1, 0xff] {
+                    let mut b = b;
+                    if x < count {
+                        b[y + x] = b[y + x].wrapping_add(delta);
+                    }
+
+                    let lhs = &a[y..y + count];
+                    let rhs = &b[y..y + count];
+                    let expected = compare(lhs, rhs);
+                    let (cmp, bcmp) = unsafe {
+                        (
+                            memcmp(lhs.as_ptr(), rhs.as_ptr(), count),
+                            bcmp(lhs.as_ptr(), rhs.as_ptr(), count),
+                        )
+                    };
+                    check(cmp.signum() == expected, "memcmp");
+                    check((bcmp == 0) == (expected == 0), "bcmp");
+                }
+            }
+        }
+    }
+
+    Stdout.write(b"OK\n").ok();
+}
+
+fn check(cond: bool, what: &str) {
+    if !cond {
+        Stderr.write(what.as_bytes()).ok();
+        Stderr.write(b": mismatch\n").ok();
+        process::exit(1);
+    }
+}
+
+// Reference implementations. These work one byte at a time and must not be turned into calls to
+// the functions under test, hence the volatile operations
+
+fn compare(lhs: &[u8], rhs: &[u8]) -> i32 {
+    for i in 0..lhs.len() {
+        let (l, r) = unsafe {
+            (
+                (&lhs[i] as *const u8).read_volatile(),
+                (&rhs[i] as *const u8).read_volatile(),
+            )
+        };
+
+        if l != r {
+            return if l < r { -1 } else { 1 };
+        }
+    }
+
+    0
+}
+
+fn copy(dest: &mut [u8; LEN], at: usize, src: &[u8; LEN], from: usize, count: usize) {
+    for i in 0..count {
+        unsafe {
+            (&mut dest[at + i] as *mut u8)
+                .write_volatile((&src[from + i] as *const u8).read_volatile())
+        }
+    }
+}
+
+fn fill(buf: &mut [u8; LEN], seed: u8) {
+    for (i, byte) in buf.iter_mut().enumerate() {
+        unsafe { (byte as *mut u8).write_volatile((i as u8).wrapping_mul(seed).wrapping_add(1)) }
+    }
+}
@@ -5,14 +5,12 @@
 #![no_main]
 #![no_std]
 
-use core::{mem, ptr};
+use core::ptr;
 
 use cty::c_void;
-use linux_io::Stdout;
-use linux_sys::{sigaction, sighandler_t, siginfo_t, SIGRTMIN};
+use linux_io::{println, Stdout};
+use linux_sys::{sigaction, sighandler_t, siginfo_t, sigval_t, SigSet, SIGRTMIN};
 use panic_stderr as _;
-use ufmt::uwriteln;
-use ufmt_utils::{consts, Ignore, LineBuffered};
 
 #[linux_rt::entry]
 fn main() {
@@ -32,7 +30,7 @@ fn main() {
                 sa_flags: linux_sys::SA_RESTORER | linux_sys::SA_SIGINFO,
                 sa_restorer: Some(__restorer),
                 // highest priority, blocks the other two RT signals
-                sa_mask: (1 << SIGRTMIN) | (1 << SIGRTMIN + 1),
+                sa_mask: SigSet::rt(1..3),
             },
             ptr::null_mut(),
         )
@@ -45,7 +43,7 @@ fn main() {
                 sa_flags: linux_sys::SA_RESTORER | linux_sys::SA_SIGINFO,
                 sa_restorer: Some(__restorer),
                 // mid priority, blocks the lowest priority RT signal
-                sa_mask: (1 << SIGRTMIN + 1),
+                sa_mask: SigSet::rt(2..3),
             },
             ptr::null_mut(),
         )
@@ -58,7 +56,7 @@ fn main() {
                 sa_flags: linux_sys::SA_RESTORER | linux_sys::SA_SIGINFO,
                 sa_restorer: Some(__restorer),
                 // lowest priority, doesn't block any other RT signal
-                sa_mask: 0,
+                sa_mask: SigSet::empty(),
             },
             ptr::null_mut(),
         )
@@ -67,9 +65,7 @@ fn main() {
         // raise the second real-time signal
         let tgid = linux_sys::getpid();
         let tid = tgid;
-        let mut si: siginfo_t = mem::uninitialized();
-        si.si_code = linux_sys::SI_QUEUE;
-        si.si_value = 1;
+        let si = siginfo_t::queue(sigval_t { sival_int: 1 });
         linux_sys::rt_tgsigqueueinfo(tgid, tid, SIGRTMIN + 1, &si).unwrap_or_else(|_| panic!());
 
         // `p2` should run at this point
@@ -79,59 +75,52 @@ fn main() {
 }
 
 extern "C" fn p1(sig: i32, si: &mut siginfo_t, _: *mut c_void) {
-    let mut stdout = LineBuffered::<_, consts::U100>::new(Ignore::new(Stdout));
-
     let sp = &mut 0;
-    uwriteln!(
-        &mut stdout,
+    println!(
         "p1(sig={}, si={}, sp={:?})",
         sig,
-        si.si_value,
+        unsafe { si.value().unwrap_or_else(|| panic!()).sival_int },
         sp as *mut i32,
-    )
-    .ok();
+    );
 }
 
 extern "C" fn p2(sig: i32, si: &mut siginfo_t, _: *mut c_void) {
-    let mut stdout = LineBuffered::<_, consts::U100>::new(Ignore::new(Stdout));
-
     let sp = &mut 0;
-    uwriteln!(
-        &mut stdout,
+    println!(
         "p2(sig={}, si={}, sp={:?})",
         sig,
-        si.si_value,
+        unsafe { si.value().unwrap_or_else(|| panic!()).sival_int },
         sp as *mut i32,
-    )
-    .ok();
+    );
 
     unsafe {
         // raise the third (lowest priority) RT signal
         let tgid = linux_sys::getpid();
         let tid = tgid;
-        si.si_value += 1;
+        let value = si.value().unwrap_or_else(|| panic!()).sival_int;
+        let si = siginfo_t::queue(sigval_t {
+            sival_int: value + 1,
+        });
         linux_sys::rt_tgsigqueueinfo(tgid, tid, SIGRTMIN + 2, &si).unwrap_or_else(|_| panic!());
 
-        uwriteln!(&mut stdout, "after raise(SIGRTMIN+2)").ok();
+        println!("after raise(SIGRTMIN+2)");
 
         // raise the first (highest priority) RT signal
-        si.si_value += 1;
+        let si = siginfo_t::queue(sigval_t {
+            sival_int: value + 2,
+        });
         linux_sys::rt_tgsigqueueinfo(tgid, tid, SIGRTMIN, &si).unwrap_or_else(|_| panic!());
 
-        uwriteln!(&mut stdout, "after raise(SIGRTMIN)").ok();
+        println!("after raise(SIGRTMIN)");
     }
 }
 
 extern "C" fn p3(sig: i32, si: &mut siginfo_t, _: *mut c_void) {
-    let mut stdout = LineBuffered::<_, consts::U100>::new(Ignore::new(Stdout));
-
     let sp = &mut 0;
-    uwriteln!(
-        &mut stdout,
+    println!(
         "p3(sig={}, si={}, sp={:?})",
         sig,
-        si.si_value,
+        unsafe { si.value().unwrap_or_else(|| panic!()).sival_int },
         sp as *mut i32,
-    )
-    .ok();
+    );
 }
@@ -5,14 +5,12 @@
 #![no_main]
 #![no_std]
 
-use core::{mem, ptr};
+use core::ptr;
 
 use cty::c_void;
-use linux_io::{Stderr, Stdout};
-use linux_sys::{sigaction, sighandler_t, siginfo_t, SIGRTMIN};
+use linux_io::{println, Stderr};
+use linux_sys::{sigaction, sighandler_t, siginfo_t, sigval_t, SigSet, SIGRTMIN};
 use panic_stderr as _;
-use ufmt::uwriteln;
-use ufmt_utils::{consts, Ignore, LineBuffered};
 
 #[linux_rt::entry]
 fn main() {
@@ -31,7 +29,7 @@ fn main() {
                 sa_: sighandler_t { sigaction },
                 sa_flags: linux_sys::SA_RESTORER | linux_sys::SA_SIGINFO,
                 sa_restorer: Some(__restorer),
-                sa_mask: 0,
+                sa_mask: SigSet::empty(),
             },
             ptr::null_mut(),
         )
@@ -40,9 +38,7 @@ fn main() {
         // raise the first real-time signal
         let tgid = linux_sys::getpid();
         let tid = tgid;
-        let mut si: siginfo_t = mem::uninitialized();
-        si.si_code = linux_sys::SI_QUEUE;
-        si.si_value = 1;
+        let si = siginfo_t::queue(sigval_t { sival_int: 1 });
         linux_sys::rt_tgsigqueueinfo(tgid, tid, SIGRTMIN, &si).unwrap_or_else(|_| panic!());
 
         // `sigaction` should run at this point
@@ -52,7 +48,5 @@ fn main() {
 }
 
 extern "C" fn sigaction(sig: i32, si: &mut siginfo_t, _: *mut c_void) {
-    let mut stdout = LineBuffered::<_, consts::U100>::new(Ignore::new(Stdout));
-
-    uwriteln!(&mut stdout, "handler(sig={}, si={:#?})", sig, si).ok();
+    println!("handler(sig={}, si={:#?})", sig, si);
 }
@@ -6,7 +6,7 @@
 
 use core::{mem, ptr};
 
-use linux_sys::{siginfo_t, timespec, SIGRTMIN};
+use linux_sys::{siginfo_t, sigval_t, timespec, SigSet, SIGRTMIN};
 use panic_stderr as _;
 
 #[linux_rt::entry]
@@ -16,25 +16,19 @@ fn main() {
         linux_sys::sched_setaffinity(0, &[1, 0, 0, 0, 0, 0, 0, 0]).unwrap_or_else(|_| panic!());
 
         // block the first real-time signal
-        linux_sys::rt_sigprocmask(
-            linux_sys::SIG_BLOCK,
-            &(1 << (SIGRTMIN - 1)),
-            ptr::null_mut(),
-        )
-        .unwrap_or_else(|_| panic!());
+        linux_sys::rt_sigprocmask(linux_sys::SIG_BLOCK, &SigSet::rt(0..1), ptr::null_mut())
+            .unwrap_or_else(|_| panic!());
 
         // raise the first real-time signal
         let tgid = linux_sys::getpid();
         let tid = tgid;
-        let mut si: siginfo_t = mem::uninitialized();
-        si.si_code = linux_sys::SI_QUEUE;
-        si.si_value = 1;
+        let si = siginfo_t::queue(sigval_t { sival_int: 1 });
         linux_sys::rt_tgsigqueueinfo(tgid, tid, SIGRTMIN, &si).unwrap_or_else(|_| panic!());
 
         // check pending signals; the real-time signal should be there
-        let ss = 1 << (SIGRTMIN - 1);
+        let ss = SigSet::rt(0..1);
         let pending = linux_sys::rt_sigpending().unwrap_or_else(|_| panic!());
-        assert_eq!(pending, ss);
+        assert!(pending == ss);
 
         // wait for the signal; this should return immediately
         let mut si: siginfo_t = mem::uninitialized();
@@ -49,9 +43,11 @@ fn main() {
         .unwrap_or_else(|_| panic!());
 
         // check the signal payload
-        assert_eq!(si.si_value, 1);
+        assert_eq!(si.value().map(|value| value.sival_int), Some(1));
 
         // there should be no pending signals
-        assert_eq!(linux_sys::rt_sigpending().unwrap_or_else(|_| panic!()), 0);
+        assert!(linux_sys::rt_sigpending()
+            .unwrap_or_else(|_| panic!())
+            .is_empty());
     }
 }
@@ -0,0 +1,52 @@
+//! Polls standard input without blocking until some input arrives, then echoes the rest of it
+//! back, line by line
+//!
+//! Try `$ (sleep 1; printf 'hello\nworld\n') | target/debug/examples/stdin`
+
+#![deny(rust_2018_compatibility)]
+#![deny(rust_2018_idioms)]
+#![deny(warnings)]
+#![feature(proc_macro_hygiene)]
+#![no_main]
+#![no_std]
+
+use heapless::{consts, Vec};
+use linux_io::{println, Stdin, Stdout};
+use panic_stderr as _;
+
+#[linux_rt::entry]
+fn main() -> Result<(), linux_sys::Error> {
+    // poll standard input until some input arrives
+    Stdin.set_nonblocking(true)?;
+    let mut buf = [0; 64];
+    let mut polls = 0;
+    let n = loop {
+        match Stdin.try_read(&mut buf) {
+            Ok(n) => break n,
+            Err(nb::Error::WouldBlock) => {
+                polls += 1;
+                linux_sys::sched_yield();
+            }
+            Err(nb::Error::Other(e)) => return Err(e),
+        }
+    };
+    Stdin.set_nonblocking(false)?;
+
+    println!("{} bytes arrived after {} polls", n, polls);
+    Stdout.write_all(&buf[..n])?;
+
+    let mut line = Vec::<u8, consts::U64>::new();
+    let mut lineno = 1;
+    loop {
+        line.clear();
+        if Stdin.read_line(&mut line)? == 0 {
+            break;
+        }
+
+        println!("{}: {} bytes", lineno, line.len());
+        Stdout.write_all(&line)?;
+        lineno += 1;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+//! Launches helper processes and reaps them as `SIGCHLD` signals arrive
+
+#![deny(rust_2018_compatibility)]
+#![deny(rust_2018_idioms)]
+#![deny(warnings)]
+#![feature(proc_macro_hygiene)]
+#![no_main]
+#![no_std]
+
+use core::{mem, ptr};
+
+use linux_io::{
+    println,
+    process::{self, Command},
+};
+use linux_sys::{siginfo_t, timespec, SigSet, SIGCHLD};
+use panic_stderr as _;
+
+#[linux_rt::entry]
+fn main() {
+    // block `SIGCHLD` so we can wait for it synchronously
+    let mut set = SigSet::empty();
+    set.add(SIGCHLD);
+    unsafe {
+        linux_sys::rt_sigprocmask(linux_sys::SIG_BLOCK, &set, ptr::null_mut())
+            .unwrap_or_else(|_| panic!());
+    }
+
+    println!(
+        "supervisor: pid={}, ppid={}",
+        process::getpid(),
+        process::getppid()
+    );
+
+    let fast = Command::new("/bin/sh")
+        .arg("-c")
+        .arg("echo helper: HELPER=$HELPER; exit 3")
+        .env("HELPER", "fast")
+        .spawn()
+        .unwrap_or_else(|_| panic!());
+    let slow = Command::new("/bin/sleep")
+        .arg("60")
+        .spawn()
+        .unwrap_or_else(|_| panic!());
+    println!("spawned {} and {}", fast.id(), slow.id());
+
+    if let Err(e) = Command::new("/does/not/exist").spawn() {
+        println!("spawn error: {:?}", e);
+    }
+
+    let mut running = 2;
+    while running != 0 {
+        // wait for a child to change state; stop the slow one if it takes too long
+        let mut si: siginfo_t = unsafe { mem::zeroed() };
+        let res = unsafe {
+            linux_sys::rt_sigtimedwait(
+                &set,
+                &mut si,
+                &timespec {
+                    tv_sec: 1,
+                    tv_nsec: 0,
+                },
+            )
+        };
+
+        if res.is_err() {
+            println!("timeout; killing {}", slow.id());
+            slow.kill().unwrap_or_else(|_| panic!());
+            continue;
+        }
+
+        // one signal may stand for several exits
+        while running != 0 {
+            match process::try_wait().unwrap_or_else(|_| panic!()) {
+                Some((pid, status)) => {
+                    println!("{} exited ({})", pid, status);
+                    running -= 1;
+                }
+                None => break,
+            }
+        }
+    }
+}
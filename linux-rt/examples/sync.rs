@@ -0,0 +1,68 @@
+//! A worker thread waits on a condition variable for jobs posted by the main thread
+
+#![deny(rust_2018_compatibility)]
+#![deny(rust_2018_idioms)]
+#![deny(warnings)]
+#![feature(proc_macro_hygiene)]
+#![no_main]
+#![no_std]
+
+use linux_io::{println, Stdout};
+use linux_rt::sync::{Condvar, Mutex, Once};
+use panic_stderr as _;
+
+// (pending job, done)
+static JOB: Mutex<(Option<u32>, bool)> = Mutex::with_priority_inheritance((None, false));
+static READY: Condvar = Condvar::new();
+static BANNER: Once = Once::new();
+
+#[linux_rt::entry]
+fn main() -> Result<(), linux_sys::Error> {
+    let worker = linux_rt::thread::spawn(16 * 1024, || {
+        BANNER.call_once(|| {
+            Stdout.write(b"started\n").ok();
+        });
+
+        let mut sum = 0;
+        let mut job = JOB.lock();
+        loop {
+            if let Some(x) = job.0.take() {
+                println!("job {}", x);
+                sum += x;
+                READY.notify_all();
+            } else if job.1 {
+                break sum;
+            } else {
+                job = READY.wait(job);
+            }
+        }
+    })?;
+
+    BANNER.call_once(|| {
+        Stdout.write(b"started\n").ok();
+    });
+
+    for x in 1..=3 {
+        let mut job = JOB.lock();
+        // wait until the worker has taken the previous job
+        while job.0.is_some() {
+            job = READY.wait(job);
+        }
+        job.0 = Some(x);
+        READY.notify_all();
+    }
+
+    {
+        let mut job = JOB.lock();
+        while job.0.is_some() {
+            job = READY.wait(job);
+        }
+        job.1 = true;
+        READY.notify_all();
+    }
+
+    let sum = worker.join();
+    println!("sum = {}", sum);
+
+    Ok(())
+}
@@ -7,11 +7,9 @@
 #![no_main]
 #![no_std]
 
-use linux_io::{Stderr, Stdout};
+use linux_io::{println, Stderr};
 use linux_sys::sched_param;
 use panic_stderr as _;
-use ufmt::uwriteln;
-use ufmt_utils::{consts, Ignore, LineBuffered};
 
 // `$ getconf PAGE_SIZE`
 const PAGE_SIZE: u64 = 4096;
@@ -19,8 +17,6 @@ const STACK_SIZE: u64 = 4 * PAGE_SIZE;
 
 #[linux_rt::entry]
 fn main() {
-    let mut stdout = LineBuffered::<_, consts::U100>::new(Ignore::new(Stdout));
-
     unsafe {
         // schedule all threads on the first core
         // spawned threads (`clone`) inherit this CPU affinity
@@ -57,13 +53,10 @@ fn main() {
         let stack_high = (stack_low as u64 + STACK_SIZE) as *mut _;
 
         let x = &mut 0;
-        uwriteln!(
-            &mut stdout,
+        println!(
             "before clone: parent_stack={:?}, child_stack={:?}",
-            x as *mut _,
-            stack_high
-        )
-        .ok();
+            x as *mut _, stack_high
+        );
 
         // spin a new thread
         let child_tid = linux_sys::x86_64_clone(
@@ -74,7 +67,7 @@ fn main() {
             child,
         )
         .unwrap_or_else(|e| {
-            uwriteln!(&mut stdout, "{:?}", e).ok();
+            println!("{:?}", e);
             panic!()
         });
 
@@ -82,13 +75,7 @@ fn main() {
         // CPU affinity so it will not run just yet
 
         let parent_tid = linux_sys::gettid();
-        uwriteln!(
-            &mut stdout,
-            "(parent) after clone ({} -> {})",
-            parent_tid,
-            child_tid
-        )
-        .ok();
+        println!("(parent) after clone ({} -> {})", parent_tid, child_tid);
 
         // raise the priority of the child to 2
         linux_sys::sched_setparam(child_tid, &sched_param { sched_priority: 2 })
@@ -96,24 +83,20 @@ fn main() {
 
         // now we'll be *preempted* by `child`; `child` will also run on core #0
 
-        uwriteln!(&mut stdout, "(parent) after sched_setparam").ok();
+        println!("(parent) after sched_setparam");
     }
 }
 
 // code that the child thread will run
 extern "C" fn child() -> ! {
-    let mut stdout = LineBuffered::<_, consts::U100>::new(Ignore::new(Stdout));
-
     unsafe {
         let x = &mut 0;
-        uwriteln!(
-            &mut stdout,
+        println!(
             "(child) tgid={}, tid={}, SP={:?}",
             linux_sys::getpid(),
             linux_sys::gettid(),
             x as *mut _
-        )
-        .ok();
+        );
 
         // exit this thread
         linux_sys::exit(0);
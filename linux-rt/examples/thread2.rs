@@ -7,10 +7,8 @@
 #![no_main]
 #![no_std]
 
-use linux_io::{Stderr, Stdout};
+use linux_io::{eprintln, Stderr, Stdout};
 use panic_stderr as _;
-use ufmt::uwriteln;
-use ufmt_utils::{consts, Ignore, LineBuffered};
 
 // `$ getconf PAGE_SIZE`
 const PAGE_SIZE: u64 = 4_096;
@@ -18,8 +16,6 @@ const STACK_SIZE: u64 = 4 * PAGE_SIZE;
 
 #[linux_rt::entry]
 fn main() {
-    let mut stderr = LineBuffered::<_, consts::U100>::new(Ignore::new(Stderr));
-
     unsafe {
         // schedule this thread on the first core
         linux_sys::sched_setaffinity(0, &[1 << 0, 0, 0, 0, 0, 0, 0, 0])
@@ -64,7 +60,7 @@ fn main() {
             child,
         )
         .unwrap_or_else(|e| {
-            uwriteln!(&mut stderr, "{:?}", e).ok();
+            eprintln!("{:?}", e);
             panic!()
         });
 
@@ -7,16 +7,14 @@
 
 use core::ptr;
 
-use linux_io::{time::Instant, Stdout};
-use linux_sys::{itimerspec, sigaction, sigevent, sighandler_t, sigval_t, timespec, SIGRTMIN};
+use linux_io::{println, time::Instant};
+use linux_sys::{
+    itimerspec, sigaction, sigevent, sighandler_t, sigval_t, timespec, SigSet, SIGRTMIN,
+};
 use panic_stderr as _;
-use ufmt::uwriteln;
-use ufmt_utils::{consts, Ignore, LineBuffered};
 
 #[linux_rt::entry]
 fn main() {
-    let mut stdout = LineBuffered::<_, consts::U100>::new(Ignore::new(Stdout));
-
     unsafe {
         // schedule all threads on the first core
         linux_sys::sched_setaffinity(0, &[1, 0, 0, 0, 0, 0, 0, 0]).unwrap_or_else(|_| panic!());
@@ -32,7 +30,7 @@ fn main() {
                 sa_: sighandler_t { handler },
                 sa_flags: linux_sys::SA_RESTORER,
                 sa_restorer: Some(__restorer),
-                sa_mask: 0,
+                sa_mask: SigSet::empty(),
             },
             ptr::null_mut(),
         )
@@ -66,18 +64,16 @@ fn main() {
             ptr::null_mut(),
         )
         .unwrap_or_else(|_| panic!());
-        uwriteln!(&mut stdout, "before handler @ {:?}", now).ok();
+        println!("before handler @ {:?}", now);
 
         linux_sys::pause();
 
         let now = timespec::from(Instant::now());
-        uwriteln!(&mut stdout, "after handler @ {:?}", now).ok();
+        println!("after handler @ {:?}", now);
     }
 }
 
 extern "C" fn handler(_sig: i32) {
     let now = timespec::from(Instant::now());
-    let mut stdout = LineBuffered::<_, consts::U100>::new(Ignore::new(Stdout));
-
-    uwriteln!(&mut stdout, "handler @ {:?}", now).ok();
+    println!("handler @ {:?}", now);
 }
@@ -0,0 +1,49 @@
+//! Sends telemetry datagrams over the loopback interface and receives them with timestamps
+
+#![deny(rust_2018_compatibility)]
+#![deny(rust_2018_idioms)]
+#![deny(warnings)]
+#![feature(proc_macro_hygiene)]
+#![no_main]
+#![no_std]
+
+use linux_io::{
+    net::{Ipv4Addr, UdpSocket},
+    println,
+};
+use linux_sys::Error;
+use panic_stderr as _;
+
+#[linux_rt::entry]
+fn main() -> Result<(), Error> {
+    // the operating system picks the ports
+    let collector = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0))?;
+    collector.set_recv_buffer_size(64 * 1024)?;
+    collector.set_timestamps(true)?;
+    let addr = collector.local_addr()?;
+
+    let sensor = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0))?;
+    sensor.set_priority(6)?;
+
+    let size = collector.recv_buffer_size()?;
+    println!("collector at {} ({} bytes of receive buffer)", addr, size);
+
+    for sample in 0..3u8 {
+        sensor.send_to(&[sample; 8], addr)?;
+    }
+
+    let mut buf = [0; 64];
+    for _ in 0..3 {
+        let (n, from, timestamp) = collector.recv_from_timestamped(&mut buf)?;
+        let timestamp = timestamp.unwrap_or_default();
+        println!(
+            "{} bytes from {} at {} s + {} ns",
+            n,
+            from,
+            timestamp.as_secs(),
+            timestamp.subsec_nanos()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+//! Compares the cost of reading the monotonic clock through the vDSO and through the system call
+
+#![deny(rust_2018_compatibility)]
+#![deny(rust_2018_idioms)]
+#![deny(warnings)]
+#![feature(proc_macro_hygiene)]
+#![no_main]
+#![no_std]
+
+use core::mem::MaybeUninit;
+
+use linux_io::{println, time::Instant};
+use linux_sys::{timespec, CLOCK_MONOTONIC};
+use panic_stderr as _;
+use sc::syscall;
+
+const N: u32 = 100_000;
+
+#[linux_rt::entry]
+fn main() {
+    let start = Instant::now();
+    for _ in 0..N {
+        linux_sys::clock_gettime(CLOCK_MONOTONIC).ok();
+    }
+    let vdso = Instant::now().saturating_duration_since(start).as_nanos() / u128::from(N);
+
+    let start = Instant::now();
+    for _ in 0..N {
+        let mut tp = MaybeUninit::<timespec>::uninit();
+        unsafe {
+            syscall!(CLOCK_GETTIME, CLOCK_MONOTONIC, tp.as_mut_ptr());
+        }
+    }
+    let syscall = Instant::now().saturating_duration_since(start).as_nanos() / u128::from(N);
+
+    println!("vDSO: {} ns", vdso as u32);
+    println!("syscall: {} ns", syscall as u32);
+}
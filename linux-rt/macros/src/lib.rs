@@ -5,29 +5,27 @@ use proc_macro::TokenStream;
 use proc_macro2::Span;
 use quote::quote;
 use syn::{
-    parse, parse_macro_input, spanned::Spanned, Item, ItemFn, ItemStatic, ReturnType, Stmt, Type,
-    Visibility,
+    parse, parse_macro_input, spanned::Spanned, Ident, Item, ItemFn, ItemStatic, ReturnType, Stmt,
+    Type, Visibility,
 };
 
 #[proc_macro_attribute]
 pub fn entry(args: TokenStream, input: TokenStream) -> TokenStream {
     let f = parse_macro_input!(input as ItemFn);
 
-    if !args.is_empty() {
-        return parse::Error::new(Span::call_site(), "This attribute accepts no arguments")
-            .to_compile_error()
-            .into();
-    }
+    // `#[entry(exit_group)]`
+    let exit_group = match parse_args(args) {
+        Ok(exit_group) => exit_group,
+        Err(e) => return e.to_compile_error().into(),
+    };
 
     // check the function signature
-    let valid_signature = check_signature(&f)
-        && f.decl.inputs.is_empty()
-        && (is_bottom(&f.decl.output) || is_unit(&f.decl.output));
+    let valid_signature = check_signature(&f) && f.decl.inputs.is_empty();
 
     if !valid_signature {
         return parse::Error::new(
             f.span(),
-            "`#[entry]` function must have signature `fn() [-> !]`",
+            "`#[entry]` function must have signature `fn() [-> T]`",
         )
         .to_compile_error()
         .into();
@@ -54,22 +52,50 @@ pub fn entry(args: TokenStream, input: TokenStream) -> TokenStream {
 
     let attrs = f.attrs;
     let ident = f.ident;
+    let output = f.decl.output;
+    // NOTE `!` doesn't implement `Termination`; it coerces into the exit code
+    let exit = if is_bottom(&output) {
+        quote!(crate::#ident(#(#args),*))
+    } else if exit_group {
+        quote!(linux_rt::export::exit_group(linux_rt::Termination::report(
+            crate::#ident(#(#args),*)
+        )))
+    } else {
+        quote!(linux_rt::Termination::report(crate::#ident(#(#args),*)))
+    };
     quote!(
-        fn #ident(#(#params),*) {
-            #(#stmts)*
-
+        fn #ident(#(#params),*) #output {
+            // NOTE this item goes first because the last statement may be the tail expression
             #(#attrs)*
             #[export_name = "main"]
-            unsafe fn #ident() {
+            unsafe fn #ident() -> u8 {
                 #(#vars)*
 
-                crate::#ident(#(#args),*)
+                #exit
             }
+
+            #(#stmts)*
         }
     )
     .into()
 }
 
+/// Parses the `#[entry]` arguments; returns `true` if `exit_group` was passed
+fn parse_args(args: TokenStream) -> parse::Result<bool> {
+    if args.is_empty() {
+        return Ok(false);
+    }
+
+    let span = Span::call_site();
+    match syn::parse::<Ident>(args) {
+        Ok(ref ident) if ident == "exit_group" => Ok(true),
+        _ => Err(parse::Error::new(
+            span,
+            "This attribute accepts no arguments or `exit_group`",
+        )),
+    }
+}
+
 /// checks that a function signature
 ///
 /// - has no bounds (like where clauses)
@@ -107,18 +133,6 @@ fn is_bottom(ty: &ReturnType) -> bool {
     }
 }
 
-fn is_unit(ty: &ReturnType) -> bool {
-    if let ReturnType::Type(_, ty) = ty {
-        if let Type::Tuple(ref tuple) = **ty {
-            tuple.elems.is_empty()
-        } else {
-            false
-        }
-    } else {
-        true
-    }
-}
-
 /// Extracts `static mut` vars from the beginning of the given statements
 fn extract_statics(stmts: Vec<Stmt>) -> (Vec<ItemStatic>, Vec<Stmt>) {
     let mut istmts = stmts.into_iter();
@@ -0,0 +1,57 @@
+use core::slice;
+
+use crate::{ARGC, ARGV};
+
+/// Returns an iterator over the command line arguments of this process
+///
+/// The first argument is usually the path to the program
+pub fn args() -> Args {
+    unsafe {
+        Args {
+            next: ARGV,
+            end: ARGV.add(ARGC),
+        }
+    }
+}
+
+/// Iterator over the command line arguments of this process
+///
+/// Arguments are returned as they were passed by the kernel: without the trailing nul byte and not
+/// necessarily UTF-8 encoded
+pub struct Args {
+    next: *const *const u8,
+    end: *const *const u8,
+}
+
+impl Iterator for Args {
+    type Item = &'static [u8];
+
+    fn next(&mut self) -> Option<&'static [u8]> {
+        if self.next == self.end {
+            None
+        } else {
+            unsafe {
+                let arg = cstr(*self.next);
+                self.next = self.next.add(1);
+                Some(arg)
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.end as usize - self.next as usize) / core::mem::size_of::<*const u8>();
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Args {}
+
+/// Returns the nul-terminated string that starts at `ptr`, minus the nul byte
+pub(crate) unsafe fn cstr(ptr: *const u8) -> &'static [u8] {
+    let mut len = 0;
+    while *ptr.add(len) != 0 {
+        len += 1;
+    }
+
+    slice::from_raw_parts(ptr, len)
+}
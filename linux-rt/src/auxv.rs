@@ -0,0 +1,44 @@
+//! Auxiliary vector
+//!
+//! Information the kernel passes to the program at startup. See `man 3 getauxval`; the keys are the
+//! `AT_*` constants in `linux_sys`
+
+use linux_sys::{cty::c_ulong, AT_NULL};
+
+use crate::AUXV;
+
+/// Returns the value associated to `key`
+///
+/// Returns `None` if the kernel didn't provide this entry
+pub fn get(key: c_ulong) -> Option<usize> {
+    iter().find(|(k, _)| *k == key).map(|(_, value)| value)
+}
+
+/// Returns an iterator over all the `(key, value)` entries of the auxiliary vector
+pub fn iter() -> Iter {
+    Iter {
+        next: unsafe { AUXV },
+    }
+}
+
+/// Iterator over the entries of the auxiliary vector
+pub struct Iter {
+    next: *const usize,
+}
+
+impl Iterator for Iter {
+    type Item = (c_ulong, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        unsafe {
+            let key = *self.next as c_ulong;
+            if key == AT_NULL {
+                return None;
+            }
+
+            let value = *self.next.add(1);
+            self.next = self.next.add(2);
+            Some((key, value))
+        }
+    }
+}
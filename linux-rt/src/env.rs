@@ -0,0 +1,52 @@
+//! Environment variables
+
+use crate::{args::cstr, ENVP};
+
+/// Returns the value of the environment variable `name`
+///
+/// Returns `None` if the variable is not set
+pub fn var<N>(name: N) -> Option<&'static [u8]>
+where
+    N: AsRef<[u8]>,
+{
+    let name = name.as_ref();
+
+    vars().find(|(key, _)| *key == name).map(|(_, value)| value)
+}
+
+/// Returns an iterator over all the environment variables of this process, as `(name, value)`
+/// pairs
+pub fn vars() -> Vars {
+    Vars {
+        next: unsafe { ENVP },
+    }
+}
+
+/// Iterator over the environment variables of this process
+///
+/// Names and values are not necessarily UTF-8 encoded
+pub struct Vars {
+    next: *const *const u8,
+}
+
+impl Iterator for Vars {
+    type Item = (&'static [u8], &'static [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        unsafe {
+            // the list is terminated by a null pointer
+            let entry = *self.next;
+            if entry.is_null() {
+                return None;
+            }
+            self.next = self.next.add(1);
+
+            // entries have the form `name=value`
+            let entry = cstr(entry);
+            Some(match entry.iter().position(|byte| *byte == b'=') {
+                Some(pos) => (&entry[..pos], &entry[pos + 1..]),
+                None => (entry, &[]),
+            })
+        }
+    }
+}
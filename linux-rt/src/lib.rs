@@ -5,24 +5,146 @@
 #![deny(rust_2018_idioms)]
 #![deny(warnings)]
 #![feature(global_asm)]
+#![feature(proc_macro_hygiene)]
 #![no_std]
 
+use core::ptr;
+
+use linux_io::Stderr;
+use linux_sys::AT_SYSINFO_EHDR;
+use ufmt::{uDebug, uwriteln};
+
+mod args;
+pub mod auxv;
+pub mod env;
 mod symbols;
+pub mod sync;
+pub mod thread;
 
+pub use crate::args::{args, Args};
 pub use linux_rt_macros::entry;
 
 #[cfg(not(target_arch = "x86_64"))]
 compile_error!("Only x86_64 is currently supported");
 
+// NOTE these are written once, in `start` (or `init`), before `main` runs; afterwards they are
+// only read
+static mut ARGC: usize = 0;
+static mut ARGV: *const *const u8 = ptr::null();
+static mut AUXV: *const usize = ptr::null();
+static mut ENVP: *const *const u8 = ptr::null();
+
+#[cfg(not(feature = "std"))]
 #[allow(unused_attributes)]
 #[no_mangle]
-unsafe extern "C" fn start(_stack_top: *const u32) -> ! {
+unsafe extern "C" fn start(stack_top: *const usize) -> ! {
     extern "Rust" {
-        fn main();
+        fn main() -> u8;
+    }
+
+    // the kernel leaves `argc`, `argv`, `envp` and the auxiliary vector at the top of the stack
+    // (see "Process Initialization" in the System V x86_64 psABI)
+    ARGC = *stack_top;
+    ARGV = stack_top.add(1) as *const *const u8;
+    // `argv` and `envp` are terminated by a null pointer
+    ENVP = ARGV.add(ARGC + 1);
+    let mut envp = ENVP;
+    while !(*envp).is_null() {
+        envp = envp.add(1);
     }
+    AUXV = envp.add(1) as *const usize;
+
+    linux_sys::vdso::init(auxv::get(AT_SYSINFO_EHDR).unwrap_or(0));
 
-    main();
+    let code = main();
+
+    linux_io::export::run_at_exit();
 
     // exit only *this* thread; the user may spawn more in `main`
-    linux_sys::exit(0)
+    linux_sys::exit(code)
+}
+
+// With the `std` feature the C library owns the entry point. Like the standard library, we get
+// `argc`, `argv` and `envp` from an `.init_array` constructor, which glibc calls with those
+// arguments before `main` runs
+#[cfg(feature = "std")]
+#[link_section = ".init_array.00099"]
+#[used]
+static INIT: unsafe extern "C" fn(i32, *const *const u8, *const *const u8) = init;
+
+#[cfg(feature = "std")]
+unsafe extern "C" fn init(argc: i32, argv: *const *const u8, envp: *const *const u8) {
+    ARGC = argc as usize;
+    ARGV = argv;
+    ENVP = envp;
+    // the auxiliary vector still follows the environment on the initial stack but the dynamic
+    // loader may have removed variables (e.g. `LD_PRELOAD` from programs with capabilities), which
+    // leaves extra null pointers behind; the first auxiliary vector key is never `AT_NULL`
+    let mut envp = ENVP;
+    while !(*envp).is_null() {
+        envp = envp.add(1);
+    }
+    while (*envp).is_null() {
+        envp = envp.add(1);
+    }
+    AUXV = envp as *const usize;
+
+    linux_sys::vdso::init(auxv::get(AT_SYSINFO_EHDR).unwrap_or(0));
+}
+
+/// Return type of the `#[entry]` function
+///
+/// The value returned by the `#[entry]` function is turned into the exit code of the program
+pub trait Termination {
+    /// Returns the exit code
+    fn report(self) -> u8;
+}
+
+impl Termination for () {
+    fn report(self) -> u8 {
+        0
+    }
+}
+
+impl Termination for u8 {
+    fn report(self) -> u8 {
+        self
+    }
+}
+
+/// Prints the error to `stderr` and exits with code 1
+impl<E> Termination for Result<(), E>
+where
+    E: uDebug,
+{
+    fn report(self) -> u8 {
+        match self {
+            Ok(()) => 0,
+            Err(e) => {
+                uwriteln!(&mut Stderr, "Error: {:?}", e).ok();
+                1
+            }
+        }
+    }
+}
+
+#[doc(hidden)]
+pub mod export {
+    pub use linux_io::process::exit as exit_group;
+
+    /// Checks that the `.init_array` constructor ran
+    ///
+    /// `#[used]` doesn't stop the linker from discarding the object file that contains `INIT`
+    /// when nothing else in it is referenced (rust-lang/rust#47384); this function references
+    /// `INIT` so calling it keeps the constructor in the final binary
+    #[cfg(feature = "std")]
+    pub fn assert_init() {
+        unsafe {
+            core::ptr::read_volatile(&super::INIT);
+
+            if super::ARGV.is_null() {
+                linux_io::export::fatal("error: the `linux-rt` constructor didn't run\n")
+            }
+        }
+    }
 }
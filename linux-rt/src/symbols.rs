@@ -1,10 +1,11 @@
+#[cfg(not(feature = "std"))]
 use core::hint;
 
 // One true entry point
 // 1. clear the frame point
 // 2. pass the top of the stack to `start`
 // 3. align the stack to 16 bytes
-#[cfg(target_arch = "x86_64")]
+#[cfg(all(target_arch = "x86_64", not(feature = "std")))]
 global_asm!(
     r#"
   .global _start
@@ -19,57 +20,266 @@ _start:
 
 // we don't link to `libc.a` and `compiler-builtins` doesn't provide these symbols on some targets
 // so we need to provide them ourselves; they need to be written in assembly or we'll end with
-// infinite recursion. The assembly used is the output of compiling these two Rust functions with
-// `opt-level=z`
+// infinite recursion (LLVM turns loops like the ones below back into calls to these functions).
+//
+// By default we provide the smallest implementations, which work one byte at a time. They are
+// equivalent to these Rust functions:
+//
+// #[no_mangle]
+// unsafe extern "C" fn memcpy(dest: *mut u8, src: *const u8, count: usize) -> *mut u8 {
+//     for i in 0..count {
+//         dest.add(i).write_volatile(src.add(i).read_volatile());
+//     }
+//     dest
+// }
 //
 // #[no_mangle]
-// unsafe extern "C" fn memcpy(mut dest: *mut u8, mut src: *const u8, count: usize) {
-//     for _ in 0..count {
-//         dest.write_volatile(src.read_volatile());
-//         src = src.add(1);
-//         dest = dest.add(1);
+// unsafe extern "C" fn memmove(dest: *mut u8, src: *const u8, count: usize) -> *mut u8 {
+//     if dest as usize <= src as usize {
+//         memcpy(dest, src, count)
+//     } else {
+//         // copy backwards so we don't overwrite the part of `src` we have yet to read
+//         for i in (0..count).rev() {
+//             dest.add(i).write_volatile(src.add(i).read_volatile());
+//         }
+//         dest
 //     }
 // }
 //
 // #[no_mangle]
-// unsafe extern "C" fn memset(mut dest: *mut u8, ch: i32, count: usize) {
-//     for _ in 0..count {
-//         dest.write_volatile(ch as u8);
-//         dest = dest.add(1);
+// unsafe extern "C" fn memset(dest: *mut u8, ch: i32, count: usize) -> *mut u8 {
+//     for i in 0..count {
+//         dest.add(i).write_volatile(ch as u8);
 //     }
+//     dest
 // }
+//
+// #[no_mangle] // also exported as `bcmp`
+// unsafe extern "C" fn memcmp(lhs: *const u8, rhs: *const u8, count: usize) -> i32 {
+//     for i in 0..count {
+//         let (l, r) = (lhs.add(i).read_volatile(), rhs.add(i).read_volatile());
+//         if l != r {
+//             return i32::from(l) - i32::from(r);
+//         }
+//     }
+//     0
+// }
+//
+// The `fast-mem` feature replaces them with faster, but larger, implementations: `memcpy`, `memset`
+// and forward `memmove`s use `rep movsb` / `rep stosb` (fast on CPUs with ERMSB); `memcmp` and
+// `bcmp` compare one word (8 bytes) at a time
+//
+// With the `std` feature these symbols, and the entry point, come from the C library
 
-#[cfg(target_arch = "x86_64")]
+#[cfg(all(
+    target_arch = "x86_64",
+    not(feature = "fast-mem"),
+    not(feature = "std")
+))]
 global_asm!(
     r#"
   .global memcpy
   .section .text.memcpy
 memcpy:
   movq %rdi, %rax
-  xorl %edi, %edi
+  xorl %ecx, %ecx
   jmp  2f
-1:movb (%rsi,%rdi), %cl
-  movb %cl, (%rax,%rdi)
-  incq %rdi
-2:cmpq %rdi, %rdx
+1:movb (%rsi,%rcx), %r8b
+  movb %r8b, (%rax,%rcx)
+  incq %rcx
+2:cmpq %rcx, %rdx
   jne  1b
-  addq %rdi, %rax
   retq
 "#
 );
 
-#[cfg(target_arch = "x86_64")]
+#[cfg(all(
+    target_arch = "x86_64",
+    not(feature = "fast-mem"),
+    not(feature = "std")
+))]
+global_asm!(
+    r#"
+  .global memmove
+  .section .text.memmove
+memmove:
+  movq %rdi, %rax
+  cmpq %rsi, %rdi
+  jbe  3f
+  jmp  2f
+1:decq %rdx
+  movb (%rsi,%rdx), %r8b
+  movb %r8b, (%rdi,%rdx)
+2:testq %rdx, %rdx
+  jne  1b
+  retq
+3:xorl %ecx, %ecx
+  jmp  5f
+4:movb (%rsi,%rcx), %r8b
+  movb %r8b, (%rdi,%rcx)
+  incq %rcx
+5:cmpq %rcx, %rdx
+  jne  4b
+  retq
+"#
+);
+
+#[cfg(all(
+    target_arch = "x86_64",
+    not(feature = "fast-mem"),
+    not(feature = "std")
+))]
 global_asm!(
     r#"
   .global memset
   .section .text.memset
 memset:
-  xorl %eax, %eax
+  movq %rdi, %rax
+  xorl %ecx, %ecx
   jmp  2f
-1:movb %sil, (%rdi,%rax)
-  incq %rax
-2:cmpq %rax, %rdx
+1:movb %sil, (%rax,%rcx)
+  incq %rcx
+2:cmpq %rcx, %rdx
   jne  1b
+  retq
+"#
+);
+
+#[cfg(all(
+    target_arch = "x86_64",
+    not(feature = "fast-mem"),
+    not(feature = "std")
+))]
+global_asm!(
+    r#"
+  .global bcmp
+  .global memcmp
+  .section .text.memcmp
+bcmp:
+memcmp:
+  xorl %ecx, %ecx
+  jmp  2f
+1:movzbl (%rdi,%rcx), %eax
+  movzbl (%rsi,%rcx), %r8d
+  subl %r8d, %eax
+  jne  3f
+  incq %rcx
+2:cmpq %rcx, %rdx
+  jne  1b
+  xorl %eax, %eax
+3:retq
+"#
+);
+
+#[cfg(all(target_arch = "x86_64", feature = "fast-mem", not(feature = "std")))]
+global_asm!(
+    r#"
+  .global memcpy
+  .section .text.memcpy
+memcpy:
+  movq %rdi, %rax
+  movq %rdx, %rcx
+  rep movsb
+  retq
+"#
+);
+
+// NOTE the backward copy runs with the direction flag set; the kernel clears it before running a
+// signal handler and restores it afterwards
+#[cfg(all(target_arch = "x86_64", feature = "fast-mem", not(feature = "std")))]
+global_asm!(
+    r#"
+  .global memmove
+  .section .text.memmove
+memmove:
+  movq %rdi, %rax
+  movq %rdx, %rcx
+  movq %rdi, %r8
+  subq %rsi, %r8
+  cmpq %rdx, %r8
+  jae  1f
+  leaq -1(%rsi,%rdx), %rsi
+  leaq -1(%rdi,%rdx), %rdi
+  std
+  rep movsb
+  cld
+  retq
+1:rep movsb
+  retq
+"#
+);
+
+#[cfg(all(target_arch = "x86_64", feature = "fast-mem", not(feature = "std")))]
+global_asm!(
+    r#"
+  .global memset
+  .section .text.memset
+memset:
+  movq %rdi, %r8
+  movl %esi, %eax
+  movq %rdx, %rcx
+  rep stosb
+  movq %r8, %rax
+  retq
+"#
+);
+
+// compare words until they differ, then find the first different byte
+#[cfg(all(target_arch = "x86_64", feature = "fast-mem", not(feature = "std")))]
+global_asm!(
+    r#"
+  .global memcmp
+  .section .text.memcmp
+memcmp:
+  xorl %ecx, %ecx
+  jmp  2f
+1:movq (%rdi,%rcx), %rax
+  cmpq (%rsi,%rcx), %rax
+  jne  4f
+  addq $8, %rcx
+2:leaq 8(%rcx), %r8
+  cmpq %rdx, %r8
+  jbe  1b
+  jmp  4f
+3:movzbl (%rdi,%rcx), %eax
+  movzbl (%rsi,%rcx), %r8d
+  subl %r8d, %eax
+  jne  5f
+  incq %rcx
+4:cmpq %rcx, %rdx
+  jne  3b
+  xorl %eax, %eax
+5:retq
+"#
+);
+
+// like `memcmp` but only reports whether the buffers are equal
+#[cfg(all(target_arch = "x86_64", feature = "fast-mem", not(feature = "std")))]
+global_asm!(
+    r#"
+  .global bcmp
+  .section .text.bcmp
+bcmp:
+  xorl %ecx, %ecx
+  jmp  2f
+1:movq (%rdi,%rcx), %rax
+  cmpq (%rsi,%rcx), %rax
+  jne  5f
+  addq $8, %rcx
+2:leaq 8(%rcx), %r8
+  cmpq %rdx, %r8
+  jbe  1b
+  jmp  4f
+3:movb (%rdi,%rcx), %al
+  cmpb (%rsi,%rcx), %al
+  jne  5f
+  incq %rcx
+4:cmpq %rcx, %rdx
+  jne  3b
+  xorl %eax, %eax
+  retq
+5:movl $1, %eax
+  retq
 "#
 );
 
@@ -87,6 +297,8 @@ __restorer:
 );
 
 // `core` was compiled with `-C panic=unwind` so it contains undefined references to these symbols
+// (`std` already defines them)
+#[cfg(not(feature = "std"))]
 #[allow(unused_attributes)]
 #[allow(non_snake_case)]
 #[no_mangle]
@@ -94,6 +306,7 @@ fn _Unwind_Resume() {
     unsafe { hint::unreachable_unchecked() }
 }
 
+#[cfg(not(feature = "std"))]
 #[allow(unused_attributes)]
 #[allow(non_snake_case)]
 #[no_mangle]
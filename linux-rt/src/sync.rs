@@ -0,0 +1,386 @@
+//! Synchronization primitives
+//!
+//! These are built on top of the `futex` system call: threads sleep in the kernel rather than spin
+//! while they wait. See `man 7 futex` for more details
+
+use core::{
+    cell::UnsafeCell,
+    ops::{Deref, DerefMut},
+    ptr,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use linux_io::export::fatal;
+use linux_sys::{
+    FUTEX_LOCK_PI, FUTEX_PRIVATE_FLAG, FUTEX_TRYLOCK_PI, FUTEX_UNLOCK_PI, FUTEX_WAIT, FUTEX_WAKE,
+};
+
+/// A mutual exclusion primitive
+///
+/// Threads that find the mutex locked sleep until it's unlocked. A mutex created with
+/// `with_priority_inheritance` temporarily raises the priority of its owner to that of the highest
+/// priority thread that's waiting on it (see `FUTEX_LOCK_PI` in `man 2 futex`) to bound priority
+/// inversion
+pub struct Mutex<T> {
+    // if `pi == false`: 0 = unlocked, 1 = locked, 2 = locked and there may be waiters
+    // if `pi == true`: TID of the owner (0 = unlocked) plus the kernel's `FUTEX_WAITERS` bit
+    futex: AtomicU32,
+    pi: bool,
+    data: UnsafeCell<T>,
+}
+
+unsafe impl<T> Send for Mutex<T> where T: Send {}
+unsafe impl<T> Sync for Mutex<T> where T: Send {}
+
+impl<T> Mutex<T> {
+    /// Creates a new mutex in the unlocked state
+    pub const fn new(data: T) -> Self {
+        Mutex {
+            futex: AtomicU32::new(0),
+            pi: false,
+            data: UnsafeCell::new(data),
+        }
+    }
+
+    /// Creates a new priority inheritance mutex in the unlocked state
+    pub const fn with_priority_inheritance(data: T) -> Self {
+        Mutex {
+            futex: AtomicU32::new(0),
+            pi: true,
+            data: UnsafeCell::new(data),
+        }
+    }
+
+    /// Acquires the mutex, blocking the current thread until it's able to do so
+    ///
+    /// NOTE locking a mutex that the current thread already holds deadlocks, or, for priority
+    /// inheritance mutexes, exits the process with a non-zero code
+    pub fn lock(&self) -> MutexGuard<'_, T> {
+        if self.pi {
+            let tid = linux_sys::gettid() as u32;
+            if self
+                .futex
+                .compare_exchange(0, tid, Ordering::Acquire, Ordering::Relaxed)
+                .is_err()
+            {
+                // the kernel will make us the owner
+                unsafe {
+                    linux_sys::futex(
+                        addr(&self.futex),
+                        FUTEX_LOCK_PI | FUTEX_PRIVATE_FLAG,
+                        0,
+                        ptr::null(),
+                        ptr::null(),
+                        0,
+                    )
+                }
+                .unwrap_or_else(|_| fatal("error: couldn't lock a mutex\n"));
+            }
+        } else if self
+            .futex
+            .compare_exchange(0, 1, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            self.lock_contended();
+        }
+
+        MutexGuard { mutex: self }
+    }
+
+    /// Attempts to acquire the mutex without blocking
+    pub fn try_lock(&self) -> Option<MutexGuard<'_, T>> {
+        let new = if self.pi {
+            linux_sys::gettid() as u32
+        } else {
+            1
+        };
+
+        if self
+            .futex
+            .compare_exchange(0, new, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+        {
+            return Some(MutexGuard { mutex: self });
+        }
+
+        // the owner may have exited; in that case the kernel hands the mutex over to us
+        if self.pi
+            && unsafe {
+                linux_sys::futex(
+                    addr(&self.futex),
+                    FUTEX_TRYLOCK_PI | FUTEX_PRIVATE_FLAG,
+                    0,
+                    ptr::null(),
+                    ptr::null(),
+                    0,
+                )
+            }
+            .is_ok()
+        {
+            return Some(MutexGuard { mutex: self });
+        }
+
+        None
+    }
+
+    /// Returns a mutable reference to the underlying data
+    pub fn get_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.data.get() }
+    }
+
+    // NOTE only for `pi == false`
+    fn lock_contended(&self) {
+        // assume there are other waiters; `unlock` will wake one of them up
+        while self.futex.swap(2, Ordering::Acquire) != 0 {
+            wait(&self.futex, 2);
+        }
+    }
+
+    fn unlock(&self) {
+        if self.pi {
+            let tid = linux_sys::gettid() as u32;
+            if self
+                .futex
+                .compare_exchange(tid, 0, Ordering::Release, Ordering::Relaxed)
+                .is_err()
+            {
+                // there are waiters; the kernel hands the mutex over to the highest priority one
+                unsafe {
+                    linux_sys::futex(
+                        addr(&self.futex),
+                        FUTEX_UNLOCK_PI | FUTEX_PRIVATE_FLAG,
+                        0,
+                        ptr::null(),
+                        ptr::null(),
+                        0,
+                    )
+                }
+                .unwrap_or_else(|_| fatal("error: couldn't unlock a mutex\n"));
+            }
+        } else if self.futex.swap(0, Ordering::Release) == 2 {
+            wake(&self.futex, 1);
+        }
+    }
+}
+
+/// An RAII guard that releases the `Mutex` when dropped
+pub struct MutexGuard<'a, T> {
+    mutex: &'a Mutex<T>,
+}
+
+impl<T> Deref for MutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.mutex.data.get() }
+    }
+}
+
+impl<T> DerefMut for MutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.mutex.data.get() }
+    }
+}
+
+impl<T> Drop for MutexGuard<'_, T> {
+    fn drop(&mut self) {
+        self.mutex.unlock()
+    }
+}
+
+/// A condition variable
+///
+/// Blocks a thread, without consuming CPU time, until some condition, protected by a `Mutex`,
+/// becomes true
+pub struct Condvar {
+    // incremented on each notification
+    seq: AtomicU32,
+}
+
+impl Condvar {
+    /// Creates a new condition variable
+    pub const fn new() -> Self {
+        Condvar {
+            seq: AtomicU32::new(0),
+        }
+    }
+
+    /// Unlocks the mutex and blocks the current thread until it's notified; then locks the mutex
+    /// again
+    ///
+    /// NOTE this function is susceptible to spurious wake ups so it should be called in a loop
+    /// that checks the condition
+    pub fn wait<'a, T>(&self, guard: MutexGuard<'a, T>) -> MutexGuard<'a, T> {
+        let mutex = guard.mutex;
+        let seq = self.seq.load(Ordering::Relaxed);
+
+        drop(guard);
+
+        // if a notification arrived after the unlock `seq` changed and this returns right away
+        wait(&self.seq, seq);
+
+        if mutex.pi {
+            mutex.lock()
+        } else {
+            // other threads may have been woken up as well
+            mutex.lock_contended();
+            MutexGuard { mutex }
+        }
+    }
+
+    /// Wakes up one of the threads blocked on this condition variable
+    pub fn notify_one(&self) {
+        self.seq.fetch_add(1, Ordering::Relaxed);
+        wake(&self.seq, 1);
+    }
+
+    /// Wakes up all the threads blocked on this condition variable
+    pub fn notify_all(&self) {
+        self.seq.fetch_add(1, Ordering::Relaxed);
+        wake(&self.seq, i32::max_value());
+    }
+}
+
+/// A synchronization primitive used to run one-time initialization
+pub struct Once {
+    state: AtomicU32,
+}
+
+// states of `Once`
+const INCOMPLETE: u32 = 0;
+const RUNNING: u32 = 1;
+// `RUNNING` and other threads are waiting
+const RUNNING_WAITERS: u32 = 2;
+const COMPLETE: u32 = 3;
+
+impl Once {
+    /// Creates a new `Once` value
+    pub const fn new() -> Self {
+        Once {
+            state: AtomicU32::new(INCOMPLETE),
+        }
+    }
+
+    /// Runs the closure `f` if this is the first call to `call_once`
+    ///
+    /// Other callers block until `f` has completed
+    pub fn call_once<F>(&self, f: F)
+    where
+        F: FnOnce(),
+    {
+        match self
+            .state
+            .compare_exchange(INCOMPLETE, RUNNING, Ordering::Acquire, Ordering::Acquire)
+        {
+            Ok(_) => {
+                f();
+
+                if self.state.swap(COMPLETE, Ordering::Release) == RUNNING_WAITERS {
+                    wake(&self.state, i32::max_value());
+                }
+            }
+
+            Err(mut state) => {
+                while state != COMPLETE {
+                    if state == RUNNING {
+                        // tell the running thread that it must wake us up
+                        self.state
+                            .compare_exchange(
+                                RUNNING,
+                                RUNNING_WAITERS,
+                                Ordering::Relaxed,
+                                Ordering::Relaxed,
+                            )
+                            .ok();
+                    }
+
+                    wait(&self.state, RUNNING_WAITERS);
+                    state = self.state.load(Ordering::Acquire);
+                }
+            }
+        }
+    }
+
+    /// Returns `true` if a `call_once` call has completed
+    pub fn is_completed(&self) -> bool {
+        self.state.load(Ordering::Acquire) == COMPLETE
+    }
+}
+
+/// A barrier enables multiple threads to synchronize the beginning of some computation
+pub struct Barrier {
+    // number of threads that have arrived in the current generation
+    count: AtomicU32,
+    generation: AtomicU32,
+    n: u32,
+}
+
+impl Barrier {
+    /// Creates a new barrier that blocks `n` threads
+    pub const fn new(n: u32) -> Self {
+        Barrier {
+            count: AtomicU32::new(0),
+            generation: AtomicU32::new(0),
+            n,
+        }
+    }
+
+    /// Blocks the current thread until all `n` threads have called `wait`
+    ///
+    /// Returns `true` in exactly one of the threads, the "leader". The barrier can be reused
+    /// afterwards
+    pub fn wait(&self) -> bool {
+        let generation = self.generation.load(Ordering::Acquire);
+
+        if self.count.fetch_add(1, Ordering::AcqRel) + 1 >= self.n {
+            // NOTE the count must be reset before the waiters are released
+            self.count.store(0, Ordering::Relaxed);
+            self.generation.fetch_add(1, Ordering::Release);
+            wake(&self.generation, i32::max_value());
+
+            true
+        } else {
+            while self.generation.load(Ordering::Acquire) == generation {
+                wait(&self.generation, generation);
+            }
+
+            false
+        }
+    }
+}
+
+/// Blocks the current thread while `futex` contains the value `expected`
+///
+/// This function may return spuriously (e.g. when the thread is interrupted by a signal)
+pub fn wait(futex: &AtomicU32, expected: u32) {
+    unsafe {
+        linux_sys::futex(
+            addr(futex),
+            FUTEX_WAIT | FUTEX_PRIVATE_FLAG,
+            expected,
+            ptr::null(),
+            ptr::null(),
+            0,
+        )
+        .ok();
+    }
+}
+
+/// Wakes up to `n` threads blocked on `futex`
+pub fn wake(futex: &AtomicU32, n: i32) {
+    unsafe {
+        linux_sys::futex(
+            addr(futex),
+            FUTEX_WAKE | FUTEX_PRIVATE_FLAG,
+            n as u32,
+            ptr::null(),
+            ptr::null(),
+            0,
+        )
+        .ok();
+    }
+}
+
+fn addr(futex: &AtomicU32) -> *const u32 {
+    futex as *const AtomicU32 as *const u32
+}
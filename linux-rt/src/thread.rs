@@ -0,0 +1,307 @@
+//! Threads
+
+use core::{
+    cell::UnsafeCell,
+    marker::PhantomData,
+    mem::{self, MaybeUninit},
+    ptr,
+    sync::atomic::{AtomicI32, AtomicU8, Ordering},
+};
+
+use linux_io::export::fatal;
+use linux_sys::{
+    cty::{c_int, c_ulong},
+    pid_t, sched_param, Error, SigSet,
+};
+
+// `$ getconf PAGE_SIZE`
+const PAGE_SIZE: usize = 4 * 1024;
+
+// states of a thread
+const RUNNING: u8 = 0;
+// the `JoinHandle` was dropped
+const DETACHED: u8 = 1;
+// the closure returned
+const FINISHED: u8 = 2;
+
+/// Spawns a new thread with a stack of (at least) `stack_size` bytes that runs the closure `f`
+///
+/// This is equivalent to `Builder::new(stack_size).spawn(f)`
+pub fn spawn<F, T>(stack_size: usize, f: F) -> Result<JoinHandle<T>, Error>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    Builder::new(stack_size).spawn(f)
+}
+
+/// Thread factory, used to configure the properties of a new thread
+pub struct Builder {
+    affinity: Option<[c_ulong; 8]>,
+    scheduler: Option<(c_int, sched_param)>,
+    stack_size: usize,
+}
+
+impl Builder {
+    /// Creates a builder for threads with a stack of (at least) `stack_size` bytes
+    ///
+    /// New threads inherit the CPU affinity and scheduling policy of the calling thread unless
+    /// they are set with the other methods of the builder
+    pub fn new(stack_size: usize) -> Self {
+        Builder {
+            affinity: None,
+            scheduler: None,
+            stack_size,
+        }
+    }
+
+    /// Sets the CPU affinity mask of the new thread
+    ///
+    /// See `man 2 sched_setaffinity` for more details
+    pub fn affinity(mut self, mask: [c_ulong; 8]) -> Self {
+        self.affinity = Some(mask);
+        self
+    }
+
+    /// Sets the scheduling policy (`SCHED_*`) and priority of the new thread
+    ///
+    /// See `man 2 sched_setscheduler` for more details
+    pub fn scheduler(mut self, policy: c_int, priority: c_int) -> Self {
+        self.scheduler = Some((
+            policy,
+            sched_param {
+                sched_priority: priority,
+            },
+        ));
+        self
+    }
+
+    /// Spawns a new thread that runs the closure `f`
+    ///
+    /// The new thread shares memory, file descriptors and signal handlers with the calling thread.
+    /// Its stack is unmapped once it has been joined or, if its `JoinHandle` was dropped, once it
+    /// exits
+    ///
+    /// NOTE if the new thread can't change its CPU affinity or scheduling policy it exits the
+    /// process with a non-zero code
+    pub fn spawn<F, T>(self, f: F) -> Result<JoinHandle<T>, Error>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        // the `Packet` goes at the top of the mapping; the stack grows downwards from it
+        let size = mem::size_of::<Packet<F, T>>() + mem::align_of::<Packet<F, T>>();
+        let len = (self.stack_size + size + PAGE_SIZE - 1) / PAGE_SIZE * PAGE_SIZE;
+
+        unsafe {
+            let low = linux_sys::mmap(
+                0,          // address; 0 means any page-aligned address
+                len as u64, // length of mapping
+                linux_sys::PROT_READ | // read access
+                linux_sys::PROT_WRITE, // write access
+                linux_sys::MAP_ANONYMOUS | // mapping is not backed by any file
+                linux_sys::MAP_PRIVATE | // mapping is private to other threads / processes
+                linux_sys::MAP_GROWSDOWN, // mapping suitable for stacks
+                !0,         // file descriptor; needs to be `-1` because of MAP_ANONYMOUS
+                0,          // offset; ignored because of MAP_ANONYMOUS
+            )? as usize;
+
+            let packet = (low + len - mem::size_of::<Packet<F, T>>())
+                & !(mem::align_of::<Packet<F, T>>() - 1);
+            let packet = packet as *mut Packet<F, T>;
+            ptr::write(
+                packet,
+                Packet {
+                    tid: AtomicI32::new(0),
+                    state: AtomicU8::new(RUNNING),
+                    low,
+                    len,
+                    result: UnsafeCell::new(MaybeUninit::uninit()),
+                    affinity: self.affinity,
+                    scheduler: self.scheduler,
+                    f: UnsafeCell::new(MaybeUninit::new(f)),
+                },
+            );
+
+            // the stack must be 16-byte aligned
+            let stack_high = packet as usize & !15;
+
+            let tid = linux_sys::x86_64_clone_with_arg(
+                linux_sys::CLONE_VM | // new thread shares memory with the parent
+                linux_sys::CLONE_FS | // share the filesystem information (e.g. cwd)
+                linux_sys::CLONE_FILES | // share the file descriptor table
+                linux_sys::CLONE_SIGHAND | // shared signal handlers; required by `CLONE_THREAD`
+                linux_sys::CLONE_THREAD | // share thread group
+                linux_sys::CLONE_SYSVSEM | // share System V semaphore adjustments
+                linux_sys::CLONE_PARENT_SETTID | // write the TID into `packet.tid` ..
+                linux_sys::CLONE_CHILD_CLEARTID, // .. and clear it when the thread exits
+                stack_high as *mut _,
+                &(*packet).tid as *const AtomicI32 as *mut pid_t,
+                start::<F, T>,
+                packet as usize,
+            )
+            .map_err(|e| {
+                ptr::drop_in_place((*(*packet).f.get()).as_mut_ptr());
+                linux_sys::munmap(low as *mut _, len).ok();
+                e
+            })?;
+
+            Ok(JoinHandle {
+                packet: packet as *const Packet<(), T>,
+                tid,
+                _marker: PhantomData,
+            })
+        }
+    }
+}
+
+/// An owned permission to join on a thread (block on its termination)
+///
+/// Dropping the handle *detaches* the thread: it keeps running and frees its stack when it exits
+pub struct JoinHandle<T> {
+    // NOTE `Packet` is `repr(C)` and the closure is its last field so all the other fields can be
+    // accessed through a pointer that has the closure type erased
+    packet: *const Packet<(), T>,
+    tid: pid_t,
+    _marker: PhantomData<T>,
+}
+
+unsafe impl<T> Send for JoinHandle<T> where T: Send {}
+
+impl<T> JoinHandle<T> {
+    /// Returns the thread ID (TID) of the thread
+    pub fn tid(&self) -> pid_t {
+        self.tid
+    }
+
+    /// Waits for the thread to finish and returns the value returned by its closure
+    pub fn join(self) -> T {
+        unsafe {
+            let packet = &*self.packet;
+            wait(packet);
+
+            let result = ptr::read(packet.result.get()).assume_init();
+            let (low, len) = (packet.low, packet.len);
+            mem::forget(self);
+            linux_sys::munmap(low as *mut _, len).ok();
+
+            result
+        }
+    }
+}
+
+impl<T> Drop for JoinHandle<T> {
+    fn drop(&mut self) {
+        unsafe {
+            let packet = &*self.packet;
+
+            if packet.state.swap(DETACHED, Ordering::AcqRel) == FINISHED {
+                // the thread is (about to be) gone; we have to free its resources
+                wait(packet);
+
+                ptr::drop_in_place((*packet.result.get()).as_mut_ptr());
+                let (low, len) = (packet.low, packet.len);
+                linux_sys::munmap(low as *mut _, len).ok();
+            }
+        }
+    }
+}
+
+// Shared state between a thread and its `JoinHandle`
+#[repr(C)]
+struct Packet<F, T> {
+    // TID of the thread; the kernel sets it to 0 when the thread exits
+    tid: AtomicI32,
+    state: AtomicU8,
+    // the mapping that contains the stack and this packet
+    low: usize,
+    len: usize,
+    result: UnsafeCell<MaybeUninit<T>>,
+    affinity: Option<[c_ulong; 8]>,
+    scheduler: Option<(c_int, sched_param)>,
+    // NOTE must be the last field
+    f: UnsafeCell<MaybeUninit<F>>,
+}
+
+// Blocks until the thread has exited
+unsafe fn wait<F, T>(packet: &Packet<F, T>) {
+    loop {
+        let tid = packet.tid.load(Ordering::Acquire);
+        if tid == 0 {
+            break;
+        }
+
+        // NOTE the kernel issues a (non-private) `FUTEX_WAKE` on `tid` when the thread exits
+        linux_sys::futex(
+            &packet.tid as *const AtomicI32 as *const u32,
+            linux_sys::FUTEX_WAIT,
+            tid as u32,
+            ptr::null(),
+            ptr::null(),
+            0,
+        )
+        .ok();
+    }
+}
+
+// Entry point of the new thread
+extern "C" fn start<F, T>(packet: usize) -> !
+where
+    F: FnOnce() -> T,
+{
+    unsafe {
+        let packet = &*(packet as *const Packet<F, T>);
+
+        if let Some(mask) = &packet.affinity {
+            linux_sys::sched_setaffinity(0, mask)
+                .unwrap_or_else(|_| fatal("error: couldn't change CPU affinity\n"));
+        }
+
+        if let Some((policy, param)) = &packet.scheduler {
+            linux_sys::sched_setscheduler(0, *policy, param)
+                .unwrap_or_else(|_| fatal("error: couldn't change scheduling policy\n"));
+        }
+
+        let f = ptr::read(packet.f.get()).assume_init();
+        (*packet.result.get()) = MaybeUninit::new(f());
+
+        if packet.state.swap(FINISHED, Ordering::AcqRel) == DETACHED {
+            // nobody will join us; we must free our own stack
+            ptr::drop_in_place((*packet.result.get()).as_mut_ptr());
+
+            // no signal handler must run on the stack after it has been unmapped
+            linux_sys::rt_sigprocmask(linux_sys::SIG_SETMASK, &SigSet::full(), ptr::null_mut())
+                .ok();
+            // the kernel must not clear `tid` as that memory may have been reused by then
+            linux_sys::set_tid_address(ptr::null_mut());
+
+            unmap_and_exit(packet.low, packet.len)
+        } else {
+            linux_sys::exit(0)
+        }
+    }
+}
+
+// Unmaps the stack of the calling thread and then exits the thread
+//
+// This has to be written in assembly because the stack can't be used after the `munmap` call
+unsafe fn unmap_and_exit(low: usize, len: usize) -> ! {
+    extern "C" {
+        fn __unmap_and_exit(low: usize, len: usize) -> !;
+    }
+
+    __unmap_and_exit(low, len)
+}
+
+global_asm!(
+    r#"
+  .global __unmap_and_exit
+  .section .text.__unmap_and_exit
+__unmap_and_exit:
+  mov    $0xb,%eax
+  syscall
+  xor    %edi,%edi
+  mov    $0x3c,%eax
+  syscall
+"#
+);
@@ -1,6 +1,17 @@
-use cty::{c_int, c_ulong, size_t};
+use cty::{c_int, c_uint, c_ulong, size_t};
 
-use crate::types::clockid_t;
+use crate::types::{clockid_t, umode_t};
+
+/* 8. lseek */
+// from: include/uapi/linux/fs.h
+/// Seek from beginning of file
+pub const SEEK_SET: c_uint = 0;
+
+/// Seek from current position
+pub const SEEK_CUR: c_uint = 1;
+
+/// Seek from end of file
+pub const SEEK_END: c_uint = 2;
 
 /* 9. mmap */
 // from:
@@ -50,6 +61,42 @@ pub const SIG_IGN: size_t = 1;
 /// Smallest real-time signal
 pub const SIGRTMIN: c_int = 32;
 
+/// Largest real-time signal
+pub const SIGRTMAX: c_int = 64;
+
+/// Illegal instruction
+pub const SIGILL: c_int = 4;
+
+/// Trace / breakpoint trap
+pub const SIGTRAP: c_int = 5;
+
+/// Abort signal (`abort`)
+pub const SIGABRT: c_int = 6;
+
+/// Bus error (bad memory access)
+pub const SIGBUS: c_int = 7;
+
+/// Floating-point exception
+pub const SIGFPE: c_int = 8;
+
+/// Kill signal; can't be caught, blocked or ignored
+pub const SIGKILL: c_int = 9;
+
+/// Invalid memory reference
+pub const SIGSEGV: c_int = 11;
+
+/// Termination signal
+pub const SIGTERM: c_int = 15;
+
+/// Child stopped or terminated
+pub const SIGCHLD: c_int = 17;
+
+/// Pollable event; synonym of `SIGIO`
+pub const SIGPOLL: c_int = 29;
+
+/// Bad system call
+pub const SIGSYS: c_int = 31;
+
 /* 14. sigprocmask */
 /// Additionally block these signals
 pub const SIG_BLOCK: c_int = 0;
@@ -71,13 +118,129 @@ pub const CLONE_THREAD: c_ulong = 0x00010000;
 /// Set if handlers and blocked signals shared
 pub const CLONE_SIGHAND: c_ulong = 0x00000800;
 
+/// Set if fs info shared between processes
+pub const CLONE_FS: c_ulong = 0x00000200;
+
+/// Set if open files shared between processes
+pub const CLONE_FILES: c_ulong = 0x00000400;
+
+/// Share system V SEM_UNDO semantics
+pub const CLONE_SYSVSEM: c_ulong = 0x00040000;
+
+/// Set the TID in the parent
+pub const CLONE_PARENT_SETTID: c_ulong = 0x00100000;
+
+/// Clear the TID in the child
+pub const CLONE_CHILD_CLEARTID: c_ulong = 0x00200000;
+
+/// Set if the parent wants the child to wake it up on `exit` / `execve`
+pub const CLONE_VFORK: c_ulong = 0x00004000;
+
+/* 61. wait4, 247. waitid */
+// from: include/uapi/linux/wait.h
+/// Don't block if no child has changed state
+pub const WNOHANG: c_int = 0x00000001;
+
+/// Also report children that have been stopped (by a signal)
+pub const WSTOPPED: c_int = 0x00000002;
+
+/// Report children that have terminated; `waitid` only
+pub const WEXITED: c_int = 0x00000004;
+
+/// Also report stopped children that have been resumed by `SIGCONT`
+pub const WCONTINUED: c_int = 0x00000008;
+
+/// Leave the child in a waitable state; `waitid` only
+pub const WNOWAIT: c_int = 0x01000000;
+
+/// Wait for any child; `waitid` only
+pub const P_ALL: c_int = 0;
+
+/// Wait for the child whose PID matches; `waitid` only
+pub const P_PID: c_int = 1;
+
+/// Wait for any child whose process group ID matches; `waitid` only
+pub const P_PGID: c_int = 2;
+
+/* 72. fcntl */
+// from: include/uapi/asm-generic/fcntl.h
+/// Get file descriptor flags
+pub const F_GETFD: c_uint = 1;
+
+/// Set file descriptor flags
+pub const F_SETFD: c_uint = 2;
+
+/// Get file status flags
+pub const F_GETFL: c_uint = 3;
+
+/// Set file status flags
+pub const F_SETFL: c_uint = 4;
+
+/// Close the file descriptor on `execve`; file descriptor flag
+pub const FD_CLOEXEC: c_int = 1;
+
 /* 129. rt_sigqueueinfo */
+// from: include/uapi/asm-generic/siginfo.h
+/// Sent by kill, sigsend, raise
+pub const SI_USER: c_int = 0;
+
+/// Sent by the kernel from somewhere
+pub const SI_KERNEL: c_int = 0x80;
+
 /// Signal issued by rt_sigqueueinfo
 pub const SI_QUEUE: c_int = -1;
 /// Sent by timer expiration
 #[cfg(not(target_arch = "mips"))]
 pub const SI_TIMER: c_int = -2;
 
+/// Sent by real time mesq state change
+pub const SI_MESGQ: c_int = -3;
+
+/// Sent by AIO completion
+pub const SI_ASYNCIO: c_int = -4;
+
+/// Sent by queued SIGIO
+pub const SI_SIGIO: c_int = -5;
+
+/// Sent by tkill system call
+pub const SI_TKILL: c_int = -6;
+
+/// Child has exited
+pub const CLD_EXITED: c_int = 1;
+
+/// Child was killed
+pub const CLD_KILLED: c_int = 2;
+
+/// Child terminated abnormally
+pub const CLD_DUMPED: c_int = 3;
+
+/// Traced child has trapped
+pub const CLD_TRAPPED: c_int = 4;
+
+/// Child has stopped
+pub const CLD_STOPPED: c_int = 5;
+
+/// Stopped child has continued
+pub const CLD_CONTINUED: c_int = 6;
+
+/// Data input available
+pub const POLL_IN: c_int = 1;
+
+/// Output buffers available
+pub const POLL_OUT: c_int = 2;
+
+/// Input message available
+pub const POLL_MSG: c_int = 3;
+
+/// I/O error
+pub const POLL_ERR: c_int = 4;
+
+/// High priority input available
+pub const POLL_PRI: c_int = 5;
+
+/// Device disconnected
+pub const POLL_HUP: c_int = 6;
+
 /* 14*. sched_* */
 /// Standard round-robin time-sharing policy
 pub const SCHED_NORMAL: c_int = 0;
@@ -94,6 +257,44 @@ pub const SCHED_BATCH: c_int = 3;
 /// Very low priority background jobs
 pub const SCHED_IDLE: c_int = 5;
 
+/// Earliest deadline first policy with bandwidth reservation
+pub const SCHED_DEADLINE: c_int = 6;
+
+/// Flag ORed into the policy: children created by `fork` / `clone` do not inherit privileged
+/// scheduling policies
+pub const SCHED_RESET_ON_FORK: c_int = 0x4000_0000;
+
+/* 314. sched_setattr */
+// from include/uapi/linux/sched.h
+/// Children created by `fork` do not inherit privileged scheduling policies
+pub const SCHED_FLAG_RESET_ON_FORK: u64 = 0x01;
+
+/// Let a `SCHED_DEADLINE` thread reclaim bandwidth unused by others
+pub const SCHED_FLAG_RECLAIM: u64 = 0x02;
+
+/// Send `SIGXCPU` to a `SCHED_DEADLINE` thread that overruns its runtime
+pub const SCHED_FLAG_DL_OVERRUN: u64 = 0x04;
+
+/* 202. futex */
+// from include/uapi/linux/futex.h
+/// Sleep if the futex word still contains the expected value
+pub const FUTEX_WAIT: c_int = 0;
+
+/// Wake up the waiters of the futex word
+pub const FUTEX_WAKE: c_int = 1;
+
+/// Priority inheritance lock
+pub const FUTEX_LOCK_PI: c_int = 6;
+
+/// Priority inheritance unlock
+pub const FUTEX_UNLOCK_PI: c_int = 7;
+
+/// Priority inheritance lock that doesn't block
+pub const FUTEX_TRYLOCK_PI: c_int = 8;
+
+/// The futex word is not shared with other processes
+pub const FUTEX_PRIVATE_FLAG: c_int = 128;
+
 /* 223. timer_settime */
 /// `timer_settime` uses absolute time
 pub const TIMER_ABSTIME: c_int = 1;
@@ -112,6 +313,9 @@ pub const SIGEV_THREAD_ID: c_int = 4;
 
 /* 22*. clock_* */
 // from include/uapi/linux/time.h
+/// System-wide wall-clock time; it can jump when the clock is set (e.g. by `clock_settime`)
+pub const CLOCK_REALTIME: clockid_t = 0;
+
 /// Monotonic timer
 pub const CLOCK_MONOTONIC: clockid_t = 1;
 
@@ -120,3 +324,260 @@ pub const CLOCK_MONOTONIC_RAW: clockid_t = 4;
 
 /// A faster but less precise version of CLOCK_MONOTONIC
 pub const CLOCK_MONOTONIC_COARSE: clockid_t = 6;
+
+/// Like CLOCK_MONOTONIC but it also counts the time the system was suspended
+pub const CLOCK_BOOTTIME: clockid_t = 7;
+
+/// International Atomic Time
+pub const CLOCK_TAI: clockid_t = 11;
+
+/* 257. openat */
+// from:
+// - include/uapi/asm-generic/fcntl.h
+// - include/uapi/linux/fcntl.h
+// - include/uapi/linux/stat.h
+/// Open for reading only
+pub const O_RDONLY: c_int = 0o0;
+
+/// Open for writing only
+pub const O_WRONLY: c_int = 0o1;
+
+/// Open for reading and writing
+pub const O_RDWR: c_int = 0o2;
+
+/// Create the file if it doesn't exist
+pub const O_CREAT: c_int = 0o100;
+
+/// Fail if the file already exists; used with `O_CREAT`
+pub const O_EXCL: c_int = 0o200;
+
+/// Don't make the file the controlling terminal
+pub const O_NOCTTY: c_int = 0o400;
+
+/// Truncate the file to length 0
+pub const O_TRUNC: c_int = 0o1000;
+
+/// Append on each write
+pub const O_APPEND: c_int = 0o2000;
+
+/// Non-blocking mode
+pub const O_NONBLOCK: c_int = 0o4000;
+
+/// Fail if the path is not a directory
+pub const O_DIRECTORY: c_int = 0o200000;
+
+/// Don't follow symbolic links
+pub const O_NOFOLLOW: c_int = 0o400000;
+
+/// Close the file descriptor on `execve`
+pub const O_CLOEXEC: c_int = 0o2000000;
+
+/// Use the current working directory as the directory file descriptor
+pub const AT_FDCWD: c_int = -100;
+
+/// Don't follow symbolic links
+pub const AT_SYMLINK_NOFOLLOW: c_int = 0x100;
+
+/// Remove directory instead of unlinking file; used with `unlinkat`
+pub const AT_REMOVEDIR: c_int = 0x200;
+
+/// Operate on the file descriptor if the path is empty
+pub const AT_EMPTY_PATH: c_int = 0x1000;
+
+/// Type of file mask
+pub const S_IFMT: umode_t = 0o170000;
+
+/// Socket
+pub const S_IFSOCK: umode_t = 0o140000;
+
+/// Symbolic link
+pub const S_IFLNK: umode_t = 0o120000;
+
+/// Regular file
+pub const S_IFREG: umode_t = 0o100000;
+
+/// Block device
+pub const S_IFBLK: umode_t = 0o060000;
+
+/// Directory
+pub const S_IFDIR: umode_t = 0o040000;
+
+/// Character device
+pub const S_IFCHR: umode_t = 0o020000;
+
+/// FIFO
+pub const S_IFIFO: umode_t = 0o010000;
+
+/* 332. statx */
+// from: include/uapi/linux/stat.h
+/// Want / got `stx_mode & S_IFMT`
+pub const STATX_TYPE: c_uint = 0x0000_0001;
+
+/// Want / got `stx_mode & !S_IFMT`
+pub const STATX_MODE: c_uint = 0x0000_0002;
+
+/// Want / got `stx_size`
+pub const STATX_SIZE: c_uint = 0x0000_0200;
+
+/// The stuff in the normal `stat` struct
+pub const STATX_BASIC_STATS: c_uint = 0x0000_07ff;
+
+/// Want / got `stx_btime`
+pub const STATX_BTIME: c_uint = 0x0000_0800;
+
+/* 41. socket */
+// from:
+// - include/linux/socket.h
+// - include/linux/net.h
+/// Unix domain sockets
+pub const AF_UNIX: c_int = 1;
+
+/// IPv4 Internet protocols
+pub const AF_INET: c_int = 2;
+
+/// IPv6 Internet protocols
+pub const AF_INET6: c_int = 10;
+
+/// Sequenced, reliable, connection-based byte streams
+pub const SOCK_STREAM: c_int = 1;
+
+/// Connectionless, unreliable messages of a fixed maximum length
+pub const SOCK_DGRAM: c_int = 2;
+
+/// Sequenced, reliable, connection-based messages of a fixed maximum length
+pub const SOCK_SEQPACKET: c_int = 5;
+
+/// Set `O_NONBLOCK` on the new file descriptor; `socket`, `socketpair` and `accept4` flag
+pub const SOCK_NONBLOCK: c_int = O_NONBLOCK;
+
+/// Set `O_CLOEXEC` on the new file descriptor; `socket`, `socketpair` and `accept4` flag
+pub const SOCK_CLOEXEC: c_int = O_CLOEXEC;
+
+/* 54. setsockopt, 55. getsockopt */
+// from:
+// - include/uapi/asm-generic/socket.h
+// - include/linux/socket.h
+// - include/uapi/linux/in.h
+// - include/uapi/linux/tcp.h
+/// Socket level options
+pub const SOL_SOCKET: c_int = 1;
+
+/// Allow reuse of local addresses; `int` option
+pub const SO_REUSEADDR: c_int = 2;
+
+/// Receive buffer size, in bytes; `int` option
+///
+/// NOTE the kernel doubles the value that's set to account for its bookkeeping overhead
+pub const SO_RCVBUF: c_int = 8;
+
+/// Protocol-defined priority of the packets sent on this socket; `int` option
+///
+/// Values outside the range `0..=6` require the `CAP_NET_ADMIN` capability
+pub const SO_PRIORITY: c_int = 12;
+
+/// Report the reception time of packets, as a `timespec`, in a control message; `int` option
+pub const SO_TIMESTAMPNS: c_int = 35;
+
+/// Type of the control message that `SO_TIMESTAMPNS` enables
+pub const SCM_TIMESTAMPNS: c_int = SO_TIMESTAMPNS;
+
+/// TCP level options
+pub const IPPROTO_TCP: c_int = 6;
+
+/// Don't delay sending segments to coalesce small writes (Nagle's algorithm); `int` option
+pub const TCP_NODELAY: c_int = 1;
+
+/* 46. sendmsg, 47. recvmsg */
+// from: include/linux/socket.h
+/// Return data without removing it from the receive queue
+pub const MSG_PEEK: c_int = 0x2;
+
+/// The datagram was truncated because it didn't fit in the supplied buffer
+pub const MSG_TRUNC: c_int = 0x20;
+
+/// Nonblocking I/O for this call only
+pub const MSG_DONTWAIT: c_int = 0x40;
+
+/// Block until the full request is satisfied
+pub const MSG_WAITALL: c_int = 0x100;
+
+/// Don't raise `SIGPIPE` if the peer has closed the connection; `EPIPE` is still returned
+pub const MSG_NOSIGNAL: c_int = 0x4000;
+
+/* auxiliary vector */
+// from include/uapi/linux/auxvec.h and arch/x86/include/uapi/asm/auxvec.h
+/// End of the vector
+pub const AT_NULL: c_ulong = 0;
+
+/// Entry should be ignored
+pub const AT_IGNORE: c_ulong = 1;
+
+/// File descriptor of the program
+pub const AT_EXECFD: c_ulong = 2;
+
+/// Program headers of the program
+pub const AT_PHDR: c_ulong = 3;
+
+/// Size of a program header entry
+pub const AT_PHENT: c_ulong = 4;
+
+/// Number of program headers
+pub const AT_PHNUM: c_ulong = 5;
+
+/// System page size
+pub const AT_PAGESZ: c_ulong = 6;
+
+/// Base address of the interpreter
+pub const AT_BASE: c_ulong = 7;
+
+/// Flags
+pub const AT_FLAGS: c_ulong = 8;
+
+/// Entry point of the program
+pub const AT_ENTRY: c_ulong = 9;
+
+/// Program is not ELF
+pub const AT_NOTELF: c_ulong = 10;
+
+/// Real UID
+pub const AT_UID: c_ulong = 11;
+
+/// Effective UID
+pub const AT_EUID: c_ulong = 12;
+
+/// Real GID
+pub const AT_GID: c_ulong = 13;
+
+/// Effective GID
+pub const AT_EGID: c_ulong = 14;
+
+/// Address of a string that identifies the CPU
+pub const AT_PLATFORM: c_ulong = 15;
+
+/// Architecture dependent hints about the processor capabilities
+pub const AT_HWCAP: c_ulong = 16;
+
+/// Frequency at which `times` counts
+pub const AT_CLKTCK: c_ulong = 17;
+
+/// Secure mode boolean
+pub const AT_SECURE: c_ulong = 23;
+
+/// Address of a string that identifies the real platform
+pub const AT_BASE_PLATFORM: c_ulong = 24;
+
+/// Address of 16 random bytes
+pub const AT_RANDOM: c_ulong = 25;
+
+/// Extension of `AT_HWCAP`
+pub const AT_HWCAP2: c_ulong = 26;
+
+/// Address of the filename of the program
+pub const AT_EXECFN: c_ulong = 31;
+
+/// Address of the vDSO
+pub const AT_SYSINFO_EHDR: c_ulong = 33;
+
+/// Minimal stack size for signal delivery
+#[cfg(target_arch = "x86_64")]
+pub const AT_MINSIGSTKSZ: c_ulong = 51;
@@ -11,10 +11,12 @@
 #[macro_use]
 mod macros;
 mod consts;
+mod errno;
 mod types;
+pub mod vdso;
 
 use core::{
-    hint,
+    fmt, hint,
     mem::{self, MaybeUninit},
     ptr,
 };
@@ -29,6 +31,20 @@ pub use types::*;
 
 // System calls ordered by their (x86_64) "number"
 
+// NR = 0
+/// Read from a file descriptor
+///
+/// See `man 2 read` for more details
+///
+/// Source: https://github.com/torvalds/linux/blob/v5.0/fs/read_write.c#L588
+///
+/// C signature: `ssize_t read(unsigned int fd, char *buf, size_t count)`
+pub unsafe fn read(fd: c_uint, buf: &mut [u8]) -> Result<usize, Error> {
+    let count: size_t = buf.len();
+    let buf = buf.as_mut_ptr() as *mut c_char;
+    check!(syscall!(READ, fd, buf, count)).map(|ret| ret as usize)
+}
+
 // NR = 1
 /// Write to a file descriptor
 ///
@@ -43,6 +59,48 @@ pub unsafe fn write(fd: c_uint, buf: &[u8]) -> Result<usize, Error> {
     check!(syscall!(WRITE, fd, buf, count)).map(|ret| ret as usize)
 }
 
+// NR = 3
+/// Close a file descriptor
+///
+/// See `man 2 close` for more details
+///
+/// Source: https://github.com/torvalds/linux/blob/v5.0/fs/open.c#L1191
+///
+/// C signature: `int close(unsigned int fd)`
+pub unsafe fn close(fd: c_uint) -> Result<(), Error> {
+    check!(syscall!(CLOSE, fd)).map(|ret| debug_assert_eq!(ret, 0))
+}
+
+// NR = 5
+/// Get file status
+///
+/// See `man 2 fstat` for more details
+///
+/// Source: https://github.com/torvalds/linux/blob/v5.0/fs/stat.c#L335
+///
+/// C signature: `int fstat(unsigned int fd, struct stat *statbuf)`
+pub fn fstat(fd: c_uint) -> Result<stat, Error> {
+    unsafe {
+        let mut statbuf = MaybeUninit::<stat>::uninit();
+        check!(syscall!(FSTAT, fd, statbuf.as_mut_ptr())).map(move |ret| {
+            debug_assert_eq!(ret, 0);
+            statbuf.assume_init()
+        })
+    }
+}
+
+// NR = 8
+/// Reposition read/write file offset
+///
+/// See `man 2 lseek` for more details
+///
+/// Source: https://github.com/torvalds/linux/blob/v5.0/fs/read_write.c#L324
+///
+/// C signature: `off_t lseek(unsigned int fd, off_t offset, unsigned int whence)`
+pub unsafe fn lseek(fd: c_uint, offset: off_t, whence: c_uint) -> Result<off_t, Error> {
+    check!(syscall!(LSEEK, fd, offset, whence)).map(|ret| ret as off_t)
+}
+
 // NR = 9
 /// Map files or devices into memory
 ///
@@ -73,6 +131,18 @@ pub unsafe fn mmap(
     check!(syscall!(MMAP, addr, len, prot, flags, fd, off)).map(|ret| ret as *mut c_void)
 }
 
+// NR = 11
+/// Unmap files or devices from memory
+///
+/// See `man 2 munmap` for more details
+///
+/// Source: https://github.com/torvalds/linux/blob/v5.0/mm/mmap.c#L2858
+///
+/// C signature: `int munmap(unsigned long addr, size_t len)`
+pub unsafe fn munmap(addr: *mut c_void, len: size_t) -> Result<(), Error> {
+    check!(syscall!(MUNMAP, addr, len)).map(|ret| debug_assert_eq!(ret, 0))
+}
+
 // NR = 13
 /// Examine and change a signal action
 ///
@@ -91,7 +161,7 @@ pub unsafe fn rt_sigaction(
         sig,
         act,
         oact,
-        mem::size_of::<sigset_t>()
+        mem::size_of::<SigSet>()
     ))
     .map(|ret| {
         debug_assert_eq!(ret, 0);
@@ -112,21 +182,49 @@ pub unsafe fn rt_sigaction(
 /// ```
 pub unsafe fn rt_sigprocmask(
     how: c_int,
-    nset: *const sigset_t,
-    oset: *mut sigset_t,
+    nset: *const SigSet,
+    oset: *mut SigSet,
 ) -> Result<(), Error> {
     check!(syscall!(
         RT_SIGPROCMASK,
         how,
         nset,
         oset,
-        mem::size_of::<sigset_t>()
+        mem::size_of::<SigSet>()
     ))
     .map(|ret| {
         debug_assert_eq!(ret, 0);
     })
 }
 
+// NR = 17
+/// Read from a file descriptor at a given offset
+///
+/// See `man 2 pread` for more details
+///
+/// Source: https://github.com/torvalds/linux/blob/v5.0/fs/read_write.c#L640
+///
+/// C signature: `ssize_t pread64(unsigned int fd, char *buf, size_t count, loff_t pos)`
+pub unsafe fn pread(fd: c_uint, buf: &mut [u8], pos: off_t) -> Result<usize, Error> {
+    let count: size_t = buf.len();
+    let buf = buf.as_mut_ptr() as *mut c_char;
+    check!(syscall!(PREAD64, fd, buf, count, pos)).map(|ret| ret as usize)
+}
+
+// NR = 18
+/// Write to a file descriptor at a given offset
+///
+/// See `man 2 pwrite` for more details
+///
+/// Source: https://github.com/torvalds/linux/blob/v5.0/fs/read_write.c#L660
+///
+/// C signature: `ssize_t pwrite64(unsigned int fd, const char *buf, size_t count, loff_t pos)`
+pub unsafe fn pwrite(fd: c_uint, buf: &[u8], pos: off_t) -> Result<usize, Error> {
+    let count: size_t = buf.len();
+    let buf = buf.as_ptr() as *const c_char;
+    check!(syscall!(PWRITE64, fd, buf, count, pos)).map(|ret| ret as usize)
+}
+
 // NR = 24
 /// Yield the processor
 ///
@@ -166,6 +264,182 @@ pub fn getpid() -> pid_t {
     unsafe { syscall!(GETPID) as pid_t }
 }
 
+// NR = 41
+/// Create an endpoint for communication
+///
+/// See `man 2 socket` for more details
+///
+/// Source: https://github.com/torvalds/linux/blob/v5.0/net/socket.c
+///
+/// C signature: `int socket(int family, int type, int protocol)`
+pub fn socket(family: c_int, type_: c_int, protocol: c_int) -> Result<c_uint, Error> {
+    unsafe { check!(syscall!(SOCKET, family, type_, protocol)).map(|ret| ret as c_uint) }
+}
+
+// NR = 42
+/// Initiate a connection on a socket
+///
+/// `addr` must point to a socket address (e.g. `sockaddr_un`) that's `addrlen` bytes long
+///
+/// See `man 2 connect` for more details
+///
+/// Source: https://github.com/torvalds/linux/blob/v5.0/net/socket.c
+///
+/// C signature: `int connect(int fd, struct sockaddr *uservaddr, int addrlen)`
+pub unsafe fn connect(fd: c_uint, addr: *const sockaddr, addrlen: socklen_t) -> Result<(), Error> {
+    check!(syscall!(CONNECT, fd, addr, addrlen)).map(|ret| debug_assert_eq!(ret, 0))
+}
+
+// NR = 46
+/// Send a message on a socket
+///
+/// All the pointers in `msg` must be valid
+///
+/// See `man 2 sendmsg` for more details
+///
+/// Source: https://github.com/torvalds/linux/blob/v5.0/net/socket.c
+///
+/// C signature: `long sendmsg(int fd, struct user_msghdr *msg, unsigned int flags)`
+pub unsafe fn sendmsg(fd: c_uint, msg: &msghdr, flags: c_int) -> Result<usize, Error> {
+    check!(syscall!(SENDMSG, fd, msg as *const msghdr, flags)).map(|ret| ret as usize)
+}
+
+// NR = 47
+/// Receive a message from a socket
+///
+/// All the pointers in `msg` must be valid; the kernel updates `msg_namelen`, `msg_controllen`
+/// and `msg_flags`
+///
+/// See `man 2 recvmsg` for more details
+///
+/// Source: https://github.com/torvalds/linux/blob/v5.0/net/socket.c
+///
+/// C signature: `long recvmsg(int fd, struct user_msghdr *msg, unsigned int flags)`
+pub unsafe fn recvmsg(fd: c_uint, msg: &mut msghdr, flags: c_int) -> Result<usize, Error> {
+    check!(syscall!(RECVMSG, fd, msg as *mut msghdr, flags)).map(|ret| ret as usize)
+}
+
+// NR = 49
+/// Bind a name to a socket
+///
+/// `addr` must point to a socket address (e.g. `sockaddr_un`) that's `addrlen` bytes long
+///
+/// See `man 2 bind` for more details
+///
+/// Source: https://github.com/torvalds/linux/blob/v5.0/net/socket.c
+///
+/// C signature: `int bind(int fd, struct sockaddr *umyaddr, int addrlen)`
+pub unsafe fn bind(fd: c_uint, addr: *const sockaddr, addrlen: socklen_t) -> Result<(), Error> {
+    check!(syscall!(BIND, fd, addr, addrlen)).map(|ret| debug_assert_eq!(ret, 0))
+}
+
+// NR = 50
+/// Listen for connections on a socket
+///
+/// See `man 2 listen` for more details
+///
+/// Source: https://github.com/torvalds/linux/blob/v5.0/net/socket.c
+///
+/// C signature: `int listen(int fd, int backlog)`
+pub fn listen(fd: c_uint, backlog: c_int) -> Result<(), Error> {
+    unsafe { check!(syscall!(LISTEN, fd, backlog)).map(|ret| debug_assert_eq!(ret, 0)) }
+}
+
+// NR = 51
+/// Get socket name
+///
+/// `addr` must point to a buffer of `*addrlen` bytes; the address the socket is bound to is
+/// written there and `*addrlen` is updated to its actual length
+///
+/// See `man 2 getsockname` for more details
+///
+/// Source: https://github.com/torvalds/linux/blob/v5.0/net/socket.c
+///
+/// C signature: `int getsockname(int fd, struct sockaddr *usockaddr, int *usockaddr_len)`
+pub unsafe fn getsockname(
+    fd: c_uint,
+    addr: *mut sockaddr,
+    addrlen: *mut socklen_t,
+) -> Result<(), Error> {
+    check!(syscall!(GETSOCKNAME, fd, addr, addrlen)).map(|ret| debug_assert_eq!(ret, 0))
+}
+
+// NR = 53
+/// Create a pair of connected sockets
+///
+/// See `man 2 socketpair` for more details
+///
+/// Source: https://github.com/torvalds/linux/blob/v5.0/net/socket.c
+///
+/// C signature: `int socketpair(int family, int type, int protocol, int *usockvec)`
+pub fn socketpair(family: c_int, type_: c_int, protocol: c_int) -> Result<[c_uint; 2], Error> {
+    unsafe {
+        let mut usockvec = [0; 2];
+        check!(syscall!(
+            SOCKETPAIR,
+            family,
+            type_,
+            protocol,
+            usockvec.as_mut_ptr()
+        ))
+        .map(move |ret| {
+            debug_assert_eq!(ret, 0);
+            usockvec
+        })
+    }
+}
+
+// NR = 54
+/// Set options on sockets
+///
+/// `optval` must point to a value of `optlen` bytes whose type is the one `optname` expects
+///
+/// See `man 2 setsockopt` for more details
+///
+/// Source: https://github.com/torvalds/linux/blob/v5.0/net/socket.c
+///
+/// C signature:
+///
+/// ```
+/// int setsockopt(int fd, int level, int optname, char *optval, int optlen)
+/// ```
+pub unsafe fn setsockopt(
+    fd: c_uint,
+    level: c_int,
+    optname: c_int,
+    optval: *const c_void,
+    optlen: socklen_t,
+) -> Result<(), Error> {
+    check!(syscall!(SETSOCKOPT, fd, level, optname, optval, optlen))
+        .map(|ret| debug_assert_eq!(ret, 0))
+}
+
+// NR = 55
+/// Get options on sockets
+///
+/// `optval` must point to a buffer of `*optlen` bytes; the value of the option is written there
+/// and `*optlen` is updated to its actual size
+///
+/// See `man 2 getsockopt` for more details
+///
+/// Source: https://github.com/torvalds/linux/blob/v5.0/net/socket.c
+///
+/// C signature:
+///
+/// ```
+/// int getsockopt(int fd, int level, int optname, char *optval, int *optlen)
+/// ```
+pub unsafe fn getsockopt(
+    fd: c_uint,
+    level: c_int,
+    optname: c_int,
+    optval: *mut c_void,
+    optlen: *mut socklen_t,
+) -> Result<(), Error> {
+    check!(syscall!(GETSOCKOPT, fd, level, optname, optval, optlen))
+        .map(|ret| debug_assert_eq!(ret, 0))
+}
+
 // NR = 56
 /// Create a child process
 ///
@@ -268,6 +542,138 @@ __clone:
 "#
 );
 
+// NR = 56
+/// Create a thread -- interface simplified for the x86_64 architecture
+///
+/// Unlike `x86_64_clone`, `f` receives `arg` as its argument and the stack of the new thread is
+/// properly aligned. `tid` is passed to the kernel as both `parent_tidptr` and `child_tidptr` (see
+/// `CLONE_PARENT_SETTID` and `CLONE_CHILD_CLEARTID`)
+///
+/// NOTE `newsp` must be 16-byte aligned
+///
+/// See `man 2 clone` for more details
+#[cfg(target_arch = "x86_64")]
+pub unsafe fn x86_64_clone_with_arg(
+    clone_flags: c_ulong,
+    newsp: *mut c_void,
+    tid: *mut pid_t,
+    f: extern "C" fn(usize) -> !,
+    arg: usize,
+) -> Result<pid_t, Error> {
+    extern "C" {
+        fn __clone_with_arg(
+            clone_flags: c_ulong,
+            newsp: *mut c_void,
+            tid: *mut pid_t,
+            f: extern "C" fn(usize) -> !,
+            arg: usize,
+        ) -> isize;
+    }
+
+    check!(__clone_with_arg(clone_flags, newsp, tid, f, arg)).map(|ret| ret as pid_t)
+}
+
+// This assembly correspond to the following pseudo-code
+//
+// 0. `fn(clone_flags: c_ulong, mut sp: *mut usize, tid: *mut pid_t, f: usize, arg: usize)`
+// 1. `*sp.offset(-2) = f`
+// 2. `*sp.offset(-3) = arg`
+// 3. `sp = sp.offset(-3)`
+// 4-8. `sys_clone(clone_flags, sp, tid, tid, /* tls */ 0)`
+// 9-10. if we are the parent, return
+// 11-13. (child) `f(arg)`
+//
+// As in `__clone` the child starts executing right after the `syscall` instruction but with `%rsp`
+// set to `sp`. The child pops `arg` into `%rdi`, the register used for the first argument, and
+// then "returns" into `f`. At that point `%rsp` equals the original `sp` minus 8, which is where
+// the stack pointer would be right after a `call` instruction; this keeps the stack 16-byte aligned
+// as required by the ABI
+#[cfg(target_arch = "x86_64")]
+global_asm!(
+    r#"
+  .global __clone_with_arg
+  .section .text.__clone_with_arg
+__clone_with_arg:
+  mov    %rcx,-0x10(%rsi)
+  mov    %r8,-0x18(%rsi)
+  sub    $0x18,%rsi
+  mov    %rdx,%r10
+  xor    %r8d,%r8d
+  mov    $0x38,%eax
+  syscall
+  test   %rax,%rax
+  jnz    1f
+  xor    %ebp,%ebp
+  pop    %rdi
+  retq
+1:
+  retq
+"#
+);
+
+// NR = 57
+/// Create a child process
+///
+/// Returns the PID of the child to the parent and `0` to the child
+///
+/// NOTE only the calling thread is duplicated in the child; locks held by other threads remain
+/// locked forever in the child
+///
+/// See `man 2 fork` for more details
+///
+/// Source: https://github.com/torvalds/linux/blob/v5.0/kernel/fork.c
+///
+/// C signature: `pid_t fork()`
+pub unsafe fn fork() -> Result<pid_t, Error> {
+    check!(syscall!(FORK)).map(|ret| ret as pid_t)
+}
+
+// NR = 58
+/// Create a child process and block the parent until the child calls `execve` or exits
+///
+/// Returns the PID of the child to the parent and `0` to the child
+///
+/// See `man 2 vfork` for more details
+///
+/// Source: https://github.com/torvalds/linux/blob/v5.0/kernel/fork.c
+///
+/// C signature: `pid_t vfork()`
+// the child runs on the stack of the parent and nothing stops the compiler from spilling the
+// child's variables onto it, corrupting the parent's; use `x86_64_clone_with_arg` with
+// `CLONE_VM | CLONE_VFORK` and a separate stack instead
+#[cfg(unused)]
+pub unsafe fn vfork() -> Result<pid_t, Error> {
+    check!(syscall!(VFORK)).map(|ret| ret as pid_t)
+}
+
+// NR = 59
+/// Execute program
+///
+/// `filename` must be a null-terminated string; `argv` and `envp` must be null-terminated arrays of
+/// null-terminated strings
+///
+/// On success this function does not return; the calling process is replaced by the new program
+///
+/// See `man 2 execve` for more details
+///
+/// Source: https://github.com/torvalds/linux/blob/v5.0/fs/exec.c
+///
+/// C signature:
+///
+/// ```
+/// int execve(const char *filename, const char *const *argv, const char *const *envp)
+/// ```
+pub unsafe fn execve(
+    filename: *const c_char,
+    argv: *const *const c_char,
+    envp: *const *const c_char,
+) -> Error {
+    match check!(syscall!(EXECVE, filename, argv, envp)) {
+        Err(e) => e,
+        Ok(_) => hint::unreachable_unchecked(),
+    }
+}
+
 // NR = 60
 /// Terminate the calling process
 ///
@@ -282,6 +688,37 @@ pub unsafe fn exit(error_code: u8) -> ! {
     hint::unreachable_unchecked()
 }
 
+// NR = 61
+/// Wait for process to change state
+///
+/// Returns the PID of the child whose state changed and its status (see `WIFEXITED` et al. in
+/// `man 2 wait4`). With `WNOHANG` the PID is `0` if no child has changed state yet
+///
+/// NOTE resource usage (`ru`) is not collected; null is passed to the kernel
+///
+/// See `man 2 wait4` for more details
+///
+/// Source: https://github.com/torvalds/linux/blob/v5.0/kernel/exit.c
+///
+/// C signature:
+///
+/// ```
+/// pid_t wait4(pid_t upid, int *stat_addr, int options, struct rusage *ru)
+/// ```
+pub fn wait4(upid: pid_t, options: c_int) -> Result<(pid_t, c_int), Error> {
+    unsafe {
+        let mut status = 0;
+        check!(syscall!(
+            WAIT4,
+            upid,
+            &mut status as *mut c_int,
+            options,
+            ptr::null_mut::<c_void>()
+        ))
+        .map(|ret| (ret as pid_t, status))
+    }
+}
+
 // NR = 62
 /// Send signal to a process
 ///
@@ -296,6 +733,42 @@ pub unsafe fn kill(pid: pid_t, sig: c_int) -> Result<(), Error> {
     })
 }
 
+// NR = 72
+/// Manipulate file descriptor
+///
+/// See `man 2 fcntl` for more details
+///
+/// Source: https://github.com/torvalds/linux/blob/v5.0/fs/fcntl.c#L448
+///
+/// C signature: `long fcntl(unsigned int fd, unsigned int cmd, unsigned long arg)`
+pub unsafe fn fcntl(fd: c_uint, cmd: c_uint, arg: c_ulong) -> Result<c_int, Error> {
+    check!(syscall!(FCNTL, fd, cmd, arg)).map(|ret| ret as c_int)
+}
+
+// NR = 74
+/// Synchronize a file's in-core state with the storage device
+///
+/// See `man 2 fsync` for more details
+///
+/// Source: https://github.com/torvalds/linux/blob/v5.0/fs/sync.c#L219
+///
+/// C signature: `int fsync(unsigned int fd)`
+pub fn fsync(fd: c_uint) -> Result<(), Error> {
+    unsafe { check!(syscall!(FSYNC, fd)).map(|ret| debug_assert_eq!(ret, 0)) }
+}
+
+// NR = 110
+/// Get the PID of the parent process
+///
+/// See `man 2 getppid` for more details
+///
+/// Source: https://github.com/torvalds/linux/blob/v5.0/kernel/sys.c
+///
+/// C signature: `pid_t getppid()`
+pub fn getppid() -> pid_t {
+    unsafe { syscall!(GETPPID) as pid_t }
+}
+
 // NR = 127
 /// Examine pending signals
 ///
@@ -304,12 +777,12 @@ pub unsafe fn kill(pid: pid_t, sig: c_int) -> Result<(), Error> {
 /// Source: https://github.com/torvalds/linux/blob/v5.0/kernel/signal.c#2953
 ///
 /// C signature: `int rt_sigpending(sigset_t *uset, size_t sigsetsize)`
-pub unsafe fn rt_sigpending() -> Result<sigset_t, Error> {
+pub unsafe fn rt_sigpending() -> Result<SigSet, Error> {
     let mut set = MaybeUninit::uninit();
     check!(syscall!(
         RT_SIGPENDING,
         set.as_mut_ptr(),
-        mem::size_of::<sigset_t>()
+        mem::size_of::<SigSet>()
     ))
     .map(|ret| {
         debug_assert_eq!(ret, 0);
@@ -337,7 +810,7 @@ pub unsafe fn rt_sigpending() -> Result<sigset_t, Error> {
 /// ```
 /// `void exit(int error_code)`
 pub unsafe fn rt_sigtimedwait(
-    uthese: &sigset_t,
+    uthese: &SigSet,
     uinfo: *mut siginfo_t,
     uts: *const timespec,
 ) -> Result<c_int, Error> {
@@ -346,7 +819,7 @@ pub unsafe fn rt_sigtimedwait(
         uthese as *const _,
         uinfo,
         uts,
-        mem::size_of::<sigset_t>()
+        mem::size_of::<SigSet>()
     ))
     .map(|ret| ret as c_int)
 }
@@ -493,6 +966,36 @@ pub fn gettid() -> pid_t {
     unsafe { syscall!(GETTID) as pid_t }
 }
 
+// NR = 202
+/// Fast user-space locking
+///
+/// See `man 2 futex` for more details
+///
+/// Source: https://github.com/torvalds/linux/blob/v5.0/kernel/futex.c#L3612
+///
+/// C signature:
+///
+/// ```
+/// long futex(
+///     u32 *uaddr,
+///     int op,
+///     u32 val,
+///     struct timespec *utime,
+///     u32 *uaddr2,
+///     u32 val3,
+/// )
+/// ```
+pub unsafe fn futex(
+    uaddr: *const u32,
+    op: c_int,
+    val: u32,
+    utime: *const timespec,
+    uaddr2: *const u32,
+    val3: u32,
+) -> Result<usize, Error> {
+    check!(syscall!(FUTEX, uaddr, op, val, utime, uaddr2, val3)).map(|ret| ret as usize)
+}
+
 // NR = 203
 /// Set thread's CPU affinity mask
 ///
@@ -537,6 +1040,18 @@ pub unsafe fn sched_getaffinity(pid: pid_t, mask: &mut [c_ulong; 8]) -> Result<&
     })
 }
 
+// NR = 218
+/// Set pointer to thread ID
+///
+/// See `man 2 set_tid_address` for more details
+///
+/// Source: https://github.com/torvalds/linux/blob/v5.0/kernel/fork.c#L1542
+///
+/// C signature: `long set_tid_address(int *tidptr)`
+pub unsafe fn set_tid_address(tidptr: *mut pid_t) -> pid_t {
+    syscall!(SET_TID_ADDRESS, tidptr) as pid_t
+}
+
 // NR = 222
 /// Create a POSIX per-process timer
 ///
@@ -614,10 +1129,16 @@ pub fn timer_settime(
 /// Source: https://github.com/torvalds/linux/blob/v5.0/kernel/time/posix-timers.c#1032
 ///
 /// C signature: `int clock_gettime(clockid_t which_clock, struct timespec *tp)`
+///
+/// NOTE this uses the vDSO implementation, which doesn't enter the kernel, if `vdso::init` located
+/// it
 pub fn clock_gettime(which_clock: clockid_t) -> Result<timespec, Error> {
     unsafe {
         let mut tp = MaybeUninit::uninit();
-        check!(syscall!(CLOCK_GETTIME, which_clock, tp.as_mut_ptr())).map(move |ret| {
+        let ret = vdso::clock_gettime(which_clock, tp.as_mut_ptr())
+            .map(|ret| ret as usize)
+            .unwrap_or_else(|| syscall!(CLOCK_GETTIME, which_clock, tp.as_mut_ptr()));
+        check!(ret).map(move |ret| {
             debug_assert_eq!(ret, 0);
             tp.assume_init()
         })
@@ -672,6 +1193,133 @@ pub unsafe fn tgkill(tgid: pid_t, pid: pid_t, sig: c_int) -> Result<(), Error> {
     })
 }
 
+// NR = 247
+/// Wait for process to change state
+///
+/// `which` (`P_*`) and `upid` select the children to wait for. With `WNOHANG` the `si_pid` field of
+/// the returned signal information is `0` if no child has changed state yet
+///
+/// NOTE resource usage (`ru`) is not collected; null is passed to the kernel
+///
+/// See `man 2 waitid` for more details
+///
+/// Source: https://github.com/torvalds/linux/blob/v5.0/kernel/exit.c
+///
+/// C signature:
+///
+/// ```
+/// int waitid(int which, pid_t upid, struct siginfo *infop, int options, struct rusage *ru)
+/// ```
+pub fn waitid(which: c_int, upid: pid_t, options: c_int) -> Result<siginfo_t, Error> {
+    unsafe {
+        // NOTE all-zeros is a valid bit pattern for this struct
+        let mut infop: siginfo_t = mem::zeroed();
+        check!(syscall!(
+            WAITID,
+            which,
+            upid,
+            &mut infop as *mut siginfo_t,
+            options,
+            ptr::null_mut::<c_void>()
+        ))
+        .map(move |ret| {
+            debug_assert_eq!(ret, 0);
+            infop
+        })
+    }
+}
+
+// NR = 257
+/// Open (and possibly create) a file relative to a directory file descriptor
+///
+/// `pathname` must be a null-terminated string
+///
+/// See `man 2 openat` for more details
+///
+/// Source: https://github.com/torvalds/linux/blob/v5.0/fs/open.c#L1081
+///
+/// C signature: `int openat(int dfd, const char *filename, int flags, umode_t mode)`
+pub unsafe fn openat(
+    dfd: c_int,
+    pathname: *const c_char,
+    flags: c_int,
+    mode: umode_t,
+) -> Result<c_uint, Error> {
+    check!(syscall!(OPENAT, dfd, pathname, flags, mode)).map(|ret| ret as c_uint)
+}
+
+// NR = 258
+/// Create a directory relative to a directory file descriptor
+///
+/// `pathname` must be a null-terminated string
+///
+/// See `man 2 mkdirat` for more details
+///
+/// Source: https://github.com/torvalds/linux/blob/v5.0/fs/namei.c#L3839
+///
+/// C signature: `int mkdirat(int dfd, const char *pathname, umode_t mode)`
+pub unsafe fn mkdirat(dfd: c_int, pathname: *const c_char, mode: umode_t) -> Result<(), Error> {
+    check!(syscall!(MKDIRAT, dfd, pathname, mode)).map(|ret| debug_assert_eq!(ret, 0))
+}
+
+// NR = 263
+/// Delete a name (file or directory) relative to a directory file descriptor
+///
+/// `pathname` must be a null-terminated string
+///
+/// See `man 2 unlinkat` for more details
+///
+/// Source: https://github.com/torvalds/linux/blob/v5.0/fs/namei.c#L4088
+///
+/// C signature: `int unlinkat(int dfd, const char *pathname, int flag)`
+pub unsafe fn unlinkat(dfd: c_int, pathname: *const c_char, flag: c_int) -> Result<(), Error> {
+    check!(syscall!(UNLINKAT, dfd, pathname, flag)).map(|ret| debug_assert_eq!(ret, 0))
+}
+
+// NR = 288
+/// Accept a connection on a socket
+///
+/// If `addr` is not null it must point to a buffer of `*addrlen` bytes; the address of the peer
+/// is written there and `*addrlen` is updated to its actual length
+///
+/// See `man 2 accept4` for more details
+///
+/// Source: https://github.com/torvalds/linux/blob/v5.0/net/socket.c
+///
+/// C signature:
+///
+/// ```
+/// int accept4(int fd, struct sockaddr *upeer_sockaddr, int *upeer_addrlen, int flags)
+/// ```
+pub unsafe fn accept4(
+    fd: c_uint,
+    addr: *mut sockaddr,
+    addrlen: *mut socklen_t,
+    flags: c_int,
+) -> Result<c_uint, Error> {
+    check!(syscall!(ACCEPT4, fd, addr, addrlen, flags)).map(|ret| ret as c_uint)
+}
+
+// NR = 293
+/// Create a pipe
+///
+/// Returns the read end and the write end, in that order
+///
+/// See `man 2 pipe2` for more details
+///
+/// Source: https://github.com/torvalds/linux/blob/v5.0/fs/pipe.c
+///
+/// C signature: `int pipe2(int *fildes, int flags)`
+pub fn pipe2(flags: c_int) -> Result<[c_uint; 2], Error> {
+    unsafe {
+        let mut fildes = [0; 2];
+        check!(syscall!(PIPE2, fildes.as_mut_ptr(), flags)).map(move |ret| {
+            debug_assert_eq!(ret, 0);
+            fildes
+        })
+    }
+}
+
 // NR = 297
 /// Queue a signal and data
 ///
@@ -724,17 +1372,116 @@ pub fn getcpu(cpup: Option<&mut c_uint>, nodep: Option<&mut c_uint>) {
     }
 }
 
+// NR = 314
+/// Set scheduling policy and attributes
+///
+/// See `man 2 sched_setattr` for more details
+///
+/// Source: https://github.com/torvalds/linux/blob/v5.0/kernel/sched/core.c#L4563
+///
+/// C signature:
+///
+/// ```
+/// int sched_setattr(pid_t pid, struct sched_attr *uattr, unsigned int flags)
+/// ```
+pub unsafe fn sched_setattr(pid: pid_t, attr: &sched_attr, flags: c_uint) -> Result<(), Error> {
+    check!(syscall!(SCHED_SETATTR, pid, attr as *const _, flags))
+        .map(|ret| debug_assert_eq!(ret, 0))
+}
+
+// NR = 315
+/// Get scheduling policy and attributes
+///
+/// See `man 2 sched_getattr` for more details
+///
+/// Source: https://github.com/torvalds/linux/blob/v5.0/kernel/sched/core.c#L4673
+///
+/// C signature:
+///
+/// ```
+/// int sched_getattr(pid_t pid, struct sched_attr *uattr, unsigned int size, unsigned int flags)
+/// ```
+pub fn sched_getattr(pid: pid_t) -> Result<sched_attr, Error> {
+    unsafe {
+        let mut attr = MaybeUninit::<sched_attr>::uninit();
+        check!(syscall!(
+            SCHED_GETATTR,
+            pid,
+            attr.as_mut_ptr(),
+            mem::size_of::<sched_attr>() as c_uint,
+            0
+        ))
+        .map(move |ret| {
+            debug_assert_eq!(ret, 0);
+
+            attr.assume_init()
+        })
+    }
+}
+
+// NR = 332
+/// Get file status (extended)
+///
+/// `pathname` must be a null-terminated string
+///
+/// See `man 2 statx` for more details
+///
+/// Source: https://github.com/torvalds/linux/blob/v5.0/fs/stat.c#L571
+///
+/// C signature:
+///
+/// ```
+/// int statx(int dfd, const char *filename, unsigned flags, unsigned int mask, struct statx *buffer)
+/// ```
+pub unsafe fn statx(
+    dfd: c_int,
+    pathname: *const c_char,
+    flags: c_int,
+    mask: c_uint,
+) -> Result<statx, Error> {
+    let mut buffer = MaybeUninit::<statx>::uninit();
+    check!(syscall!(
+        STATX,
+        dfd,
+        pathname,
+        flags,
+        mask,
+        buffer.as_mut_ptr()
+    ))
+    .map(move |ret| {
+        debug_assert_eq!(ret, 0);
+        buffer.assume_init()
+    })
+}
+
 /// Thin wrapper around Linux error codes
-#[derive(Clone, Copy, PartialEq)]
+///
+/// The known error codes are available as associated constants, e.g. `Error::EAGAIN`
+#[derive(Clone, Copy, Eq, PartialEq)]
 pub struct Error {
     code: u8,
 }
 
 impl Error {
+    /// Creates an error from a raw error number (e.g. `11` for `EAGAIN`)
+    pub const fn from_raw(code: u8) -> Self {
+        Error { code }
+    }
+
     /// Returns the error code as an integer
     pub fn code(self) -> u8 {
         self.code
     }
+
+    /// Returns the name of the error code (e.g. `"EAGAIN"`), if it's a known one
+    pub fn name(self) -> Option<&'static str> {
+        errno::lookup(self.code).map(|(name, _)| name)
+    }
+
+    /// Returns a short description of the error (e.g. `"Try again"`), if it's a known one
+    pub fn description(self) -> Option<&'static str> {
+        errno::lookup(self.code).map(|(_, desc)| desc)
+    }
 }
 
 impl ufmt::uDebug for Error {
@@ -742,16 +1489,43 @@ impl ufmt::uDebug for Error {
     where
         W: ufmt::uWrite + ?Sized,
     {
-        let e = match self.code {
-            1 => "EPERM",
-            3 => "ESRCH",
-            14 => "EFAULT",
-            22 => "EINVAL",
-            _ => {
-                return uwrite!(f, "Error({})", self.code);
-            }
-        };
-
-        f.write_str(e)
+        if let Some(name) = self.name() {
+            f.write_str(name)
+        } else {
+            uwrite!(f, "Error({})", self.code)
+        }
+    }
+}
+
+impl ufmt::uDisplay for Error {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized,
+    {
+        if let Some(desc) = self.description() {
+            uwrite!(f, "{} (os error {})", desc, self.code)
+        } else {
+            uwrite!(f, "Unknown error (os error {})", self.code)
+        }
+    }
+}
+
+impl fmt::Debug for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(name) = self.name() {
+            f.write_str(name)
+        } else {
+            write!(f, "Error({})", self.code)
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(desc) = self.description() {
+            write!(f, "{} (os error {})", desc, self.code)
+        } else {
+            write!(f, "Unknown error (os error {})", self.code)
+        }
     }
 }
@@ -1,19 +1,184 @@
 #![allow(non_camel_case_types)]
 
-use core::cmp::Ordering;
+use core::{
+    cmp::Ordering,
+    mem,
+    ops::{BitAnd, BitOr, Not, Range},
+};
 
-use cty::{c_int, c_long, c_uint, c_ulong, c_void};
-use ufmt::derive::uDebug;
+use cty::{c_char, c_int, c_long, c_short, c_uint, c_ulong, c_ushort, c_void, size_t};
+use ufmt::{derive::uDebug, uDebug, uWrite, Formatter};
+
+use crate::consts::{
+    POLL_HUP, SIGBUS, SIGCHLD, SIGFPE, SIGILL, SIGPOLL, SIGRTMAX, SIGRTMIN, SIGSEGV, SIGSYS,
+    SIGTRAP, SI_KERNEL, SI_QUEUE, SI_SIGIO, SI_TIMER, SI_USER,
+};
 
 /// Clock identifier
 pub type clockid_t = c_int;
 
+/// File offset
+pub type off_t = c_long;
+
+/// File type and mode
+pub type umode_t = c_ushort;
+
 /// Process identifier
 pub type pid_t = c_int;
 
-// Linux 5.0 supports 64 signals
 /// Signal set
-pub type sigset_t = c_ulong;
+///
+/// Signal `n` is represented by bit `n - 1` of the kernel mask. Linux supports 64 signals:
+/// `1..=SIGRTMAX`
+#[derive(Clone, Copy, Default, Eq, PartialEq, uDebug)]
+#[repr(transparent)]
+pub struct SigSet {
+    bits: c_ulong,
+}
+
+impl SigSet {
+    /// The empty set
+    pub const fn empty() -> Self {
+        SigSet { bits: 0 }
+    }
+
+    /// The set of all the signals
+    pub const fn full() -> Self {
+        SigSet { bits: !0 }
+    }
+
+    /// Creates a set from its kernel representation
+    pub const fn from_bits(bits: c_ulong) -> Self {
+        SigSet { bits }
+    }
+
+    /// Returns the kernel representation of the set
+    pub const fn bits(self) -> c_ulong {
+        self.bits
+    }
+
+    /// The set of all the real-time signals: `SIGRTMIN..=SIGRTMAX`
+    pub fn realtime() -> Self {
+        Self::rt(0..(SIGRTMAX - SIGRTMIN + 1) as u8)
+    }
+
+    /// The set of real-time signals `SIGRTMIN + range.start .. SIGRTMIN + range.end`
+    #[inline]
+    pub fn rt(Range { start, end }: Range<u8>) -> Self {
+        debug_assert!(c_int::from(end) <= SIGRTMAX - SIGRTMIN + 1);
+
+        if start >= end {
+            Self::empty()
+        } else {
+            // NOTE `len` is at most 33
+            let len = end - start;
+            SigSet {
+                bits: ((1 << len) - 1) << (SIGRTMIN - 1 + c_int::from(start)),
+            }
+        }
+    }
+
+    /// Adds signal `signo` to the set
+    pub fn add(&mut self, signo: c_int) {
+        self.bits |= Self::bit(signo);
+    }
+
+    /// Removes signal `signo` from the set
+    pub fn remove(&mut self, signo: c_int) {
+        self.bits &= !Self::bit(signo);
+    }
+
+    /// Returns `true` if the set contains signal `signo`
+    pub fn contains(self, signo: c_int) -> bool {
+        self.bits & Self::bit(signo) != 0
+    }
+
+    /// Returns `true` if the set contains no signals
+    pub fn is_empty(self) -> bool {
+        self.bits == 0
+    }
+
+    /// Returns an iterator over the signals in this set, in ascending order
+    pub fn iter(self) -> SigSetIter {
+        SigSetIter { bits: self.bits }
+    }
+
+    #[inline]
+    fn bit(signo: c_int) -> c_ulong {
+        debug_assert!(signo >= 1 && signo <= SIGRTMAX, "invalid signal number");
+
+        1 << (signo - 1)
+    }
+}
+
+impl From<c_ulong> for SigSet {
+    fn from(bits: c_ulong) -> Self {
+        SigSet { bits }
+    }
+}
+
+impl From<SigSet> for c_ulong {
+    fn from(set: SigSet) -> Self {
+        set.bits
+    }
+}
+
+impl BitAnd for SigSet {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        SigSet {
+            bits: self.bits & rhs.bits,
+        }
+    }
+}
+
+impl BitOr for SigSet {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        SigSet {
+            bits: self.bits | rhs.bits,
+        }
+    }
+}
+
+impl Not for SigSet {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        SigSet { bits: !self.bits }
+    }
+}
+
+impl IntoIterator for SigSet {
+    type Item = c_int;
+    type IntoIter = SigSetIter;
+
+    fn into_iter(self) -> SigSetIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the signals in a `SigSet`
+pub struct SigSetIter {
+    bits: c_ulong,
+}
+
+impl Iterator for SigSetIter {
+    type Item = c_int;
+
+    fn next(&mut self) -> Option<c_int> {
+        if self.bits == 0 {
+            None
+        } else {
+            let n = self.bits.trailing_zeros();
+            // clear the lowest set bit
+            self.bits &= self.bits - 1;
+            Some(n as c_int + 1)
+        }
+    }
+}
 
 /// Timer identifier
 pub type timer_t = c_int;
@@ -89,12 +254,15 @@ pub struct sigaction {
     pub sa_restorer: Option<unsafe extern "C" fn() -> !>,
 
     /// Block mask
-    pub sa_mask: sigset_t,
+    pub sa_mask: SigSet,
 }
 
 /// Signal information
+///
+/// Which member of `_sifields` is valid depends on `si_signo` and `si_code`; the accessor methods
+/// (`pid`, `value`, `addr`, etc.) check both and return `None` when the field is not available
+#[derive(Clone, Copy)]
 #[repr(C)]
-#[derive(uDebug)]
 pub struct siginfo_t {
     /// Signal number
     pub si_signo: c_int,
@@ -102,26 +270,373 @@ pub struct siginfo_t {
     /// errno
     pub si_errno: c_int,
 
-    /// Signal info code (`SI_*`)
+    /// Signal info code (`SI_*`, `CLD_*`, `POLL_*`, etc.)
     pub si_code: c_int,
 
-    pad0: u32,
+    /// Signal specific information
+    pub _sifields: sifields_t,
+}
+
+const SI_MAX_SIZE: usize = 128;
+#[allow(dead_code)]
+const ASSERT: [(); 0 - !(core::mem::size_of::<siginfo_t>() == SI_MAX_SIZE) as usize] = [];
 
-    /// Process ID
-    pub si_pid: pid_t,
+/// Signal specific information
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub union sifields_t {
+    /// `kill`, `tkill`, `tgkill` and signals sent by the kernel (`SI_USER`, `SI_TKILL`, `SI_KERNEL`)
+    pub kill: sifields_kill,
 
-    /// User ID
-    pub si_uid: c_uint,
+    /// POSIX.1b timers (`SI_TIMER`)
+    pub timer: sifields_timer,
 
-    /// User data
-    pub si_value: usize,
+    /// POSIX.1b signals (`SI_QUEUE`, `SI_MESGQ`, `SI_ASYNCIO`)
+    pub rt: sifields_rt,
 
-    pad1: [u32; 24],
+    /// `SIGCHLD`
+    pub sigchld: sifields_sigchld,
+
+    /// `SIGILL`, `SIGFPE`, `SIGSEGV`, `SIGBUS` and `SIGTRAP`
+    pub sigfault: sifields_sigfault,
+
+    /// `SIGPOLL`
+    pub sigpoll: sifields_sigpoll,
+
+    /// `SIGSYS`
+    pub sigsys: sifields_sigsys,
+
+    _pad: [c_int; (SI_MAX_SIZE - 16) / 4],
 }
 
-const SI_MAX_SIZE: usize = 128;
-#[allow(dead_code)]
-const ASSERT: [(); 0 - !(core::mem::size_of::<siginfo_t>() == SI_MAX_SIZE) as usize] = [];
+/// `kill` information
+#[derive(Clone, Copy, uDebug)]
+#[repr(C)]
+pub struct sifields_kill {
+    /// Sender's PID
+    pub pid: pid_t,
+
+    /// Sender's UID
+    pub uid: c_uint,
+}
+
+/// POSIX.1b timer information
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct sifields_timer {
+    /// Timer ID
+    pub tid: c_int,
+
+    /// Overrun count
+    pub overrun: c_int,
+
+    /// Data passed to `timer_create` in `sigevent.sigev_value`
+    pub sigval: sigval_t,
+
+    _sys_private: c_int,
+}
+
+/// POSIX.1b signal information
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct sifields_rt {
+    /// Sender's PID
+    pub pid: pid_t,
+
+    /// Sender's UID
+    pub uid: c_uint,
+
+    /// Data sent along with the signal
+    pub sigval: sigval_t,
+}
+
+/// `SIGCHLD` information
+#[derive(Clone, Copy, uDebug)]
+#[repr(C)]
+pub struct sifields_sigchld {
+    /// Which child
+    pub pid: pid_t,
+
+    /// Child's UID
+    pub uid: c_uint,
+
+    /// Exit status (`CLD_EXITED`) or signal number
+    pub status: c_int,
+
+    /// User time consumed
+    pub utime: c_long,
+
+    /// System time consumed
+    pub stime: c_long,
+}
+
+/// `SIGILL`, `SIGFPE`, `SIGSEGV`, `SIGBUS` and `SIGTRAP` information
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct sifields_sigfault {
+    /// Faulting instruction or memory reference
+    pub addr: *mut c_void,
+
+    /// Signal specific information
+    pub _u: sigfault_t,
+}
+
+/// `si_code` specific fault information
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub union sigfault_t {
+    /// LSB of the reported address (`BUS_MCEERR_*`)
+    pub addr_lsb: c_short,
+
+    /// Bounds that were violated (`SEGV_BNDERR`)
+    pub addr_bnd: addr_bnd_t,
+
+    /// Protection key of the faulting page table entry (`SEGV_PKUERR`)
+    pub addr_pkey: addr_pkey_t,
+}
+
+/// Bounds that were violated
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct addr_bnd_t {
+    _dummy: *mut c_void,
+
+    /// Lower bound
+    pub lower: *mut c_void,
+
+    /// Upper bound
+    pub upper: *mut c_void,
+}
+
+/// Protection key
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct addr_pkey_t {
+    _dummy: *mut c_void,
+
+    /// Protection key
+    pub pkey: u32,
+}
+
+/// `SIGPOLL` information
+#[derive(Clone, Copy, uDebug)]
+#[repr(C)]
+pub struct sifields_sigpoll {
+    /// `POLL_IN`, `POLL_OUT`, `POLL_MSG`
+    pub band: c_long,
+
+    /// File descriptor
+    pub fd: c_int,
+}
+
+/// `SIGSYS` information
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct sifields_sigsys {
+    /// Calling user instruction
+    pub call_addr: *mut c_void,
+
+    /// Triggering system call number
+    pub syscall: c_int,
+
+    /// `AUDIT_ARCH_*` of syscall
+    pub arch: c_uint,
+}
+
+// Mirrors `siginfo_layout` in kernel/signal.c
+#[derive(Clone, Copy, PartialEq)]
+enum Layout {
+    Kill,
+    Timer,
+    Poll,
+    Fault,
+    Chld,
+    Rt,
+    Sys,
+}
+
+impl siginfo_t {
+    /// Returns signal information that can be sent with `rt_sigqueueinfo` or `rt_tgsigqueueinfo`
+    ///
+    /// `si_code` is set to `SI_QUEUE`; the sender's PID and UID are left as 0
+    pub fn queue(value: sigval_t) -> Self {
+        // NOTE all-zeros is a valid bit pattern for this struct
+        let mut si: siginfo_t = unsafe { mem::zeroed() };
+        si.si_code = SI_QUEUE;
+        si._sifields.rt = sifields_rt {
+            pid: 0,
+            uid: 0,
+            sigval: value,
+        };
+        si
+    }
+
+    /// Returns the PID of the sending process
+    ///
+    /// Available for signals sent by `kill` (and the other `SI_USER` / `SI_TKILL` senders), by
+    /// `rt_sigqueueinfo` (`SI_QUEUE`) and for `SIGCHLD`, where it's the PID of the child
+    pub fn pid(&self) -> Option<pid_t> {
+        match self.layout() {
+            // NOTE `pid` and `uid` have the same offset in all these variants
+            Layout::Kill | Layout::Rt | Layout::Chld => Some(unsafe { self._sifields.kill.pid }),
+            _ => None,
+        }
+    }
+
+    /// Returns the real UID of the sending process
+    ///
+    /// Available in the same cases as `pid`
+    pub fn uid(&self) -> Option<c_uint> {
+        match self.layout() {
+            Layout::Kill | Layout::Rt | Layout::Chld => Some(unsafe { self._sifields.kill.uid }),
+            _ => None,
+        }
+    }
+
+    /// Returns the data sent along with the signal
+    ///
+    /// Available for signals sent by `rt_sigqueueinfo` (`SI_QUEUE`) and for POSIX timer
+    /// expirations (`SI_TIMER`)
+    pub fn value(&self) -> Option<sigval_t> {
+        match self.layout() {
+            Layout::Rt => Some(unsafe { self._sifields.rt.sigval }),
+            Layout::Timer => Some(unsafe { self._sifields.timer.sigval }),
+            _ => None,
+        }
+    }
+
+    /// Returns the ID of the POSIX timer that expired (`SI_TIMER`)
+    pub fn timerid(&self) -> Option<c_int> {
+        if self.layout() == Layout::Timer {
+            Some(unsafe { self._sifields.timer.tid })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the number of timer expirations that were lost because the signal was still
+    /// pending (`SI_TIMER`)
+    pub fn overrun(&self) -> Option<c_int> {
+        if self.layout() == Layout::Timer {
+            Some(unsafe { self._sifields.timer.overrun })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the address of the fault (`SIGILL`, `SIGFPE`, `SIGSEGV`, `SIGBUS` and `SIGTRAP`)
+    pub fn addr(&self) -> Option<*mut c_void> {
+        if self.layout() == Layout::Fault {
+            Some(unsafe { self._sifields.sigfault.addr })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the exit status of the child or the signal that changed its state (`SIGCHLD`)
+    ///
+    /// It's an exit status if `si_code` is `CLD_EXITED`; otherwise it's a signal number
+    pub fn status(&self) -> Option<c_int> {
+        if self.layout() == Layout::Chld {
+            Some(unsafe { self._sifields.sigchld.status })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the band event (`POLL*`) of the I/O event (`SIGPOLL`)
+    pub fn band(&self) -> Option<c_long> {
+        if self.layout() == Layout::Poll {
+            Some(unsafe { self._sifields.sigpoll.band })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the file descriptor of the I/O event (`SIGPOLL`)
+    pub fn fd(&self) -> Option<c_int> {
+        if self.layout() == Layout::Poll {
+            Some(unsafe { self._sifields.sigpoll.fd })
+        } else {
+            None
+        }
+    }
+
+    fn layout(&self) -> Layout {
+        let code = self.si_code;
+
+        if code > SI_USER && code < SI_KERNEL {
+            match self.si_signo {
+                SIGILL | SIGFPE | SIGSEGV | SIGBUS | SIGTRAP => Layout::Fault,
+                SIGCHLD => Layout::Chld,
+                SIGPOLL => Layout::Poll,
+                SIGSYS => Layout::Sys,
+                _ if code <= POLL_HUP => Layout::Poll,
+                _ => Layout::Kill,
+            }
+        } else if code == SI_TIMER {
+            Layout::Timer
+        } else if code == SI_SIGIO {
+            Layout::Poll
+        } else if code < 0 {
+            Layout::Rt
+        } else {
+            Layout::Kill
+        }
+    }
+}
+
+impl uDebug for siginfo_t {
+    fn fmt<W>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: uWrite + ?Sized,
+    {
+        let mut s = f.debug_struct("siginfo_t")?;
+        s.field("si_signo", &self.si_signo)?
+            .field("si_errno", &self.si_errno)?
+            .field("si_code", &self.si_code)?;
+
+        unsafe {
+            match self.layout() {
+                Layout::Kill => {
+                    s.field("si_pid", &self._sifields.kill.pid)?
+                        .field("si_uid", &self._sifields.kill.uid)?;
+                }
+                Layout::Timer => {
+                    s.field("si_timerid", &self._sifields.timer.tid)?
+                        .field("si_overrun", &self._sifields.timer.overrun)?
+                        .field("si_value", &self._sifields.timer.sigval.sival_ptr)?;
+                }
+                Layout::Rt => {
+                    s.field("si_pid", &self._sifields.rt.pid)?
+                        .field("si_uid", &self._sifields.rt.uid)?
+                        .field("si_value", &self._sifields.rt.sigval.sival_ptr)?;
+                }
+                Layout::Chld => {
+                    s.field("si_pid", &self._sifields.sigchld.pid)?
+                        .field("si_uid", &self._sifields.sigchld.uid)?
+                        .field("si_status", &self._sifields.sigchld.status)?
+                        .field("si_utime", &self._sifields.sigchld.utime)?
+                        .field("si_stime", &self._sifields.sigchld.stime)?;
+                }
+                Layout::Fault => {
+                    s.field("si_addr", &self._sifields.sigfault.addr)?;
+                }
+                Layout::Poll => {
+                    s.field("si_band", &self._sifields.sigpoll.band)?
+                        .field("si_fd", &self._sifields.sigpoll.fd)?;
+                }
+                Layout::Sys => {
+                    s.field("si_call_addr", &self._sifields.sigsys.call_addr)?
+                        .field("si_syscall", &self._sifields.sigsys.syscall)?
+                        .field("si_arch", &self._sifields.sigsys.arch)?;
+                }
+            }
+        }
+
+        s.finish()
+    }
+}
 
 /* sigevent */
 /// Signal event
@@ -141,6 +656,8 @@ pub struct sigevent {
 }
 
 /// `sigev` data
+#[derive(Clone, Copy)]
+#[repr(C)]
 pub union sigval_t {
     /// An integer
     pub sival_int: c_int,
@@ -149,6 +666,149 @@ pub union sigval_t {
     pub sival_ptr: *mut c_void,
 }
 
+/* fstat */
+/// File status
+#[derive(Clone, Copy, uDebug)]
+#[repr(C)]
+pub struct stat {
+    /// ID of device containing file
+    pub st_dev: c_ulong,
+
+    /// Inode number
+    pub st_ino: c_ulong,
+
+    /// Number of hard links
+    pub st_nlink: c_ulong,
+
+    /// File type and mode
+    pub st_mode: c_uint,
+
+    /// User ID of owner
+    pub st_uid: c_uint,
+
+    /// Group ID of owner
+    pub st_gid: c_uint,
+
+    __pad0: c_uint,
+
+    /// Device ID (if special file)
+    pub st_rdev: c_ulong,
+
+    /// Total size, in bytes
+    pub st_size: c_long,
+
+    /// Block size for filesystem I/O
+    pub st_blksize: c_long,
+
+    /// Number of 512B blocks allocated
+    pub st_blocks: c_long,
+
+    /// Time of last access (seconds)
+    pub st_atime: c_ulong,
+
+    /// Time of last access (nanoseconds)
+    pub st_atime_nsec: c_ulong,
+
+    /// Time of last modification (seconds)
+    pub st_mtime: c_ulong,
+
+    /// Time of last modification (nanoseconds)
+    pub st_mtime_nsec: c_ulong,
+
+    /// Time of last status change (seconds)
+    pub st_ctime: c_ulong,
+
+    /// Time of last status change (nanoseconds)
+    pub st_ctime_nsec: c_ulong,
+
+    __unused: [c_long; 3],
+}
+
+/* statx */
+/// Timestamp of a `statx` structure
+#[derive(Clone, Copy, uDebug)]
+#[repr(C)]
+pub struct statx_timestamp {
+    /// Seconds since the Epoch
+    pub tv_sec: i64,
+
+    /// Nanoseconds since `tv_sec`
+    pub tv_nsec: u32,
+
+    __reserved: i32,
+}
+
+/// Extended file status
+#[derive(Clone, Copy, uDebug)]
+#[repr(C)]
+pub struct statx {
+    /// Mask of bits (`STATX_*`) indicating filled fields
+    pub stx_mask: u32,
+
+    /// Block size for filesystem I/O
+    pub stx_blksize: u32,
+
+    /// Extra file attribute indicators
+    pub stx_attributes: u64,
+
+    /// Number of hard links
+    pub stx_nlink: u32,
+
+    /// User ID of owner
+    pub stx_uid: u32,
+
+    /// Group ID of owner
+    pub stx_gid: u32,
+
+    /// File type and mode
+    pub stx_mode: u16,
+
+    __spare0: [u16; 1],
+
+    /// Inode number
+    pub stx_ino: u64,
+
+    /// Total size, in bytes
+    pub stx_size: u64,
+
+    /// Number of 512B blocks allocated
+    pub stx_blocks: u64,
+
+    /// Mask to show what's supported in `stx_attributes`
+    pub stx_attributes_mask: u64,
+
+    /// Last access
+    pub stx_atime: statx_timestamp,
+
+    /// Creation
+    pub stx_btime: statx_timestamp,
+
+    /// Last status change
+    pub stx_ctime: statx_timestamp,
+
+    /// Last modification
+    pub stx_mtime: statx_timestamp,
+
+    /// Major ID (if this file represents a device)
+    pub stx_rdev_major: u32,
+
+    /// Minor ID (if this file represents a device)
+    pub stx_rdev_minor: u32,
+
+    /// Major ID of the device containing the filesystem where the file resides
+    pub stx_dev_major: u32,
+
+    /// Minor ID of the device containing the filesystem where the file resides
+    pub stx_dev_minor: u32,
+
+    __spare2: [u64; 14],
+}
+
+#[allow(dead_code)]
+const ASSERT_STAT: [(); 0 - !(core::mem::size_of::<stat>() == 144) as usize] = [];
+#[allow(dead_code)]
+const ASSERT_STATX: [(); 0 - !(core::mem::size_of::<statx>() == 256) as usize] = [];
+
 /* sched_* */
 /// Scheduling parameter
 #[derive(uDebug)]
@@ -157,3 +817,276 @@ pub struct sched_param {
     /// Scheduling priority
     pub sched_priority: c_int,
 }
+
+/// Scheduling policy and attributes
+#[derive(uDebug)]
+#[repr(C)]
+pub struct sched_attr {
+    /// Size of this structure
+    pub size: u32,
+
+    /// Scheduling policy (`SCHED_*`)
+    pub sched_policy: u32,
+
+    /// Scheduling flags (`SCHED_FLAG_*`)
+    pub sched_flags: u64,
+
+    /// Nice value; used by `SCHED_NORMAL` and `SCHED_BATCH`
+    pub sched_nice: i32,
+
+    /// Static priority; used by `SCHED_FIFO` and `SCHED_RR`
+    pub sched_priority: u32,
+
+    /// Runtime budget, in nanoseconds; used by `SCHED_DEADLINE`
+    pub sched_runtime: u64,
+
+    /// Relative deadline, in nanoseconds; used by `SCHED_DEADLINE`
+    pub sched_deadline: u64,
+
+    /// Period, in nanoseconds; used by `SCHED_DEADLINE`
+    pub sched_period: u64,
+}
+
+/* socket */
+/// Address family
+pub type sa_family_t = c_ushort;
+
+/// Length of a socket address
+pub type socklen_t = c_int;
+
+/// Generic socket address
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct sockaddr {
+    /// Address family (`AF_*`)
+    pub sa_family: sa_family_t,
+
+    /// Address data
+    pub sa_data: [c_char; 14],
+}
+
+/// Unix domain socket address
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct sockaddr_un {
+    /// `AF_UNIX`
+    pub sun_family: sa_family_t,
+
+    /// Path name; it starts with a null byte if the address is in the abstract namespace
+    pub sun_path: [c_char; 108],
+}
+
+/// IPv4 address
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct in_addr {
+    /// Address in network byte order
+    pub s_addr: u32,
+}
+
+/// IPv4 socket address
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct sockaddr_in {
+    /// `AF_INET`
+    pub sin_family: sa_family_t,
+
+    /// Port number in network byte order
+    pub sin_port: u16,
+
+    /// Internet address
+    pub sin_addr: in_addr,
+
+    /// Padding; must be zeroed
+    pub sin_zero: [u8; 8],
+}
+
+/// IPv6 address
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct in6_addr {
+    /// Address in network byte order
+    pub s6_addr: [u8; 16],
+}
+
+/// IPv6 socket address
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct sockaddr_in6 {
+    /// `AF_INET6`
+    pub sin6_family: sa_family_t,
+
+    /// Port number in network byte order
+    pub sin6_port: u16,
+
+    /// IPv6 flow information in network byte order
+    pub sin6_flowinfo: u32,
+
+    /// IPv6 address
+    pub sin6_addr: in6_addr,
+
+    /// Scope ID
+    pub sin6_scope_id: u32,
+}
+
+/// Buffer used in scatter / gather I/O
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct iovec {
+    /// Start address
+    pub iov_base: *mut c_void,
+
+    /// Number of bytes
+    pub iov_len: size_t,
+}
+
+/// Message header used by `sendmsg` and `recvmsg`
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct msghdr {
+    /// Socket address; may be null
+    pub msg_name: *mut c_void,
+
+    /// Size of the socket address, in bytes
+    pub msg_namelen: socklen_t,
+
+    /// Data buffers
+    pub msg_iov: *mut iovec,
+
+    /// Number of elements in `msg_iov`
+    pub msg_iovlen: size_t,
+
+    /// Ancillary data; may be null
+    pub msg_control: *mut c_void,
+
+    /// Size of the ancillary data buffer, in bytes
+    pub msg_controllen: size_t,
+
+    /// Flags (`MSG_*`) on the received message
+    pub msg_flags: c_uint,
+}
+
+/// Header of a control message (ancillary data)
+///
+/// The data follows the header, aligned to `size_of::<usize>()` bytes
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct cmsghdr {
+    /// Size of the header plus the data, in bytes
+    pub cmsg_len: size_t,
+
+    /// Originating protocol (e.g. `SOL_SOCKET`)
+    pub cmsg_level: c_int,
+
+    /// Protocol-specific type (e.g. `SCM_TIMESTAMPNS`)
+    pub cmsg_type: c_int,
+}
+
+#[allow(dead_code)]
+const ASSERT_SOCKADDR_UN: [(); 0 - !(core::mem::size_of::<sockaddr_un>() == 110) as usize] = [];
+#[allow(dead_code)]
+const ASSERT_MSGHDR: [(); 0 - !(core::mem::size_of::<msghdr>() == 56) as usize] = [];
+#[allow(dead_code)]
+const ASSERT_SOCKADDR_IN: [(); 0 - !(core::mem::size_of::<sockaddr_in>() == 16) as usize] = [];
+#[allow(dead_code)]
+const ASSERT_SOCKADDR_IN6: [(); 0 - !(core::mem::size_of::<sockaddr_in6>() == 28) as usize] = [];
+
+#[cfg(test)]
+mod tests {
+    use super::SigSet;
+    use crate::consts::{SIGRTMAX, SIGRTMIN};
+
+    #[test]
+    fn rt() {
+        // signal `n` is bit `n - 1`
+        assert_eq!(SigSet::rt(0..1).bits(), 0x0000_0000_8000_0000);
+        assert_eq!(SigSet::rt(2..5).bits(), 0x0000_000e_0000_0000);
+        assert_eq!(SigSet::rt(32..33).bits(), 0x8000_0000_0000_0000);
+        assert!(SigSet::rt(3..3).is_empty());
+        assert!(SigSet::rt(5..3).is_empty());
+
+        assert_eq!(SigSet::realtime().bits(), 0xffff_ffff_8000_0000);
+        assert!(SigSet::rt(0..33) == SigSet::realtime());
+        assert_eq!(SigSet::full().bits(), !0);
+        assert_eq!((!SigSet::realtime()).bits(), 0x7fff_ffff);
+    }
+
+    #[test]
+    fn add_remove_contains() {
+        let mut set = SigSet::empty();
+        set.add(9);
+        set.add(SIGRTMIN + 1);
+        set.add(SIGRTMAX);
+        assert_eq!(set.bits(), 0x8000_0001_0000_0100);
+
+        assert!(set.contains(9));
+        assert!(set.contains(SIGRTMIN + 1));
+        assert!(set.contains(SIGRTMAX));
+        assert!(!set.contains(SIGRTMIN));
+
+        set.remove(9);
+        assert_eq!(set.bits(), 0x8000_0001_0000_0000);
+        assert!(set == SigSet::rt(1..2) | SigSet::rt(32..33));
+        assert!(set & SigSet::rt(0..2) == SigSet::rt(1..2));
+
+        // removing a signal that's not in the set is a no-op
+        set.remove(1);
+        assert_eq!(set.bits(), 0x8000_0001_0000_0000);
+    }
+
+    #[test]
+    fn iter() {
+        let set = SigSet::from_bits(0x8000_0001_0000_0100);
+        let mut iter = set.iter();
+        assert_eq!(iter.next(), Some(9));
+        assert_eq!(iter.next(), Some(SIGRTMIN + 1));
+        assert_eq!(iter.next(), Some(SIGRTMAX));
+        assert_eq!(iter.next(), None);
+
+        assert_eq!(SigSet::empty().iter().next(), None);
+        assert_eq!(SigSet::full().iter().count(), 64);
+    }
+
+    // the masks `rtfm::export::{init_runtime, register, mask}` compute for a core that uses the
+    // signals `start..end`
+    #[test]
+    fn rtfm_masks() {
+        // `init_runtime(Some(8..12), ..)`: block signals 40..=43
+        assert_eq!(SigSet::rt(8..12).bits(), 0x0000_0780_0000_0000);
+
+        // `register(0..3, priority, ..)`: `sa_mask = rt(end - priority + 1..end)`
+        assert_eq!(SigSet::rt(3..3).bits(), 0);
+        assert_eq!(SigSet::rt(2..3).bits(), 0x0000_0002_0000_0000);
+        assert_eq!(SigSet::rt(1..3).bits(), 0x0000_0003_0000_0000);
+
+        // `mask(0..3, 1, 3, ..)`: `rt(end - ceiling..end - current)`
+        assert_eq!(SigSet::rt(0..2).bits(), 0x0000_0001_8000_0000);
+        // `mask(8..11, 0, 2, ..)`, i.e. `#[app(signals = 40..43)]`
+        assert_eq!(SigSet::rt(9..11).bits(), 0x0000_0300_0000_0000);
+    }
+
+    // `SigSet` must produce the same masks as the shift arithmetic RTFM used before it existed
+    #[test]
+    fn rtfm_masks_exhaustive() {
+        for start in 0..33u8 {
+            for end in start..=33 {
+                let len = end - start;
+
+                for priority in 1..=len {
+                    let mask: u64 = (1 << len) - 1;
+                    let old =
+                        (mask ^ (mask >> (priority - 1))) << (i32::from(start) + SIGRTMIN - 1);
+                    assert_eq!(SigSet::rt(end - priority + 1..end).bits(), old);
+                }
+
+                for ceiling in 0..=len {
+                    for current in 0..ceiling {
+                        let old: u64 = ((1 << (ceiling - current)) - 1)
+                            << (SIGRTMIN - 1 + i32::from(start + len - ceiling));
+                        assert_eq!(SigSet::rt(end - ceiling..end - current).bits(), old);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,182 @@
+//! virtual Dynamic Shared Object
+//!
+//! The kernel maps a small ELF library, the vDSO, into every process. Some of its functions, like
+//! `clock_gettime`, can be called directly and run without entering the kernel. See `man 7 vdso`
+//! for more details
+
+use core::{
+    mem,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use cty::c_int;
+
+use crate::{clockid_t, timespec};
+
+// address of `__vdso_clock_gettime`; 0 if it hasn't been located (yet)
+static CLOCK_GETTIME: AtomicUsize = AtomicUsize::new(0);
+
+/// Locates the vDSO functions used by this crate
+///
+/// `sysinfo_ehdr` is the address of the ELF header of the vDSO, the `AT_SYSINFO_EHDR` entry of the
+/// auxiliary vector. Until this function is called the system calls are used
+///
+/// # Unsafety
+///
+/// `sysinfo_ehdr` must point to the vDSO or be 0
+pub unsafe fn init(sysinfo_ehdr: usize) {
+    if sysinfo_ehdr == 0 {
+        return;
+    }
+
+    if let Some(addr) = lookup(sysinfo_ehdr, b"__vdso_clock_gettime") {
+        CLOCK_GETTIME.store(addr, Ordering::Relaxed);
+    }
+}
+
+/// Calls `__vdso_clock_gettime`, if it was located; returns the same value as the system call
+pub(crate) unsafe fn clock_gettime(which_clock: clockid_t, tp: *mut timespec) -> Option<c_int> {
+    match CLOCK_GETTIME.load(Ordering::Relaxed) {
+        0 => None,
+        addr => {
+            let f: unsafe extern "C" fn(clockid_t, *mut timespec) -> c_int = mem::transmute(addr);
+
+            Some(f(which_clock, tp))
+        }
+    }
+}
+
+/* ELF64 structures; see `man 5 elf` */
+const ELFMAG: [u8; 4] = [0x7f, b'E', b'L', b'F'];
+const ELFCLASS64: u8 = 2;
+const PT_LOAD: u32 = 1;
+const PT_DYNAMIC: u32 = 2;
+const DT_NULL: i64 = 0;
+const DT_HASH: i64 = 4;
+const DT_STRTAB: i64 = 5;
+const DT_SYMTAB: i64 = 6;
+const SHN_UNDEF: u16 = 0;
+const STT_FUNC: u8 = 2;
+
+#[allow(dead_code)]
+#[repr(C)]
+struct Ehdr {
+    e_ident: [u8; 16],
+    e_type: u16,
+    e_machine: u16,
+    e_version: u32,
+    e_entry: u64,
+    e_phoff: u64,
+    e_shoff: u64,
+    e_flags: u32,
+    e_ehsize: u16,
+    e_phentsize: u16,
+    e_phnum: u16,
+    e_shentsize: u16,
+    e_shnum: u16,
+    e_shstrndx: u16,
+}
+
+#[allow(dead_code)]
+#[repr(C)]
+struct Phdr {
+    p_type: u32,
+    p_flags: u32,
+    p_offset: u64,
+    p_vaddr: u64,
+    p_paddr: u64,
+    p_filesz: u64,
+    p_memsz: u64,
+    p_align: u64,
+}
+
+#[repr(C)]
+struct Dyn {
+    d_tag: i64,
+    d_val: u64,
+}
+
+#[allow(dead_code)]
+#[repr(C)]
+struct Sym {
+    st_name: u32,
+    st_info: u8,
+    st_other: u8,
+    st_shndx: u16,
+    st_value: u64,
+    st_size: u64,
+}
+
+// Returns the address of the function `name` exported by the ELF image mapped at `base`
+unsafe fn lookup(base: usize, name: &[u8]) -> Option<usize> {
+    let ehdr = &*(base as *const Ehdr);
+    if ehdr.e_ident[..4] != ELFMAG
+        || ehdr.e_ident[4] != ELFCLASS64
+        || usize::from(ehdr.e_phentsize) != mem::size_of::<Phdr>()
+    {
+        return None;
+    }
+
+    // the image is mapped at `base` but its addresses are relative to its first `PT_LOAD` segment
+    let mut bias = None;
+    let mut dynamic = None;
+    let phdrs = (base + ehdr.e_phoff as usize) as *const Phdr;
+    for i in 0..usize::from(ehdr.e_phnum) {
+        let phdr = &*phdrs.add(i);
+
+        match phdr.p_type {
+            PT_LOAD if bias.is_none() => {
+                bias = Some(base.wrapping_sub(phdr.p_vaddr as usize - phdr.p_offset as usize))
+            }
+            PT_DYNAMIC => dynamic = Some(base + phdr.p_offset as usize),
+            _ => {}
+        }
+    }
+    let bias = bias?;
+
+    let mut hash = None;
+    let mut strtab = None;
+    let mut symtab = None;
+    let mut dyn_ = dynamic? as *const Dyn;
+    loop {
+        let Dyn { d_tag, d_val } = *dyn_;
+        let addr = bias.wrapping_add(d_val as usize);
+
+        match d_tag {
+            DT_NULL => break,
+            DT_HASH => hash = Some(addr as *const u32),
+            DT_STRTAB => strtab = Some(addr as *const u8),
+            DT_SYMTAB => symtab = Some(addr as *const Sym),
+            _ => {}
+        }
+
+        dyn_ = dyn_.add(1);
+    }
+    let (hash, strtab, symtab) = (hash?, strtab?, symtab?);
+
+    // the second word of the SysV hash table is the number of symbols
+    let nsyms = *hash.add(1) as usize;
+    for i in 0..nsyms {
+        let sym = &*symtab.add(i);
+
+        if sym.st_shndx != SHN_UNDEF
+            && sym.st_info & 0xf == STT_FUNC
+            && cstr_eq(strtab.add(sym.st_name as usize), name)
+        {
+            return Some(bias.wrapping_add(sym.st_value as usize));
+        }
+    }
+
+    None
+}
+
+// Compares the nul-terminated string at `ptr` to `s`
+unsafe fn cstr_eq(ptr: *const u8, s: &[u8]) -> bool {
+    for (i, byte) in s.iter().enumerate() {
+        if *ptr.add(i) != *byte {
+            return false;
+        }
+    }
+
+    *ptr.add(s.len()) == 0
+}
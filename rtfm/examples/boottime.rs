@@ -0,0 +1,37 @@
+//! Like `periodic` but the timer queue runs on `CLOCK_BOOTTIME`, which keeps counting while the
+//! system is suspended
+
+#![deny(rust_2018_compatibility)]
+#![deny(rust_2018_idioms)]
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+use core::time::Duration;
+
+use linux_io::{process, Stdout};
+use panic_exit as _;
+
+#[rtfm::app(monotonic = rtfm::BootInstant)]
+const APP: () = {
+    #[init(spawn = [foo])]
+    fn init(c: init::Context) {
+        c.spawn.foo().ok();
+    }
+
+    #[task(schedule = [foo])]
+    fn foo(c: foo::Context) {
+        static mut COUNT: u8 = 0;
+
+        Stdout.write(b".").ok();
+
+        *COUNT += 1;
+        if *COUNT >= 3 {
+            Stdout.write(b"\n").ok();
+            process::exit(0);
+        }
+
+        // `c.scheduled` is a `rtfm::BootInstant`
+        c.schedule.foo(c.scheduled + Duration::from_secs(1)).ok();
+    }
+};
@@ -0,0 +1,34 @@
+//! Run the app under `SCHED_DEADLINE`: the core gets 2 ms of CPU time every 10 ms, even if `spin`
+//! never yields
+
+#![deny(rust_2018_compatibility)]
+#![deny(rust_2018_idioms)]
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+use core::time::Duration;
+
+use linux_io::{process, time::Instant, Stdout};
+use panic_exit as _;
+
+// runtime, deadline and period are in microseconds
+#[rtfm::app(sched_deadline = (2_000, 10_000, 10_000))]
+const APP: () = {
+    #[init(spawn = [spin])]
+    fn init(c: init::Context) {
+        c.spawn.spin().ok();
+    }
+
+    #[task]
+    fn spin(_: spin::Context) {
+        let start = Instant::now();
+
+        // a runaway task; it would starve the rest of the system under `SCHED_FIFO`
+        while Instant::now().saturating_duration_since(start) < Duration::from_secs(3) {}
+
+        Stdout.write(b"done\n").ok();
+
+        process::exit(0);
+    }
+};
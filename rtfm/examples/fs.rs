@@ -0,0 +1,55 @@
+//! Loads a calibration file in `init` and appends to a log file from `idle`
+
+#![deny(rust_2018_compatibility)]
+#![deny(rust_2018_idioms)]
+#![deny(warnings)]
+#![feature(proc_macro_hygiene)]
+#![no_main]
+#![no_std]
+
+use linux_io::{
+    fs::{self, File, OpenOptions},
+    println, process, Stdout,
+};
+use panic_exit as _;
+use ufmt::uwriteln;
+
+const CALIBRATION: &[u8] = b"/tmp/rtfm-calibration.txt\0";
+const LOG: &[u8] = b"/tmp/rtfm.log\0";
+
+#[rtfm::app]
+const APP: () = {
+    #[init]
+    fn init(_: init::Context) {
+        // first run: write the default calibration
+        if fs::metadata(CALIBRATION).is_err() {
+            let mut f = File::create(CALIBRATION).unwrap_or_else(|_| process::exit(1));
+            uwriteln!(&mut f, "gain={}", 42).unwrap_or_else(|_| process::exit(1));
+        }
+
+        let f = File::open(CALIBRATION).unwrap_or_else(|_| process::exit(1));
+        let mut buf = [0; 64];
+        let n = f.read(&mut buf).unwrap_or_else(|_| process::exit(1));
+
+        Stdout.write(b"calibration: ").ok();
+        Stdout.write_all(&buf[..n]).ok();
+
+        println!(
+            "calibration file is {} bytes long",
+            f.metadata().map(|m| m.len()).unwrap_or(0)
+        );
+    }
+
+    #[idle]
+    fn idle(_: idle::Context) -> ! {
+        let mut log = OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(LOG)
+            .unwrap_or_else(|_| process::exit(1));
+
+        uwriteln!(&mut log, "idle started").ok();
+
+        process::exit(0);
+    }
+};
@@ -0,0 +1,50 @@
+//! Streaming data between cores through a `#[channel]`
+
+#![deny(rust_2018_compatibility)]
+#![deny(rust_2018_idioms)]
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+use linux_io::{process, Stderr, Stdout};
+use panic_exit as _;
+
+#[rtfm::app(cores = 2)]
+const APP: () = {
+    // `filter` runs once per batch of samples, not once per sample
+    #[channel(capacity = 8, producer = sensor, consumer = filter, notify = true)]
+    static SAMPLES: u32;
+
+    #[init(core = 0, spawn = [sensor])]
+    fn init(c: init::Context) {
+        c.spawn.sensor().ok();
+    }
+
+    #[task(core = 0)]
+    fn sensor(mut c: sensor::Context) {
+        for sample in 1..=4 {
+            c.channels.SAMPLES.enqueue(sample).ok();
+        }
+
+        Stdout.write(b"[0] sent 4 samples\n").ok();
+    }
+
+    #[task(core = 1)]
+    fn filter(mut c: filter::Context) {
+        static mut COUNT: u32 = 0;
+        static mut SUM: u32 = 0;
+
+        while let Some(sample) = c.channels.SAMPLES.dequeue() {
+            *COUNT += 1;
+            *SUM += sample;
+        }
+
+        if *COUNT == 4 {
+            if *SUM == 10 {
+                Stderr.write(b"[1] received 4 samples\n").ok();
+            }
+
+            process::exit(0);
+        }
+    }
+};
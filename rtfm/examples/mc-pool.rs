@@ -0,0 +1,48 @@
+//! Moving large buffers between cores without copying them
+
+#![deny(rust_2018_compatibility)]
+#![deny(rust_2018_idioms)]
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+use linux_io::{process, Stderr, Stdout};
+use panic_exit as _;
+use rtfm::pool::Box;
+
+#[rtfm::app(cores = 2)]
+const APP: () = {
+    #[pool(size = 4)]
+    static FRAMES: [u8; 4096] = [0; 4096];
+
+    #[init(core = 0, spawn = [fill])]
+    fn init(c: init::Context) {
+        c.spawn.fill().ok();
+    }
+
+    #[task(core = 0, spawn = [check])]
+    fn fill(c: fill::Context) {
+        let mut frame = FRAMES::alloc().unwrap();
+
+        for byte in frame.iter_mut() {
+            *byte = 0xaa;
+        }
+
+        Stdout.write(b"[0] sending frame\n").ok();
+
+        // only the handle is sent to the other core
+        c.spawn.check(frame).ok();
+    }
+
+    #[task(core = 1)]
+    fn check(_: check::Context, frame: Box<[u8; 4096]>) {
+        if frame.iter().all(|byte| *byte == 0xaa) {
+            Stderr.write(b"[1] frame received\n").ok();
+        }
+
+        // return the block to the pool
+        drop(frame);
+
+        process::exit(0);
+    }
+};
@@ -0,0 +1,45 @@
+//! A `#[shared]` resource accessed from two cores
+
+#![deny(rust_2018_compatibility)]
+#![deny(rust_2018_idioms)]
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+use linux_io::{process, Stderr, Stdout};
+use panic_exit as _;
+
+#[rtfm::app(cores = 2)]
+const APP: () = {
+    #[shared(tasks = [producer, consumer])]
+    static mut TABLE: [u32; 4] = [0; 4];
+
+    #[init(core = 0, spawn = [producer])]
+    fn init(c: init::Context) {
+        c.spawn.producer().ok();
+    }
+
+    #[task(core = 0, spawn = [consumer])]
+    fn producer(mut c: producer::Context) {
+        c.shared.TABLE.lock(|table| {
+            for (i, entry) in table.iter_mut().enumerate() {
+                *entry = i as u32 + 1;
+            }
+        });
+
+        Stdout.write(b"[0] table updated\n").ok();
+
+        c.spawn.consumer().ok();
+    }
+
+    #[task(core = 1)]
+    fn consumer(mut c: consumer::Context) {
+        let sum = c.shared.TABLE.lock(|table| table.iter().sum::<u32>());
+
+        if sum == 10 {
+            Stderr.write(b"[1] table is consistent\n").ok();
+        }
+
+        process::exit(0);
+    }
+};
@@ -7,10 +7,9 @@
 
 use core::sync::atomic::{AtomicBool, Ordering};
 
-use linux_io::{process, Stdout};
+use linux_io::{println, process};
+use linux_sys::SigSet;
 use panic_exit as _;
-use ufmt::uwriteln;
-use ufmt_utils::{consts, Ignore, LineBuffered};
 
 static B0: AtomicBool = AtomicBool::new(false);
 static B1: AtomicBool = AtomicBool::new(false);
@@ -47,17 +46,15 @@ const APP: () = {
 
     #[task(core = 1)]
     fn c(_: c::Context) {
-        let mut stdout = LineBuffered::<_, consts::U100>::new(Ignore::new(Stdout));
-
-        let mut sigset = 0;
+        let mut sigset = SigSet::empty();
         unsafe {
-            linux_sys::rt_sigprocmask(linux_sys::SIG_BLOCK, &0, &mut sigset)
+            linux_sys::rt_sigprocmask(linux_sys::SIG_BLOCK, &SigSet::empty(), &mut sigset)
                 .unwrap_or_else(|_| panic!());
         }
 
         // sanity check that this prints the same regardless of what the core does to its own signal
         // mask
-        uwriteln!(&mut stdout, "{:?}", sigset as *const u8).ok();
+        println!("{:?}", sigset.bits() as *const u8);
 
         if B0.swap(true, Ordering::Release) {
             B1.store(true, Ordering::Release);
@@ -0,0 +1,42 @@
+//! Cyclic executive: `sense` and `actuate` are released at fixed offsets within a 500 ms
+//! hyperperiod; `log` is event-driven and runs in the slack
+
+#![deny(rust_2018_compatibility)]
+#![deny(rust_2018_idioms)]
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+use linux_io::{process, Stderr, Stdout};
+use panic_exit as _;
+
+// offsets and worst case execution times are in microseconds
+#[rtfm::app(
+    hyperperiod = 500_000,
+    schedule_table = [(0, sense, 10_000), (250_000, actuate, 10_000)],
+)]
+const APP: () = {
+    #[task(priority = 2)]
+    fn sense(_: sense::Context) {
+        Stdout.write(b"sense\n").ok();
+    }
+
+    #[task(priority = 2, spawn = [log])]
+    fn actuate(c: actuate::Context) {
+        static mut COUNT: u8 = 0;
+
+        Stdout.write(b"actuate\n").ok();
+
+        *COUNT += 1;
+        if *COUNT == 4 {
+            process::exit(0);
+        }
+
+        c.spawn.log().ok();
+    }
+
+    #[task(priority = 1)]
+    fn log(_: log::Context) {
+        Stderr.write(b"log\n").ok();
+    }
+};
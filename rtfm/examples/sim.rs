@@ -0,0 +1,51 @@
+//! Runs a minute worth of timeouts in virtual time
+//!
+//! `cargo run --example sim --features sim`; the output is the same on every run and the program
+//! exits as soon as there's no work left
+
+#![deny(rust_2018_compatibility)]
+#![deny(rust_2018_idioms)]
+#![deny(warnings)]
+#![feature(proc_macro_hygiene)]
+#![no_main]
+#![no_std]
+
+use core::time::Duration;
+
+use linux_io::println;
+use panic_exit as _;
+use rtfm::Instant;
+
+#[rtfm::app]
+const APP: () = {
+    static mut COUNT: u32 = 0;
+
+    #[init(schedule = [tick])]
+    fn init(c: init::Context) {
+        c.schedule
+            .tick(Instant::now() + Duration::from_secs(10))
+            .ok();
+    }
+
+    #[task(priority = 1, resources = [COUNT], schedule = [tick], spawn = [report])]
+    fn tick(mut c: tick::Context) {
+        println!("tick @ {}s", c.scheduled.since_start().as_secs());
+
+        // `report` has higher priority but it can't preempt this critical section
+        let spawn = &c.spawn;
+        c.resources.COUNT.lock(|count| {
+            *count += 1;
+            spawn.report().ok();
+            println!("tick: count = {}", count);
+        });
+
+        if c.scheduled.since_start() < Duration::from_secs(60) {
+            c.schedule.tick(c.scheduled + Duration::from_secs(10)).ok();
+        }
+    }
+
+    #[task(priority = 2, resources = [COUNT])]
+    fn report(c: report::Context) {
+        println!("report: count = {}", c.resources.COUNT);
+    }
+};
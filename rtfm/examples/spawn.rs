@@ -5,10 +5,8 @@
 #![no_main]
 #![no_std]
 
-use linux_io::{process, Stdout};
+use linux_io::{println, process, Stdout};
 use panic_exit as _;
-use ufmt::uwriteln;
-use ufmt_utils::{consts, Ignore, LineBuffered};
 
 #[rtfm::app]
 const APP: () = {
@@ -21,9 +19,7 @@ const APP: () = {
 
     #[task]
     fn foo(_: foo::Context, x: u32) {
-        let mut stdout = LineBuffered::<_, consts::U100>::new(Ignore::new(Stdout));
-
-        uwriteln!(&mut stdout, "foo({})", x).ok();
+        println!("foo({})", x);
 
         process::exit(0);
     }
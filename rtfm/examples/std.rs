@@ -0,0 +1,81 @@
+//! A `std` program: a periodic task runs at a real-time priority while `idle` uses the standard
+//! library to report on it
+//!
+//! `RUSTFLAGS= cargo run --example std --features std`; the empty `RUSTFLAGS` overrides the
+//! linker flags in `.cargo/config`, which are meant for `no_std` programs
+
+#![deny(rust_2018_compatibility)]
+#![deny(rust_2018_idioms)]
+#![deny(warnings)]
+
+use std::{env, thread, time::Duration};
+
+use rtfm::Instant;
+
+pub struct Stats {
+    count: u32,
+    max_latency: Duration,
+}
+
+#[rtfm::app]
+const APP: () = {
+    static mut STATS: Stats = Stats {
+        count: 0,
+        max_latency: Duration::from_secs(0),
+    };
+
+    #[init(schedule = [sample])]
+    fn init(c: init::Context) {
+        c.schedule
+            .sample(Instant::now() + Duration::from_millis(10))
+            .ok();
+    }
+
+    // NOTE the standard library is used from `idle` only. Most of its API is not async-signal-safe
+    // (e.g. allocations or `println!`) so it must not be used from tasks that can preempt `idle`
+    #[idle(resources = [STATS])]
+    fn idle(mut c: idle::Context) -> ! {
+        let reports = env::args()
+            .nth(1)
+            .and_then(|arg| arg.parse().ok())
+            .unwrap_or(3);
+
+        let mut history = Vec::new();
+        for _ in 0..reports {
+            thread::sleep(Duration::from_secs(1));
+
+            let (count, max_latency) = c
+                .resources
+                .STATS
+                .lock(|stats| (stats.count, stats.max_latency));
+            history.push(count);
+
+            println!(
+                "{} samples so far; worst release latency: {:?}",
+                count, max_latency
+            );
+        }
+
+        println!("history: {:?}", history);
+        std::process::exit(0)
+    }
+
+    #[task(priority = 1, resources = [STATS], schedule = [sample])]
+    fn sample(c: sample::Context) {
+        let latency = Instant::now().saturating_duration_since(c.scheduled);
+
+        c.resources.STATS.count += 1;
+        if latency > c.resources.STATS.max_latency {
+            c.resources.STATS.max_latency = latency;
+        }
+
+        c.schedule
+            .sample(c.scheduled + Duration::from_millis(10))
+            .ok();
+    }
+};
+
+fn main() {
+    // anything that must happen before `init` goes here
+    rtfm::start()
+}
@@ -7,10 +7,8 @@
 
 use core::time::Duration;
 
-use linux_io::{process, time::Instant, Stdout};
+use linux_io::{println, process, time::Instant};
 use panic_exit as _;
-use ufmt::uwriteln;
-use ufmt_utils::{consts, Ignore, LineBuffered};
 
 #[rtfm::app]
 const APP: () = {
@@ -36,12 +34,10 @@ const APP: () = {
 
 #[inline(never)]
 fn print(dur: Duration) {
-    let mut stdout = LineBuffered::<_, consts::U100>::new(Ignore::new(Stdout));
-
     // samples 16384
     // quartiles [1708.0, 1752.0, 1965.0]
     // extremes [1609.0, 217872.0]
     // std 4298.72
-    uwriteln!(&mut stdout, "{}", dur.subsec_nanos()).ok();
+    println!("{}", dur.subsec_nanos());
     process::exit(0);
 }
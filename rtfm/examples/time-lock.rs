@@ -5,10 +5,8 @@
 #![no_main]
 #![no_std]
 
-use linux_io::{process, time::Instant, Stdout};
+use linux_io::{println, process, time::Instant};
 use panic_exit as _;
-use ufmt::uwriteln;
-use ufmt_utils::{consts, Ignore, LineBuffered};
 
 #[rtfm::app]
 const APP: () = {
@@ -34,19 +32,15 @@ const APP: () = {
 
 #[inline(never)]
 fn print(before: Instant, inside: Instant, after: Instant) {
-    let mut stdout = LineBuffered::<_, consts::U100>::new(Ignore::new(Stdout));
-
     // samples 32768
     // quartiles [526.0, 540.0, 563.0]
     // extremes [489.0, 31813.0]
     // std 1328.32
-    uwriteln!(
-        &mut stdout,
+    println!(
         "{} {}",
         inside.saturating_duration_since(before).subsec_nanos(),
         after.saturating_duration_since(inside).subsec_nanos(),
-    )
-    .ok();
+    );
 
     process::exit(0);
 }
@@ -7,10 +7,8 @@
 
 use core::time::Duration;
 
-use linux_io::{process, time::Instant, Stdout};
+use linux_io::{println, process, time::Instant};
 use panic_exit as _;
-use ufmt::uwriteln;
-use ufmt_utils::{consts, Ignore, LineBuffered};
 
 #[rtfm::app]
 const APP: () = {
@@ -34,13 +32,11 @@ const APP: () = {
 
 #[inline(never)]
 fn print(dur: Duration) {
-    let mut stdout = LineBuffered::<_, consts::U100>::new(Ignore::new(Stdout));
-
     // samples 16384
     // quartiles [673.0, 693.0, 750.0]
     // extremes [635.0, 34871.0]
     // std 3748.2
-    uwriteln!(&mut stdout, "{}", dur.subsec_nanos()).ok();
+    println!("{}", dur.subsec_nanos());
 
     process::exit(0);
 }
@@ -0,0 +1,46 @@
+//! Runs a task on the next wall-clock second boundary, then once per second
+
+#![deny(rust_2018_compatibility)]
+#![deny(rust_2018_idioms)]
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+use core::time::Duration;
+
+use linux_io::{process, Stdout};
+use panic_exit as _;
+use rtfm::{SystemTime, UNIX_EPOCH};
+
+#[rtfm::app(wall_clock = true)]
+const APP: () = {
+    #[init(schedule = [tick])]
+    fn init(c: init::Context) {
+        c.schedule.tick_at_wall(next_second()).ok();
+    }
+
+    #[task(schedule = [tick])]
+    fn tick(c: tick::Context) {
+        static mut COUNT: u8 = 0;
+
+        // NOTE `SystemTime` is not monotonic; this task is released on time even if the system
+        // time is set while it's pending
+        Stdout.write(b"tick\n").ok();
+
+        *COUNT += 1;
+        if *COUNT >= 3 {
+            process::exit(0);
+        }
+
+        c.schedule.tick_at_wall(next_second()).ok();
+    }
+};
+
+// the start of the next wall-clock second
+fn next_second() -> SystemTime {
+    let since_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+
+    UNIX_EPOCH + Duration::from_secs(since_epoch.as_secs() + 1)
+}
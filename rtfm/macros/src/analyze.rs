@@ -6,13 +6,29 @@ use rtfm_syntax::{
     ast::App,
     Core, P,
 };
+use syn::{Ident, Path, Type};
+
+use crate::{args::Args, items::Items};
 
 /// Signal number
 pub type Signal = u8;
 
 pub struct Analysis {
     parent: P<analyze::Analysis>,
+    /// Consumer tasks activated by `notify` channels, grouped by core and priority:
+    /// (channel, consumer task)
+    pub notifications: BTreeMap<Core, BTreeMap<Priority, Vec<(Ident, Ident)>>>,
+    /// Local ceiling of each `#[shared]` resource (in declaration order) on each core that uses it
+    pub shared: Vec<BTreeMap<Core, Priority>>,
+    /// Types that must be `Send`: those found by `rtfm-syntax` plus the types of the `#[shared]`
+    /// resources used from more than one core
+    pub send_types: BTreeMap<Core, Vec<Box<Type>>>,
     pub signals: BTreeMap<Core, Signals>,
+    pub tables: BTreeMap<Core, Table>,
+    /// Whether each timer queue has a wall-clock (`CLOCK_REALTIME`) counterpart
+    pub wall_clock: bool,
+    /// Instant type of the clock that drives the timer queues; `None` means `rtfm::Instant`
+    pub monotonic: Option<Path>,
 }
 
 impl ops::Deref for Analysis {
@@ -23,6 +39,39 @@ impl ops::Deref for Analysis {
     }
 }
 
+impl Analysis {
+    /// Priority levels that need a task dispatcher on the given core
+    pub fn dispatchers(&self, core: Core) -> BTreeSet<Priority> {
+        self.channels
+            .get(&core)
+            .into_iter()
+            .flat_map(|dispatchers| dispatchers.keys().cloned())
+            .chain(
+                self.tables
+                    .get(&core)
+                    .into_iter()
+                    .flat_map(|table| table.tasks.keys().cloned()),
+            )
+            .chain(
+                self.notifications
+                    .get(&core)
+                    .into_iter()
+                    .flat_map(|levels| levels.keys().cloned()),
+            )
+            .collect()
+    }
+
+    /// Cores that need at least one task dispatcher
+    pub fn receivers(&self) -> BTreeSet<Core> {
+        self.channels
+            .keys()
+            .chain(self.tables.keys())
+            .chain(self.notifications.keys())
+            .cloned()
+            .collect()
+    }
+}
+
 pub struct Signals {
     pub map: BTreeMap<Priority, Signal>,
     pub start: Signal,
@@ -36,13 +85,104 @@ impl Signals {
     }
 }
 
+/// Time-triggered schedule of a single core
+pub struct Table {
+    /// Priority of the signal handler that walks the table
+    pub priority: Priority,
+    /// Length of the cycle, in microseconds
+    pub hyperperiod: u32,
+    /// Releases sorted by offset: (offset in microseconds, task)
+    pub slots: Vec<(u32, Ident)>,
+    /// Tasks released by the table grouped by their priority
+    pub tasks: BTreeMap<Priority, BTreeSet<Ident>>,
+}
+
+/// Core and priority of the software task or `#[idle]` function called `name`
+pub fn locate(app: &App, name: &Ident) -> Option<(Core, Priority)> {
+    if let Some(task) = app.software_tasks.get(name) {
+        return Some((task.args.core, task.args.priority));
+    }
+
+    app.idles
+        .iter()
+        .find(|(_, idle)| idle.name == *name)
+        .map(|(core, _)| (*core, 0))
+}
+
 // Assign a RT signal handler to each priority level
-pub fn app(parent: P<analyze::Analysis>, app: &App) -> P<Analysis> {
-    let mut rt = 0;
+pub fn app(parent: P<analyze::Analysis>, app: &App, args: &Args, items: &Items) -> P<Analysis> {
+    // the local ceiling is the highest priority among the tasks, on that core, that use the resource
+    let shared = items
+        .shared
+        .iter()
+        .map(|res| {
+            let mut ceilings = BTreeMap::new();
+            for task in &res.tasks {
+                let (core, priority) = locate(app, task).expect("UNREACHABLE");
+                let ceiling = ceilings.entry(core).or_insert(0);
+                *ceiling = (*ceiling).max(priority);
+            }
+            ceilings
+        })
+        .collect::<Vec<_>>();
+
+    let mut send_types = parent
+        .send_types
+        .iter()
+        .map(|(core, tys)| (*core, tys.iter().cloned().collect::<Vec<_>>()))
+        .collect::<BTreeMap<_, _>>();
+    for (res, ceilings) in items.shared.iter().zip(&shared) {
+        // the resource will be accessed from more than one thread
+        if ceilings.len() > 1 {
+            for core in ceilings.keys() {
+                send_types
+                    .entry(*core)
+                    .or_insert_with(Vec::new)
+                    .push(res.ty.clone());
+            }
+        }
+    }
+
+    let mut tables = BTreeMap::new();
+    for slot in &args.schedule_table {
+        let task = &app.software_tasks[&slot.task];
+
+        let table = tables.entry(task.args.core).or_insert_with(|| Table {
+            priority: 0,
+            hyperperiod: args
+                .hyperperiod
+                .as_ref()
+                .map(|(hp, _)| hp.of(task.args.core))
+                .unwrap_or(0),
+            slots: vec![],
+            tasks: BTreeMap::new(),
+        });
+
+        table.slots.push((slot.offset, slot.task.clone()));
+        table
+            .tasks
+            .entry(task.args.priority)
+            .or_insert_with(BTreeSet::new)
+            .insert(slot.task.clone());
+    }
+
+    let mut notifications = BTreeMap::new();
+    for channel in items.channels.iter().filter(|channel| channel.notify) {
+        let task = &app.software_tasks[&channel.consumer];
+
+        notifications
+            .entry(task.args.core)
+            .or_insert_with(BTreeMap::new)
+            .entry(task.args.priority)
+            .or_insert_with(Vec::new)
+            .push((channel.name.clone(), channel.consumer.clone()));
+    }
+
+    let mut rt = args.signals().start;
 
     let mut signals = BTreeMap::new();
     for core in 0..app.args.cores {
-        let priorities = app
+        let mut priorities = app
             .software_tasks
             .values()
             .filter_map(|task| {
@@ -56,6 +196,13 @@ pub fn app(parent: P<analyze::Analysis>, app: &App) -> P<Analysis> {
             .chain(parent.timer_queues.get(&core).map(|tq| tq.priority))
             .collect::<BTreeSet<_>>();
 
+        // the schedule table handler runs at a higher priority than everything else on its core
+        if let Some(table) = tables.get_mut(&core) {
+            table.priority = priorities.iter().cloned().max().unwrap_or(0) + 1;
+            table.slots.sort_by_key(|slot| slot.0);
+            priorities.insert(table.priority);
+        }
+
         let map = priorities
             .iter()
             .rev()
@@ -67,5 +214,14 @@ pub fn app(parent: P<analyze::Analysis>, app: &App) -> P<Analysis> {
         rt += len as u8;
     }
 
-    P::new(Analysis { parent, signals })
+    P::new(Analysis {
+        parent,
+        notifications,
+        shared,
+        send_types,
+        signals,
+        tables,
+        wall_clock: args.wall_clock,
+        monotonic: args.monotonic.clone(),
+    })
 }
@@ -0,0 +1,378 @@
+use core::ops::Range;
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use rtfm_syntax::Core;
+use syn::{
+    parse::{self, Parse, ParseStream, Parser},
+    punctuated::Punctuated,
+    spanned::Spanned,
+    Expr, Ident, Lit, Path, RangeLimits, Token,
+};
+
+// Linux supports the real-time signals `SIGRTMIN..=SIGRTMAX`
+const SIGRTMIN: u8 = 32;
+const SIGRTMAX: u8 = 64;
+
+// Linux 5.0 only supports 32 real time signals
+const NSIGNALS: u8 = 32;
+
+// glibc reserves the first two real time signals for its own use (see `man 7 nptl`)
+const NSIGNALS_GLIBC: u8 = 2;
+
+/// `#[app]` arguments that are handled by this crate rather than by `rtfm-syntax`
+pub struct Args {
+    /// Length of the schedule table cycle, in microseconds
+    pub hyperperiod: Option<(Hyperperiod, Span)>,
+
+    /// Time-triggered releases, e.g. `schedule_table = [(0, foo, 100), (500, bar, 200)]`
+    pub schedule_table: Vec<Slot>,
+
+    /// `SCHED_DEADLINE` budget of each core, e.g. `sched_deadline = (1_000, 5_000, 10_000)`
+    pub sched_deadline: Option<Reservation>,
+
+    /// Whether tasks can also be scheduled against the wall clock (`CLOCK_REALTIME`), e.g.
+    /// `wall_clock = true`
+    pub wall_clock: bool,
+
+    /// Instant type of the clock that drives the `schedule` API, e.g.
+    /// `monotonic = rtfm::BootInstant`; it must implement `rtfm::Monotonic`
+    pub monotonic: Option<Path>,
+
+    /// Real-time signals (kernel signal numbers) the runtime can use, e.g. `signals = 40..64`
+    pub signals: Option<(Range<u8>, Span)>,
+}
+
+impl Args {
+    /// The real-time signals the runtime can use, relative to `SIGRTMIN`
+    pub fn signals(&self) -> Range<u8> {
+        if let Some((Range { start, end }, _)) = self.signals {
+            start - SIGRTMIN..end - SIGRTMIN
+        } else if cfg!(feature = "std") {
+            NSIGNALS_GLIBC..NSIGNALS
+        } else {
+            0..NSIGNALS
+        }
+    }
+}
+
+/// Length of the schedule table cycle, in microseconds
+pub enum Hyperperiod {
+    /// The same cycle on every core, e.g. `hyperperiod = 500_000`
+    All(u32),
+    /// One cycle per core, indexed by core number, e.g. `hyperperiod = [500_000, 200_000]`
+    PerCore(Vec<u32>),
+}
+
+impl Hyperperiod {
+    /// The hyperperiod of the table of `core`
+    pub fn of(&self, core: Core) -> u32 {
+        match self {
+            Hyperperiod::All(hp) => *hp,
+            Hyperperiod::PerCore(hps) => hps.get(usize::from(core)).cloned().unwrap_or(0),
+        }
+    }
+}
+
+/// `SCHED_DEADLINE` parameters, in microseconds
+pub struct Reservation {
+    pub runtime: u32,
+    pub deadline: u32,
+    pub period: u32,
+    pub span: Span,
+}
+
+/// An entry of the schedule table
+pub struct Slot {
+    /// Release time relative to the start of the hyperperiod, in microseconds
+    pub offset: u32,
+
+    /// The software task to release
+    pub task: Ident,
+
+    /// Worst case execution time of `task`, in microseconds
+    pub wcet: u32,
+}
+
+struct Arg {
+    name: Ident,
+    value: Expr,
+}
+
+impl Parse for Arg {
+    fn parse(input: ParseStream<'_>) -> parse::Result<Self> {
+        let name = input.parse()?;
+        let _: Token![=] = input.parse()?;
+        let value = input.parse()?;
+
+        Ok(Arg { name, value })
+    }
+}
+
+/// Splits the `#[app]` arguments into the ones we handle and the ones that must be forwarded to
+/// `rtfm-syntax`
+pub fn parse(tokens: TokenStream2) -> parse::Result<(Args, TokenStream2)> {
+    let list = Punctuated::<Arg, Token![,]>::parse_terminated.parse2(tokens)?;
+
+    let mut args = Args {
+        hyperperiod: None,
+        schedule_table: vec![],
+        sched_deadline: None,
+        wall_clock: false,
+        monotonic: None,
+        signals: None,
+    };
+    let mut rest = vec![];
+    let mut has_table = None;
+    let mut has_wall_clock = false;
+    for Arg { name, value } in list {
+        match &*name.to_string() {
+            "hyperperiod" => {
+                if args.hyperperiod.is_some() {
+                    return Err(parse::Error::new(
+                        name.span(),
+                        "argument passed more than once",
+                    ));
+                }
+
+                args.hyperperiod = Some((hyperperiod(&value)?, value.span()));
+            }
+
+            "schedule_table" => {
+                if has_table.is_some() {
+                    return Err(parse::Error::new(
+                        name.span(),
+                        "argument passed more than once",
+                    ));
+                }
+
+                has_table = Some(name.span());
+                args.schedule_table = schedule_table(&value)?;
+            }
+
+            "sched_deadline" => {
+                if args.sched_deadline.is_some() {
+                    return Err(parse::Error::new(
+                        name.span(),
+                        "argument passed more than once",
+                    ));
+                }
+
+                args.sched_deadline = Some(reservation(&value)?);
+            }
+
+            "wall_clock" => {
+                if has_wall_clock {
+                    return Err(parse::Error::new(
+                        name.span(),
+                        "argument passed more than once",
+                    ));
+                }
+
+                has_wall_clock = true;
+                args.wall_clock = bool_lit(&value)?;
+            }
+
+            "monotonic" => {
+                if args.monotonic.is_some() {
+                    return Err(parse::Error::new(
+                        name.span(),
+                        "argument passed more than once",
+                    ));
+                }
+
+                args.monotonic = Some(monotonic(&value)?);
+            }
+
+            "signals" => {
+                if args.signals.is_some() {
+                    return Err(parse::Error::new(
+                        name.span(),
+                        "argument passed more than once",
+                    ));
+                }
+
+                let range = signals(&value)?;
+                if cfg!(feature = "std") && range.start < SIGRTMIN + NSIGNALS_GLIBC {
+                    return Err(parse::Error::new(
+                        value.span(),
+                        "glibc reserves the real-time signals 32 and 33",
+                    ));
+                }
+
+                args.signals = Some((range, value.span()));
+            }
+
+            _ => rest.push(quote!(#name = #value)),
+        }
+    }
+
+    match (has_table, &args.hyperperiod) {
+        (Some(span), None) => {
+            return Err(parse::Error::new(
+                span,
+                "`schedule_table` requires a `hyperperiod` argument",
+            ));
+        }
+
+        (None, Some((_, span))) => {
+            return Err(parse::Error::new(
+                *span,
+                "`hyperperiod` can only be used together with `schedule_table`",
+            ));
+        }
+
+        _ => {}
+    }
+
+    Ok((args, quote!(#(#rest),*)))
+}
+
+// e.g. `[(0, foo, 100), (500, bar, 200)]`
+fn schedule_table(expr: &Expr) -> parse::Result<Vec<Slot>> {
+    const MSG: &str = "expected a list of `(offset, task, wcet)` tuples";
+
+    let array = match expr {
+        Expr::Array(array) => array,
+        _ => return Err(parse::Error::new(expr.span(), MSG)),
+    };
+
+    let mut slots = vec![];
+    for elem in &array.elems {
+        let tuple = match elem {
+            Expr::Tuple(tuple) if tuple.elems.len() == 3 => tuple,
+            _ => return Err(parse::Error::new(elem.span(), MSG)),
+        };
+
+        let offset = u32_lit(&tuple.elems[0])?;
+
+        let task = match &tuple.elems[1] {
+            Expr::Path(path)
+                if path.qself.is_none()
+                    && path.path.leading_colon.is_none()
+                    && path.path.segments.len() == 1 =>
+            {
+                path.path.segments[0].ident.clone()
+            }
+
+            expr => {
+                return Err(parse::Error::new(
+                    expr.span(),
+                    "expected the name of a software task",
+                ));
+            }
+        };
+
+        let wcet = u32_lit(&tuple.elems[2])?;
+
+        slots.push(Slot { offset, task, wcet });
+    }
+
+    Ok(slots)
+}
+
+// e.g. `500_000` or `[500_000, 200_000]`
+fn hyperperiod(expr: &Expr) -> parse::Result<Hyperperiod> {
+    match expr {
+        Expr::Array(array) => array
+            .elems
+            .iter()
+            .map(u32_lit)
+            .collect::<parse::Result<_>>()
+            .map(Hyperperiod::PerCore),
+
+        _ => u32_lit(expr).map(Hyperperiod::All),
+    }
+}
+
+// e.g. `(1_000, 5_000, 10_000)`
+fn reservation(expr: &Expr) -> parse::Result<Reservation> {
+    match expr {
+        Expr::Tuple(tuple) if tuple.elems.len() == 3 => Ok(Reservation {
+            runtime: u32_lit(&tuple.elems[0])?,
+            deadline: u32_lit(&tuple.elems[1])?,
+            period: u32_lit(&tuple.elems[2])?,
+            span: expr.span(),
+        }),
+
+        _ => Err(parse::Error::new(
+            expr.span(),
+            "expected a `(runtime, deadline, period)` tuple",
+        )),
+    }
+}
+
+// e.g. `rtfm::BootInstant`
+fn monotonic(expr: &Expr) -> parse::Result<Path> {
+    match expr {
+        Expr::Path(path) if path.qself.is_none() => {
+            let name = path
+                .path
+                .segments
+                .last()
+                .map(|pair| pair.value().ident.to_string());
+
+            if name.map_or(false, |name| name.starts_with("CLOCK_")) {
+                return Err(parse::Error::new(
+                    expr.span(),
+                    "expected the instant type of the clock, not a clock ID; \
+                     e.g. `rtfm::BootInstant` for `CLOCK_BOOTTIME`",
+                ));
+            }
+
+            Ok(path.path.clone())
+        }
+
+        _ => Err(parse::Error::new(
+            expr.span(),
+            "expected a path to a type that implements `rtfm::Monotonic`",
+        )),
+    }
+}
+
+// e.g. `40..64` or `40..=64`
+fn signals(expr: &Expr) -> parse::Result<Range<u8>> {
+    const MSG: &str = "expected a non-empty range of real-time signals within `32..=64`";
+
+    if let Expr::Range(range) = expr {
+        if let (Some(from), Some(to)) = (&range.from, &range.to) {
+            let start = u32_lit(from)?;
+            let end = u32_lit(to)?
+                + match range.limits {
+                    RangeLimits::HalfOpen(_) => 0,
+                    RangeLimits::Closed(_) => 1,
+                };
+
+            if u32::from(SIGRTMIN) <= start && start < end && end <= u32::from(SIGRTMAX) + 1 {
+                return Ok(start as u8..end as u8);
+            }
+        }
+    }
+
+    Err(parse::Error::new(expr.span(), MSG))
+}
+
+fn bool_lit(expr: &Expr) -> parse::Result<bool> {
+    if let Expr::Lit(lit) = expr {
+        if let Lit::Bool(b) = &lit.lit {
+            return Ok(b.value);
+        }
+    }
+
+    Err(parse::Error::new(expr.span(), "expected `true` or `false`"))
+}
+
+fn u32_lit(expr: &Expr) -> parse::Result<u32> {
+    if let Expr::Lit(lit) = expr {
+        if let Lit::Int(int) = &lit.lit {
+            if int.value() <= u64::from(u32::max_value()) {
+                return Ok(int.value() as u32);
+            }
+        }
+    }
+
+    Err(parse::Error::new(
+        expr.span(),
+        "expected an integer literal in the range 0..=4294967295",
+    ))
+}
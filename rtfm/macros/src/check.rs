@@ -1,13 +1,21 @@
-use std::collections::{BTreeSet, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 
 use proc_macro2::Span;
 use rtfm_syntax::{analyze::Analysis, ast::App};
-use syn::parse;
+use syn::{
+    parse,
+    spanned::Spanned,
+    visit::{self, Visit},
+    Expr, ExprMethodCall, Member, Stmt,
+};
 
-// Linux 5.0 only supports 32 real time signals
-const NSIGNALS: usize = 32;
+use crate::{
+    analyze,
+    args::{Args, Hyperperiod},
+    items::Items,
+};
 
-pub fn app(app: &App, analysis: &Analysis) -> parse::Result<()> {
+pub fn app(app: &App, analysis: &Analysis, args: &Args, items: &Items) -> parse::Result<()> {
     // this RTFM implementation uses the same namespace for all cores so we need to check that the
     // identifiers used for each core `#[init]` and `#[idle]` functions don't collide
     let mut seen = HashSet::new();
@@ -28,6 +36,287 @@ pub fn app(app: &App, analysis: &Analysis) -> parse::Result<()> {
         }
     }
 
+    // the other cores run on threads created with `clone`, which the C library knows nothing about
+    if cfg!(feature = "std") && app.args.cores > 1 {
+        return Err(parse::Error::new(
+            Span::call_site(),
+            "the `std` feature only supports single-core applications",
+        ));
+    }
+
+    if cfg!(feature = "sim") {
+        if app.args.cores > 1 {
+            return Err(parse::Error::new(
+                Span::call_site(),
+                "the simulation backend (`sim` feature) only supports single-core applications",
+            ));
+        }
+
+        if let Some(reservation) = &args.sched_deadline {
+            return Err(parse::Error::new(
+                reservation.span,
+                "`sched_deadline` can't be used with the simulation backend (`sim` feature)",
+            ));
+        }
+
+        // these clocks are not simulated; their deadlines would make the virtual clock jump to
+        // the present
+        if args.wall_clock {
+            return Err(parse::Error::new(
+                Span::call_site(),
+                "`wall_clock` can't be used with the simulation backend (`sim` feature)",
+            ));
+        }
+
+        if let Some(monotonic) = &args.monotonic {
+            return Err(parse::Error::new(
+                monotonic.span(),
+                "the simulation backend (`sim` feature) only supports the default clock \
+                 (`rtfm::Instant`)",
+            ));
+        }
+    }
+
+    // check that the schedule table is feasible
+    let mut tables = BTreeMap::new();
+    for slot in &args.schedule_table {
+        let task = app.software_tasks.get(&slot.task).ok_or_else(|| {
+            parse::Error::new(slot.task.span(), "this is not the name of a software task")
+        })?;
+
+        if !task.inputs.is_empty() {
+            return Err(parse::Error::new(
+                slot.task.span(),
+                "tasks released by the schedule table can't take inputs",
+            ));
+        }
+
+        if slot.wcet == 0 {
+            return Err(parse::Error::new(
+                slot.task.span(),
+                "the worst case execution time must be non-zero",
+            ));
+        }
+
+        tables
+            .entry(task.args.core)
+            .or_insert_with(Vec::new)
+            .push(slot);
+    }
+
+    if let Some((hyperperiod, span)) = &args.hyperperiod {
+        if let Hyperperiod::PerCore(hps) = hyperperiod {
+            if hps.len() != usize::from(app.args.cores) {
+                return Err(parse::Error::new(
+                    *span,
+                    format!(
+                        "expected one hyperperiod per core ({} values)",
+                        app.args.cores
+                    ),
+                ));
+            }
+        }
+
+        for (&core, slots) in tables.iter_mut() {
+            let hyperperiod = hyperperiod.of(core);
+            if hyperperiod == 0 {
+                return Err(parse::Error::new(*span, "the hyperperiod must be non-zero"));
+            }
+
+            slots.sort_by_key(|slot| slot.offset);
+
+            for (i, slot) in slots.iter().enumerate() {
+                if slot.offset >= hyperperiod {
+                    return Err(parse::Error::new(
+                        slot.task.span(),
+                        "this release lies outside the hyperperiod",
+                    ));
+                }
+
+                // the release that follows this one, possibly in the next hyperperiod
+                let next = slots
+                    .get(i + 1)
+                    .map(|next| u64::from(next.offset))
+                    .unwrap_or(u64::from(hyperperiod) + u64::from(slots[0].offset));
+
+                if u64::from(slot.offset) + u64::from(slot.wcet) > next {
+                    return Err(parse::Error::new(
+                        slot.task.span(),
+                        "this release may still be running when the next one is due",
+                    ));
+                }
+            }
+        }
+    }
+
+    // these are the constraints checked by the kernel; see `man 7 sched`
+    if let Some(reservation) = &args.sched_deadline {
+        // NOTE the kernel rejects runtimes smaller than 1024 ns
+        if reservation.runtime < 2 {
+            return Err(parse::Error::new(
+                reservation.span,
+                "the runtime must be at least 2 microseconds",
+            ));
+        }
+
+        if reservation.runtime > reservation.deadline || reservation.deadline > reservation.period {
+            return Err(parse::Error::new(
+                reservation.span,
+                "the budget must satisfy `runtime <= deadline <= period`",
+            ));
+        }
+    }
+
+    // check the `#[shared]` resources
+    if items.shared.len() > usize::from(u8::max_value()) {
+        return Err(parse::Error::new(
+            Span::call_site(),
+            "too many `#[shared]` resources",
+        ));
+    }
+
+    let mut names = HashSet::new();
+    // (core, task priority, index of the resource)
+    let mut accesses = vec![];
+    // (core, local ceiling, index of the resource)
+    let mut ceilings = vec![];
+    for (i, res) in items.shared.iter().enumerate() {
+        if app.resource(&res.name).is_some() || !names.insert(&res.name) {
+            return Err(parse::Error::new(
+                res.name.span(),
+                "this identifier is already being used by another resource",
+            ));
+        }
+
+        let mut tasks = HashSet::new();
+        let mut local = BTreeMap::new();
+        for task in &res.tasks {
+            let (core, priority) = analyze::locate(app, task).ok_or_else(|| {
+                parse::Error::new(
+                    task.span(),
+                    "this is not the name of a software task or an `#[idle]` function",
+                )
+            })?;
+
+            if !tasks.insert(task) {
+                return Err(parse::Error::new(
+                    task.span(),
+                    "this task appears more than once in the list",
+                ));
+            }
+
+            accesses.push((core, priority, i, task));
+
+            let ceiling = local.entry(core).or_insert(0);
+            *ceiling = (*ceiling).max(priority);
+        }
+
+        ceilings.extend(local.into_iter().map(|(core, ceiling)| (core, ceiling, i)));
+    }
+
+    // Locks on `#[shared]` resources must be taken in declaration order to rule out deadlocks
+    // across cores. A task that can preempt the critical section of resource `i` (its priority is
+    // above the local ceiling of `i`) effectively nests its locks inside that critical section so
+    // it must not access any resource declared before `i`. Nested `lock` calls within a single task
+    // are checked further below.
+    for &(core, priority, j, task) in &accesses {
+        for &(core_, ceiling, i) in &ceilings {
+            if core == core_ && priority > ceiling && j < i {
+                return Err(parse::Error::new(
+                    task.span(),
+                    format!(
+                        "this task can preempt a critical section of `{}` and then lock `{}`, \
+                         which is declared before it; this could deadlock. Declare `{}` after \
+                         `{}` or lower the priority of this task",
+                        items.shared[i].name,
+                        items.shared[j].name,
+                        items.shared[j].name,
+                        items.shared[i].name,
+                    ),
+                ));
+            }
+        }
+    }
+
+    // within a task, a `lock` closure must only lock resources declared after the one being held
+    for stmts in app
+        .software_tasks
+        .values()
+        .map(|task| &task.stmts)
+        .chain(app.idles.values().map(|idle| &idle.stmts))
+    {
+        let mut locks = Locks {
+            items,
+            held: vec![],
+            error: None,
+        };
+
+        for stmt in stmts {
+            locks.visit_stmt(stmt);
+        }
+
+        if let Some(e) = locks.error {
+            return Err(e);
+        }
+    }
+
+    // check the `#[channel]`s
+    for channel in &items.channels {
+        if app.resource(&channel.name).is_some() || !names.insert(&channel.name) {
+            return Err(parse::Error::new(
+                channel.name.span(),
+                "this identifier is already being used by another resource",
+            ));
+        }
+
+        for task in &[&channel.producer, &channel.consumer] {
+            if analyze::locate(app, task).is_none() {
+                return Err(parse::Error::new(
+                    task.span(),
+                    "this is not the name of a software task or an `#[idle]` function",
+                ));
+            }
+        }
+
+        if channel.producer == channel.consumer {
+            return Err(parse::Error::new(
+                channel.consumer.span(),
+                "the producer and the consumer must be different tasks",
+            ));
+        }
+
+        if channel.notify {
+            let task = app.software_tasks.get(&channel.consumer).ok_or_else(|| {
+                parse::Error::new(
+                    channel.consumer.span(),
+                    "only software tasks can be notified",
+                )
+            })?;
+
+            if !task.inputs.is_empty() {
+                return Err(parse::Error::new(
+                    channel.consumer.span(),
+                    "tasks notified by a channel can't take inputs",
+                ));
+            }
+        }
+    }
+
+    // check the `#[pool]`s
+    for pool in &items.pools {
+        // NOTE each pool becomes a type so it shares a namespace with the task modules
+        if app.resource(&pool.name).is_some()
+            || analyze::locate(app, &pool.name).is_some()
+            || app.inits.values().any(|init| init.name == pool.name)
+            || !names.insert(&pool.name)
+        {
+            return Err(parse::Error::new(
+                pool.name.span(),
+                "this identifier is already being used by another item",
+            ));
+        }
+    }
+
     // check that there are enough signal handlers to dispatch all tasks
     let signals = app
         .software_tasks
@@ -41,12 +330,94 @@ pub fn app(app: &App, analysis: &Analysis) -> parse::Result<()> {
         )
         .collect::<BTreeSet<_>>();
 
-    if signals.len() > NSIGNALS {
+    // each schedule table has its own, dedicated, signal handler
+    // NOTE only the signals in the `signals` range count
+    if signals.len() + tables.len() > args.signals().len() {
         return Err(parse::Error::new(
-            Span::call_site(),
+            args.signals
+                .as_ref()
+                .map(|(_, span)| *span)
+                .unwrap_or_else(Span::call_site),
             "there are not enough real time signals to dispatch all tasks",
         ));
     }
 
     Ok(())
 }
+
+// Walks the body of a task looking for `lock` calls on `#[shared]` resources nested in the closure
+// of another such `lock` call
+struct Locks<'a> {
+    items: &'a Items,
+    // indices of the resources whose closures enclose the current expression
+    held: Vec<usize>,
+    error: Option<parse::Error>,
+}
+
+impl<'a> Locks<'a> {
+    // returns the index of the `#[shared]` resource that `expr` (e.g. `c.shared.FOO` or `FOO`)
+    // refers to
+    fn shared(&self, expr: &Expr) -> Option<usize> {
+        let name = match expr {
+            Expr::Field(field) => match &field.member {
+                Member::Named(name) => name,
+                Member::Unnamed(_) => return None,
+            },
+            Expr::Path(path) if path.qself.is_none() && path.path.segments.len() == 1 => {
+                &path.path.segments.iter().next()?.ident
+            }
+            _ => return None,
+        };
+
+        self.items.shared.iter().position(|res| res.name == *name)
+    }
+}
+
+impl<'a, 'ast> Visit<'ast> for Locks<'a> {
+    fn visit_expr_method_call(&mut self, call: &'ast ExprMethodCall) {
+        if self.error.is_some() {
+            return;
+        }
+
+        let inner = if call.method == "lock" {
+            self.shared(&call.receiver)
+        } else {
+            None
+        };
+
+        let inner = match inner {
+            Some(inner) => inner,
+            None => return visit::visit_expr_method_call(self, call),
+        };
+
+        if let Some(&outer) = self.held.iter().find(|&&outer| outer >= inner) {
+            let (outer, inner) = (&self.items.shared[outer].name, &self.items.shared[inner].name);
+            self.error = Some(parse::Error::new(
+                call.method.span(),
+                if outer == inner {
+                    format!("`{}` is already locked by an enclosing `lock` call", inner)
+                } else {
+                    format!(
+                        "`{}` is declared before `{}` so it can't be locked while `{}` is \
+                         held; this could deadlock. Lock `{}` first or declare it after `{}`",
+                        inner, outer, outer, inner, outer,
+                    )
+                },
+            ));
+            return;
+        }
+
+        self.visit_expr(&call.receiver);
+        self.held.push(inner);
+        for arg in &call.args {
+            self.visit_expr(arg);
+        }
+        self.held.pop();
+    }
+
+    fn visit_stmt(&mut self, stmt: &'ast Stmt) {
+        if self.error.is_none() {
+            visit::visit_stmt(self, stmt)
+        }
+    }
+}
@@ -3,21 +3,27 @@ use proc_macro::TokenStream;
 use quote::quote;
 use rtfm_syntax::ast::App;
 
-use crate::analyze::Analysis;
+use crate::{analyze::Analysis, args::Args, items::Items};
 
 mod assertions;
+mod channels;
+mod channels_struct;
 mod childs;
 mod dispatchers;
 mod idle;
 mod init;
 mod locals;
 mod module;
+mod pools;
 mod post_init;
 mod pre_init;
 mod resources;
 mod resources_struct;
 mod schedule;
 mod schedule_body;
+mod schedule_table;
+mod shared;
+mod shared_struct;
 mod spawn;
 mod spawn_body;
 mod tasks;
@@ -25,12 +31,16 @@ mod timer_body;
 mod timer_queue;
 mod util;
 
-pub fn app(app: &App, analysis: &Analysis) -> TokenStream {
+pub fn app(app: &App, analysis: &Analysis, args: &Args, items: &Items) -> TokenStream {
     let assertion_stmts = assertions::codegen(analysis);
 
-    let (const_app_pre_init, pre_init_stmts) = pre_init::codegen(app, analysis);
+    let (const_app_channels, channels_stmts) = channels::codegen(app, items);
 
-    let const_app_childs = childs::codegen(app, analysis);
+    let (const_app_pools, root_pools, pools_stmts) = pools::codegen(items);
+
+    let (const_app_pre_init, pre_init_stmts) = pre_init::codegen(app, analysis, args);
+
+    let const_app_childs = childs::codegen(app, analysis, args);
 
     let (
         const_app_init,
@@ -45,12 +55,14 @@ pub fn app(app: &App, analysis: &Analysis) -> TokenStream {
     let (const_app_post_init, post_init_stmts) = post_init::codegen(analysis);
 
     let (const_app_idle, mod_idle, idle_locals, idle_resources, user_idle, call_idle) =
-        idle::codegen(app, analysis);
+        idle::codegen(app, analysis, items);
 
     let (const_app_resources, mod_resources) = resources::codegen(app, analysis);
 
+    let (const_app_shared, mod_shared) = shared::codegen(analysis, items);
+
     let (const_app_tasks, task_mods, task_locals, task_resources, user_tasks) =
-        tasks::codegen(app, analysis);
+        tasks::codegen(app, analysis, items);
 
     let const_app_dispatchers = dispatchers::codegen(app, analysis);
 
@@ -60,6 +72,15 @@ pub fn app(app: &App, analysis: &Analysis) -> TokenStream {
 
     let const_app_schedule = schedule::codegen(app, analysis);
 
+    let const_app_schedule_table = schedule_table::codegen(app, analysis);
+
+    // with the `std` feature the C library provides `main`; `rtfm::start` calls this one instead
+    let main_attr = if cfg!(feature = "std") {
+        quote!(#[export_name = "__rtfm_main"])
+    } else {
+        quote!(#[no_mangle])
+    };
+
     let name = &app.name;
     quote!(
         #(#user_init)*
@@ -68,6 +89,10 @@ pub fn app(app: &App, analysis: &Analysis) -> TokenStream {
 
         #mod_resources
 
+        #mod_shared
+
+        #(#root_pools)*
+
         #(#user_tasks)*
 
         #(#init_locals)*
@@ -92,6 +117,10 @@ pub fn app(app: &App, analysis: &Analysis) -> TokenStream {
 
         /// Implementation details
         const #name: () = {
+            #(#const_app_channels)*
+
+            #(#const_app_pools)*
+
             #(#const_app_pre_init)*
 
             #(#const_app_childs)*
@@ -104,6 +133,8 @@ pub fn app(app: &App, analysis: &Analysis) -> TokenStream {
 
             #(#const_app_resources)*
 
+            #(#const_app_shared)*
+
             #(#const_app_tasks)*
 
             #(#const_app_dispatchers)*
@@ -114,10 +145,16 @@ pub fn app(app: &App, analysis: &Analysis) -> TokenStream {
 
             #(#const_app_schedule)*
 
-            #[no_mangle]
-            unsafe fn main() -> ! {
+            #(#const_app_schedule_table)*
+
+            #main_attr
+            unsafe fn main() -> u8 {
                 #(#assertion_stmts)*
 
+                #(#channels_stmts)*
+
+                #(#pools_stmts)*
+
                 #(#pre_init_stmts)*
 
                 #call_init
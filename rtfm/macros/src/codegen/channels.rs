@@ -0,0 +1,69 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use rtfm_syntax::ast::App;
+
+use crate::{analyze, codegen::util, items::Items};
+
+pub fn codegen(
+    app: &App,
+    items: &Items,
+) -> (
+    // const_app
+    Vec<TokenStream2>,
+    // stmts
+    Vec<TokenStream2>,
+) {
+    let mut const_app = vec![];
+    let mut stmts = vec![];
+
+    for channel in &items.channels {
+        let attrs = &channel.attrs;
+        let name = &channel.name;
+        let ty = &channel.ty;
+        let cap_ty = util::typenum_capacity(channel.capacity, true);
+
+        const_app.push(quote!(
+            #(#attrs)*
+            static mut #name: rtfm::export::Queue<#ty, #cap_ty, u8> =
+                rtfm::export::Queue(rtfm::export::iQueue::u8());
+        ));
+
+        let p = util::producer_ident(name);
+        let c = util::consumer_ident(name);
+        const_app.push(quote!(
+            /// Producer endpoint of the previous channel
+            static mut #p: core::mem::MaybeUninit<
+                rtfm::export::Producer<'static, #ty, #cap_ty, u8>,
+            > = core::mem::MaybeUninit::uninit();
+
+            /// Consumer endpoint of the previous channel
+            static mut #c: core::mem::MaybeUninit<
+                rtfm::export::Consumer<'static, #ty, #cap_ty, u8>,
+            > = core::mem::MaybeUninit::uninit();
+        ));
+
+        if channel.notify {
+            let pending = util::pending_ident(name);
+            const_app.push(quote!(
+                /// Whether the consumer of the previous channel has a pending activation
+                static #pending: rtfm::export::Pending = rtfm::export::Pending::new();
+            ));
+        }
+
+        let producer = analyze::locate(app, &channel.producer).expect("UNREACHABLE");
+        let consumer = analyze::locate(app, &channel.consumer).expect("UNREACHABLE");
+        if producer.0 != consumer.0 {
+            // items will be moved across threads
+            stmts.push(quote!(rtfm::export::assert_send::<#ty>();));
+        }
+
+        // NOTE this runs before any other thread is spawned
+        stmts.push(quote!({
+            let (p, c) = #name.split();
+            #p.as_mut_ptr().write(p);
+            #c.as_mut_ptr().write(c);
+        }));
+    }
+
+    (const_app, stmts)
+}
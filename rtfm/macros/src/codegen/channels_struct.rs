@@ -0,0 +1,92 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use rtfm_syntax::{ast::App, Context};
+
+use crate::{analyze::Analysis, codegen::util, items::Items};
+
+pub fn codegen(
+    context: Context,
+    app: &App,
+    analysis: &Analysis,
+    items: &Items,
+) -> (TokenStream2, TokenStream2) {
+    let name = context.ident(app);
+
+    let mut fields = vec![];
+    let mut values = vec![];
+    for channel in items.channels_of(&name) {
+        let ty = &channel.ty;
+        let cap_ty = util::typenum_capacity(channel.capacity, true);
+        let ch = &channel.name;
+
+        if channel.producer == name {
+            let p = util::producer_ident(ch);
+
+            if channel.notify {
+                let task = &app.software_tasks[&channel.consumer];
+                let (core, priority) = (task.args.core, task.args.priority);
+
+                let tid = if !util::directed_signals(app) {
+                    quote!(None)
+                } else {
+                    let tid = util::tid_ident(core);
+                    quote!(Some(#tid.get()))
+                };
+                let signo = analysis.signals[&core].map[&priority];
+                let t = util::spawn_t_ident(core, priority);
+                let variant = util::notify_ident(ch);
+                let pending = util::pending_ident(ch);
+
+                fields.push(quote!(
+                    pub #ch: rtfm::channel::Sender<'a, #ty, #cap_ty>
+                ));
+
+                values.push(quote!(
+                    #ch: rtfm::channel::Sender::new(
+                        &mut *#p.as_mut_ptr(),
+                        &#pending,
+                        TGID.get(),
+                        #tid,
+                        #signo,
+                        #t::#variant as u8,
+                    )
+                ));
+            } else {
+                fields.push(quote!(
+                    pub #ch: &'a mut rtfm::export::Producer<'static, #ty, #cap_ty, u8>
+                ));
+
+                values.push(quote!(#ch: &mut *#p.as_mut_ptr()));
+            }
+        } else {
+            let c = util::consumer_ident(ch);
+
+            fields.push(quote!(
+                pub #ch: &'a mut rtfm::export::Consumer<'static, #ty, #cap_ty, u8>
+            ));
+
+            values.push(quote!(#ch: &mut *#c.as_mut_ptr()));
+        }
+    }
+
+    let doc = format!("Channel endpoints `{}` has access to", name);
+    let ident = util::channels_ident(context, app);
+    let item = quote!(
+        #[allow(non_snake_case)]
+        #[doc = #doc]
+        pub struct #ident<'a> {
+            #(#fields,)*
+        }
+    );
+    let constructor = quote!(
+        impl<'a> #ident<'a> {
+            #[inline(always)]
+            unsafe fn new() -> Self {
+                #ident {
+                    #(#values,)*
+                }
+            }
+        }
+    );
+    (item, constructor)
+}
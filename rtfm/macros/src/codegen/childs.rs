@@ -4,9 +4,9 @@ use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
 use rtfm_syntax::ast::App;
 
-use crate::{analyze::Analysis, codegen::util};
+use crate::{analyze::Analysis, args::Args, codegen::util};
 
-pub fn codegen(app: &App, analysis: &Analysis) -> Vec<TokenStream2> {
+pub fn codegen(app: &App, analysis: &Analysis, args: &Args) -> Vec<TokenStream2> {
     let mut const_app = vec![];
 
     // initialize the other threads and the `TID`s
@@ -21,11 +21,15 @@ pub fn codegen(app: &App, analysis: &Analysis) -> Vec<TokenStream2> {
 
         let mut stmts = vec![];
 
-        // spin yield until this thread has been migrated to a different CPU
+        // sleep until this thread has been migrated to a different CPU
         stmts.push(quote!(
             #tid.wait();
         ));
 
+        if let Some(reservation) = &args.sched_deadline {
+            stmts.push(util::set_deadline(reservation));
+        }
+
         if let Some(init) = app.inits.get(&core) {
             let name = &init.name;
             stmts.push(quote!(
@@ -1,3 +1,5 @@
+use std::collections::{BTreeMap, BTreeSet};
+
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
 use rtfm_syntax::ast::App;
@@ -8,13 +10,32 @@ use crate::{
 };
 
 pub fn codegen(app: &App, analysis: &Analysis) -> Vec<TokenStream2> {
+    let monotonic = util::monotonic(analysis);
     let mut items = vec![];
 
-    for (&receiver, dispatchers) in &analysis.channels {
+    for receiver in analysis.receivers() {
         let signals = &analysis.signals[&receiver];
+        let no_dispatchers = BTreeMap::new();
+        let dispatchers = analysis.channels.get(&receiver).unwrap_or(&no_dispatchers);
+        let table = analysis.tables.get(&receiver);
+        let notifications = analysis.notifications.get(&receiver);
+
+        let levels = analysis.dispatchers(receiver);
+        for &level in &levels {
+            let no_channels = BTreeMap::new();
+            let channels = dispatchers.get(&level).unwrap_or(&no_channels);
+            // tasks released by the schedule table
+            let no_tasks = BTreeSet::new();
+            let table_tasks = table
+                .and_then(|table| table.tasks.get(&level))
+                .unwrap_or(&no_tasks);
+            // consumer tasks activated by `notify` channels
+            let no_notifications = vec![];
+            let notified = notifications
+                .and_then(|levels| levels.get(&level))
+                .unwrap_or(&no_notifications);
 
-        for (&level, channels) in dispatchers {
-            let variants = channels
+            let mut variants = channels
                 .iter()
                 .flat_map(|(&sender, channel)| {
                     channel.tasks.iter().map(move |name| {
@@ -28,6 +49,16 @@ pub fn codegen(app: &App, analysis: &Analysis) -> Vec<TokenStream2> {
                     })
                 })
                 .collect::<Vec<_>>();
+            variants.extend(table_tasks.iter().map(|name| {
+                let task = util::table_task_ident(name);
+
+                quote!(#task)
+            }));
+            variants.extend(notified.iter().map(|(channel, _)| {
+                let variant = util::notify_ident(channel);
+
+                quote!(#variant)
+            }));
 
             let t = util::spawn_t_ident(receiver, level);
             let doc = format!(
@@ -44,7 +75,7 @@ pub fn codegen(app: &App, analysis: &Analysis) -> Vec<TokenStream2> {
                 }
             ));
 
-            let arms = channels
+            let mut arms = channels
                 .iter()
                 .flat_map(|(&sender, channel)| {
                     let t = &t;
@@ -100,6 +131,64 @@ pub fn codegen(app: &App, analysis: &Analysis) -> Vec<TokenStream2> {
                 })
                 .collect::<Vec<_>>();
 
+            for name in table_tasks {
+                let (let_instant, instant) = if app.uses_schedule(receiver) {
+                    let release = util::release_ident(name);
+
+                    (
+                        Some(quote!(let instant = #release.as_ptr().read();)),
+                        Some(quote!(, instant)),
+                    )
+                } else {
+                    (None, None)
+                };
+
+                let variant = util::table_task_ident(name);
+                arms.push(quote!(
+                    #t::#variant => {
+                        #let_instant
+                        let priority = &rtfm::export::Priority::new(PRIORITY);
+                        #name(
+                            #name::Locals::new(),
+                            #name::Context::new(priority #instant)
+                        )
+                    }
+                ));
+            }
+
+            for (channel, name) in notified {
+                let (let_instant, instant) = if app.uses_schedule(receiver) {
+                    (
+                        Some(quote!(let instant = <#monotonic as rtfm::Monotonic>::now();)),
+                        Some(quote!(, instant)),
+                    )
+                } else {
+                    (None, None)
+                };
+
+                let pending = util::pending_ident(channel);
+                let variant = util::notify_ident(channel);
+                arms.push(quote!(
+                    #t::#variant => {
+                        // NOTE items enqueued from now on will activate this task again
+                        #pending.clear();
+                        #let_instant
+                        let priority = &rtfm::export::Priority::new(PRIORITY);
+                        #name(
+                            #name::Locals::new(),
+                            #name::Context::new(priority #instant)
+                        )
+                    }
+                ));
+            }
+
+            // only `spawn`-ed and `schedule`-d tasks use an input slot
+            let let_index = if channels.is_empty() {
+                None
+            } else {
+                Some(quote!(let index = (value & 0xff) as u8;))
+            };
+
             let handler = util::rt_ident(signals.map[&level]);
             if analysis
                 .timer_queues
@@ -125,14 +214,21 @@ pub fn codegen(app: &App, analysis: &Analysis) -> Vec<TokenStream2> {
                             /// The priority of this interrupt handler
                             const PRIORITY: u8 = #level;
 
-                            if si.si_code == rtfm::export::SI_QUEUE {
-                                let task: #t = core::mem::transmute((si.si_value >> 8) as u8);
-                                let index = (si.si_value & 0xff) as u8;
-                                match task {
-                                    #(#arms)*
+                            match si.value() {
+                                // a message sent by `spawn` or `schedule`
+                                Some(value) if si.si_code == rtfm::export::SI_QUEUE => {
+                                    let value = value.sival_int;
+                                    let task: #t = core::mem::transmute((value >> 8) as u8);
+                                    #let_index
+                                    match task {
+                                        #(#arms)*
+                                    }
+                                }
+
+                                // a timer expiration
+                                _ => {
+                                    #tq
                                 }
-                            } else {
-                                #tq
                             }
                         }
                     }
@@ -151,8 +247,13 @@ pub fn codegen(app: &App, analysis: &Analysis) -> Vec<TokenStream2> {
                             /// The priority of this interrupt handler
                             const PRIORITY: u8 = #level;
 
-                            let task: #t = core::mem::transmute((si.si_value >> 8) as u8);
-                            let index = (si.si_value & 0xff) as u8;
+                            let value = match si.value() {
+                                Some(value) => value.sival_int,
+                                // not sent by `spawn` or `schedule`
+                                None => return,
+                            };
+                            let task: #t = core::mem::transmute((value >> 8) as u8);
+                            #let_index
                             match task {
                                 #(#arms)*
                             }
@@ -166,7 +267,7 @@ pub fn codegen(app: &App, analysis: &Analysis) -> Vec<TokenStream2> {
         if let Some(timer_queue) = analysis.timer_queues.get(&receiver) {
             let priority = timer_queue.priority;
 
-            if !dispatchers.contains_key(&priority) {
+            if !levels.contains(&priority) {
                 let handler = util::rt_ident(signals.map[&priority]);
                 let tqh =
                     timer_body::codegen(receiver, &analysis.timer_queues[&receiver], app, analysis);
@@ -5,12 +5,14 @@ use rtfm_syntax::{ast::App, Context};
 
 use crate::{
     analyze::Analysis,
-    codegen::{locals, module, resources_struct},
+    codegen::{channels_struct, locals, module, resources_struct, shared_struct},
+    items::Items,
 };
 
 pub fn codegen(
     app: &App,
     analysis: &Analysis,
+    items: &Items,
 ) -> (
     // const_app_idle
     Vec<TokenStream2>,
@@ -46,6 +48,23 @@ pub fn codegen(
         }
 
         let name = &idle.name;
+        let has_shared = items.shared_by(name).next().is_some();
+        if has_shared {
+            let (item, constructor) = shared_struct::codegen(Context::Idle(core), app, items);
+
+            idle_resources.push(item);
+            const_app.push(constructor);
+        }
+
+        let has_channels = items.channels_of(name).next().is_some();
+        if has_channels {
+            let (item, constructor) =
+                channels_struct::codegen(Context::Idle(core), app, analysis, items);
+
+            idle_resources.push(item);
+            const_app.push(constructor);
+        }
+
         if core == 0 {
             call_idle = quote!(#name(
                 #name::Locals::new(),
@@ -71,10 +90,13 @@ pub fn codegen(
         mod_idle.push(module::codegen(
             Context::Idle(core),
             (!idle.args.resources.is_empty(), needs_lt),
+            has_shared,
+            has_channels,
             !idle.args.schedule.is_empty(),
             !idle.args.spawn.is_empty(),
             false,
             app,
+            analysis,
         ));
     }
 
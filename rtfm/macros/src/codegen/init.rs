@@ -98,10 +98,13 @@ pub fn codegen(
         mod_init.push(module::codegen(
             Context::Init(core),
             (!init.args.resources.is_empty(), needs_lt),
+            false,
+            false,
             !init.args.schedule.is_empty(),
             !init.args.spawn.is_empty(),
             has_late_resources,
             app,
+            analysis,
         ));
     }
 
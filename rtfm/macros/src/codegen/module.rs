@@ -2,21 +2,25 @@ use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
 use rtfm_syntax::{ast::App, Context};
 
-use crate::codegen::util;
+use crate::{analyze::Analysis, codegen::util};
 
 pub fn codegen(
     ctxt: Context,
     resources: (/* has */ bool, /* 'a */ bool),
+    shared: bool,
+    channels: bool,
     schedule: bool,
     spawn: bool,
     late_resources: bool,
     app: &App,
+    analysis: &Analysis,
 ) -> TokenStream2 {
     let mut items = vec![];
     let mut fields = vec![];
     let mut values = vec![];
 
     let name = ctxt.ident(app);
+    let monotonic = util::monotonic(analysis);
 
     let core = ctxt.core(app);
     let mut needs_instant = false;
@@ -30,7 +34,7 @@ pub fn codegen(
             if app.uses_schedule(core) {
                 fields.push(quote!(
                     /// The time at which this task was scheduled to run
-                    pub scheduled: rtfm::Instant
+                    pub scheduled: #monotonic
                 ));
 
                 values.push(quote!(scheduled: instant));
@@ -73,6 +77,40 @@ pub fn codegen(
         values.push(quote!(resources: Resources::new(#priority)));
     }
 
+    if shared {
+        let ident = util::shared_ident(ctxt, app);
+        lt = Some(quote!('a));
+
+        items.push(quote!(
+            #[doc(inline)]
+            pub use super::#ident as Shared;
+        ));
+
+        fields.push(quote!(
+            /// `#[shared]` resources this task has access to
+            pub shared: Shared<'a>
+        ));
+
+        values.push(quote!(shared: Shared::new(priority)));
+    }
+
+    if channels {
+        let ident = util::channels_ident(ctxt, app);
+        lt = Some(quote!('a));
+
+        items.push(quote!(
+            #[doc(inline)]
+            pub use super::#ident as Channels;
+        ));
+
+        fields.push(quote!(
+            /// Channel endpoints this task has access to
+            pub channels: Channels<'a>
+        ));
+
+        values.push(quote!(channels: Channels::new()));
+    }
+
     if schedule {
         let doc = "Tasks that can be `schedule`-d from this context";
         if ctxt.is_init() {
@@ -162,11 +200,11 @@ pub fn codegen(
                 let instant_field = if app.uses_schedule(core) {
                     needs_instant = true;
                     instant_method = Some(quote!(
-                        pub unsafe fn instant(&self) -> rtfm::Instant {
+                        pub unsafe fn instant(&self) -> #monotonic {
                             self.instant
                         }
                     ));
-                    Some(quote!(instant: rtfm::Instant,))
+                    Some(quote!(instant: #monotonic,))
                 } else {
                     None
                 };
@@ -225,7 +263,7 @@ pub fn codegen(
     };
 
     let instant = if needs_instant {
-        Some(quote!(, instant: rtfm::Instant))
+        Some(quote!(, instant: #monotonic))
     } else {
         None
     };
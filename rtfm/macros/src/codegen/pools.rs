@@ -0,0 +1,61 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+
+use crate::{codegen::util, items::Items};
+
+pub fn codegen(
+    items: &Items,
+) -> (
+    // const_app
+    Vec<TokenStream2>,
+    // root
+    Vec<TokenStream2>,
+    // stmts
+    Vec<TokenStream2>,
+) {
+    let mut const_app = vec![];
+    let mut root = vec![];
+    let mut stmts = vec![];
+
+    for pool in &items.pools {
+        let attrs = &pool.attrs;
+        let expr = &pool.expr;
+        let name = &pool.name;
+        let ty = &pool.ty;
+
+        let doc = format!("Memory pool of {} blocks", pool.size);
+        root.push(quote!(
+            #(#attrs)*
+            #[allow(dead_code)]
+            #[allow(non_camel_case_types)]
+            #[doc = #doc]
+            struct #name;
+        ));
+
+        let nodes = util::nodes_ident(name);
+        let pool_ = util::pool_ident(name);
+        let cap = util::capacity_literal(pool.size);
+        let elems = (0..pool.size).map(|_| quote!(rtfm::pool::Node::new(#expr)));
+        const_app.push(quote!(
+            /// Memory blocks of the previous pool
+            static mut #nodes: [rtfm::pool::Node<#ty>; #cap] = [#(#elems,)*];
+
+            static #pool_: rtfm::pool::Pool<#ty> = rtfm::pool::Pool::new();
+
+            impl #name {
+                /// Takes a block from the pool; returns `None` if the pool is exhausted
+                ///
+                /// NOTE blocks are *not* reset when they return to the pool
+                #[allow(dead_code)]
+                fn alloc() -> Option<rtfm::pool::Box<#ty>> {
+                    #pool_.alloc()
+                }
+            }
+        ));
+
+        // NOTE this runs before any other thread is spawned
+        stmts.push(quote!(#pool_.init(&mut #nodes);));
+    }
+
+    (const_app, root, stmts)
+}
@@ -1,25 +1,37 @@
 use core::ops::Range;
-
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use rtfm_syntax::ast::App;
+use rtfm_syntax::{ast::App, Core};
 
-use crate::{analyze::Analysis, codegen::util};
+use crate::{analyze::Analysis, args::Args, codegen::util};
 
-pub fn codegen(app: &App, analysis: &Analysis) -> (Vec<TokenStream2>, Vec<TokenStream2>) {
+pub fn codegen(
+    app: &App,
+    analysis: &Analysis,
+    args: &Args,
+) -> (Vec<TokenStream2>, Vec<TokenStream2>) {
     let mut const_app = vec![];
     let mut stmts = vec![];
+    let monotonic = util::monotonic(analysis);
 
-    let signo_max = match analysis
+    // NOTE signals are allocated contiguously, starting from the start of the `signals` range
+    let used = match analysis
         .signals
         .values()
         .flat_map(|signals| signals.map.values())
         .max()
     {
-        Some(signo) => quote!(Some(#signo)),
+        Some(signo) => {
+            let start = args.signals().start;
+            let end = signo + 1;
+            quote!(Some(#start..#end))
+        }
         None => quote!(None),
     };
-    stmts.push(quote!(rtfm::export::init_runtime(#signo_max);));
+    // NOTE the kernel doesn't let `SCHED_DEADLINE` threads have a CPU affinity narrower than the
+    // whole system so threads are not pinned to cores in that case
+    let pin = args.sched_deadline.is_none();
+    stmts.push(quote!(rtfm::export::init_runtime(#used, #pin);));
 
     // populate the `FreeQueue`s
     for (name, senders) in &analysis.free_queues {
@@ -55,24 +67,62 @@ pub fn codegen(app: &App, analysis: &Analysis) -> (Vec<TokenStream2>, Vec<TokenS
         let timer = util::timer_ident(0);
         let signo = analysis.signals[&0].map[&tq.priority];
 
-        let tid = if app.args.cores == 1 {
+        let tid = if !util::directed_signals(app) {
             quote!(None)
         } else {
-            quote!(Some(tgid))
+            quote!(Some(rtfm::export::gettid()))
         };
         stmts.push(quote!(
-            #timer.init(rtfm::export::timer_create(#tid, #signo));
+            #timer.init(rtfm::export::timer_create(
+                <#monotonic as rtfm::Monotonic>::CLOCK,
+                #tid,
+                #signo,
+            ));
         ));
+
+        if analysis.wall_clock {
+            let timer = util::wall_timer_ident(0);
+
+            stmts.push(quote!(
+                #timer.init(rtfm::export::timer_create(
+                    rtfm::export::CLOCK_REALTIME,
+                    #tid,
+                    #signo,
+                ));
+            ));
+        }
+    }
+
+    // all the schedule tables start at the same instant, so they stay in phase, one (longest)
+    // hyperperiod from now
+    if let Some(hyperperiod) = analysis.tables.values().map(|table| table.hyperperiod).max() {
+        let hyperperiod = u64::from(hyperperiod);
+
+        stmts.push(quote!(
+            let epoch = <#monotonic as rtfm::Monotonic>::now() + core::time::Duration::from_micros(#hyperperiod);
+        ));
+    }
+
+    // start the core #0 schedule table
+    if analysis.tables.contains_key(&0) {
+        let tid = if !util::directed_signals(app) {
+            quote!(None)
+        } else {
+            quote!(Some(rtfm::export::gettid()))
+        };
+
+        stmts.push(table_start(0, tid, analysis));
     }
 
     // register signal handlers
-    // NOTE iterating analysis.channels instead of analysis.signals avoid referring to non-existent
-    // (not codegen-ed) signal handlers
-    for (&core, dispatchers) in &analysis.channels {
+    // NOTE iterating analysis.receivers() instead of analysis.signals avoid referring to
+    // non-existent (not codegen-ed) signal handlers
+    for core in analysis.receivers() {
         let signals = &analysis.signals[&core];
 
         let Range { start, end } = signals.range();
-        for priority in dispatchers.keys() {
+        let dispatchers = analysis.dispatchers(core);
+        for priority in &dispatchers {
             let rt = util::rt_ident(signals.map[priority]);
 
             stmts.push(quote!(
@@ -82,7 +132,7 @@ pub fn codegen(app: &App, analysis: &Analysis) -> (Vec<TokenStream2>, Vec<TokenS
 
         // the timer handler may be its own signal handler
         if let Some(tq) = analysis.timer_queues.get(&core) {
-            if !dispatchers.contains_key(&tq.priority) {
+            if !dispatchers.contains(&tq.priority) {
                 let priority = tq.priority;
                 let rt = util::rt_ident(signals.map[&priority]);
 
@@ -91,16 +141,27 @@ pub fn codegen(app: &App, analysis: &Analysis) -> (Vec<TokenStream2>, Vec<TokenS
                 ));
             }
         }
+
+        // the schedule table handler is always its own signal handler
+        if let Some(table) = analysis.tables.get(&core) {
+            let priority = table.priority;
+            let rt = util::rt_ident(signals.map[&priority]);
+
+            stmts.push(quote!(
+                rtfm::export::register(#start..#end, #priority, #rt);
+            ));
+        }
     }
 
-    if app.args.cores > 1 {
+    if util::directed_signals(app) {
         let tid = util::tid_ident(0);
         const_app.push(quote!(
             static #tid: rtfm::export::Pid = rtfm::export::Pid::uninit();
         ));
 
+        // NOTE with the `std` feature `rtfm::start` may be called from any thread
         stmts.push(quote!(
-            #tid.init(tgid);
+            #tid.init(rtfm::export::gettid());
         ));
     }
 
@@ -127,18 +188,72 @@ pub fn codegen(app: &App, analysis: &Analysis) -> (Vec<TokenStream2>, Vec<TokenS
             let timer = util::timer_ident(core);
             let signo = analysis.signals[&core].map[&tq.priority];
             stmts.push(quote!(
-                #timer.init(rtfm::export::timer_create(Some(tid), #signo));
+                #timer.init(rtfm::export::timer_create(
+                    <#monotonic as rtfm::Monotonic>::CLOCK,
+                    Some(tid),
+                    #signo,
+                ));
             ));
+
+            if analysis.wall_clock {
+                let timer = util::wall_timer_ident(core);
+
+                stmts.push(quote!(
+                    #timer.init(rtfm::export::timer_create(
+                        rtfm::export::CLOCK_REALTIME,
+                        Some(tid),
+                        #signo,
+                    ));
+                ));
+            }
         }
 
-        stmts.push(quote!(
-            // migrate the thread to a different core
-            rtfm::export::set_affinity(tid, #core);
+        // start schedule table
+        if analysis.tables.contains_key(&core) {
+            stmts.push(table_start(core, quote!(Some(tid)), analysis));
+        }
 
+        if pin {
+            stmts.push(quote!(
+                // migrate the thread to a different core
+                rtfm::export::set_affinity(tid, #core);
+            ));
+        }
+
+        stmts.push(quote!(
             // unblock the thread
             #tid.init(tid);
         ));
     }
 
+    // NOTE this must be done *after* spawning the other threads because `SCHED_DEADLINE` threads
+    // are not allowed to `clone`
+    if let Some(reservation) = &args.sched_deadline {
+        stmts.push(util::set_deadline(reservation));
+    }
+
     (const_app, stmts)
 }
+
+// Creates the timer that drives the schedule table of `core` and arms it for the first release
+fn table_start(core: Core, tid: TokenStream2, analysis: &Analysis) -> TokenStream2 {
+    let table = &analysis.tables[&core];
+    let monotonic = util::monotonic(analysis);
+    let signo = analysis.signals[&core].map[&table.priority];
+    let t = util::table_t_ident(core);
+    let table_ = util::table_ident(core);
+    let hyperperiod = table.hyperperiod;
+    let slots = table
+        .slots
+        .iter()
+        .map(|(offset, task)| quote!((#offset, #t::#task)));
+
+    quote!(
+        #table_.as_mut_ptr().write(rtfm::export::ScheduleTable::new(
+            epoch,
+            #hyperperiod,
+            &[#(#slots,)*],
+            rtfm::export::timer_create(<#monotonic as rtfm::Monotonic>::CLOCK, #tid, #signo),
+        ));
+    )
+}
@@ -10,6 +10,7 @@ use crate::{
 };
 
 pub fn codegen(app: &App, analysis: &Analysis) -> Vec<TokenStream2> {
+    let monotonic = util::monotonic(analysis);
     let mut items = vec![];
 
     let mut seen = BTreeSet::new();
@@ -27,45 +28,68 @@ pub fn codegen(app: &App, analysis: &Analysis) -> Vec<TokenStream2> {
 
             let cfgs = &schedulee.cfgs;
 
-            let schedule = util::schedule_ident(name);
-            if scheduler.is_init() {
-                let body = schedule_body::codegen(scheduler, name, app, analysis);
-
-                let args = args.clone();
-                methods.push(quote!(
-                    #(#cfgs)*
-                    fn #name(&self, instant: rtfm::Instant #(,#args)*) -> Result<(), #ty> {
-                        #body
-                    }
-                ));
+            // `foo(instant, ..)` and, if enabled, `foo_at_wall(time, ..)`
+            let walls: &[bool] = if analysis.wall_clock {
+                &[false, true]
             } else {
-                if !seen.contains(name) {
-                    seen.insert(name);
+                &[false]
+            };
+            for &wall in walls {
+                let (method, schedule, time, ty_) = if wall {
+                    (
+                        util::at_wall_ident(name),
+                        util::schedule_at_wall_ident(name),
+                        quote!(time),
+                        quote!(rtfm::SystemTime),
+                    )
+                } else {
+                    (
+                        name.clone(),
+                        util::schedule_ident(name),
+                        quote!(instant),
+                        quote!(#monotonic),
+                    )
+                };
+
+                if scheduler.is_init() {
+                    let body = schedule_body::codegen(scheduler, name, wall, app, analysis);
 
-                    let body = schedule_body::codegen(scheduler, name, app, analysis);
                     let args = args.clone();
-
-                    items.push(quote!(
+                    methods.push(quote!(
                         #(#cfgs)*
-                        fn #schedule(
-                            priority: &rtfm::export::Priority,
-                            instant: rtfm::Instant
-                                #(,#args)*
-                        ) -> Result<(), #ty> {
+                        fn #method(&self, #time: #ty_ #(,#args)*) -> Result<(), #ty> {
                             #body
                         }
                     ));
-                }
+                } else {
+                    if !seen.contains(&schedule) {
+                        seen.insert(schedule.clone());
+
+                        let body = schedule_body::codegen(scheduler, name, wall, app, analysis);
+                        let args = args.clone();
+
+                        items.push(quote!(
+                            #(#cfgs)*
+                            fn #schedule(
+                                priority: &rtfm::export::Priority,
+                                #time: #ty_
+                                    #(,#args)*
+                            ) -> Result<(), #ty> {
+                                #body
+                            }
+                        ));
+                    }
 
-                methods.push(quote!(
-                    #(#cfgs)*
-                    #[inline(always)]
-                    fn #name(&self, instant: rtfm::Instant #(,#args)*) -> Result<(), #ty> {
-                        let priority = unsafe { self.priority() };
+                    methods.push(quote!(
+                        #(#cfgs)*
+                        #[inline(always)]
+                        fn #method(&self, #time: #ty_ #(,#args)*) -> Result<(), #ty> {
+                            let priority = unsafe { self.priority() };
 
-                        #schedule(priority, instant #(,#untupled)*)
-                    }
-                ));
+                            #schedule(priority, #time #(,#untupled)*)
+                        }
+                    ));
+                }
             }
         }
 
@@ -5,19 +5,32 @@ use syn::Ident;
 
 use crate::{analyze::Analysis, codegen::util};
 
-pub fn codegen(ctxt: Context, name: &Ident, app: &App, analysis: &Analysis) -> TokenStream2 {
+/// `wall` selects the wall-clock timer queue; in that case the scheduled time is a
+/// `rtfm::SystemTime` called `time` rather than a `Monotonic` instant called `instant`
+pub fn codegen(
+    ctxt: Context,
+    name: &Ident,
+    wall: bool,
+    app: &App,
+    analysis: &Analysis,
+) -> TokenStream2 {
     let sender = ctxt.core(app);
+    let monotonic = util::monotonic(analysis);
     let schedulee = &app.software_tasks[name];
     let receiver = schedulee.args.core;
 
     let (_, tupled, _, _) = util::regroup_inputs(&schedulee.inputs);
 
     let fq = util::fq_ident_(name, sender);
-    let tq = util::tq_ident(sender);
+    let tq = if wall {
+        util::wall_tq_ident(sender)
+    } else {
+        util::tq_ident(sender)
+    };
     let inputs = util::inputs_ident(name);
 
     let signo = analysis.signals[&sender].map[&analysis.timer_queues[&sender].priority];
-    let tgid_tid = if app.args.cores == 1 {
+    let tgid_tid = if !util::directed_signals(app) {
         quote!(None)
     } else {
         let tid = util::tid_ident(sender);
@@ -46,12 +59,24 @@ pub fn codegen(ctxt: Context, name: &Ident, app: &App, analysis: &Analysis) -> T
     let instants_write = if app.uses_schedule(receiver) {
         let instants = util::instants_ident(name);
 
-        Some(quote!(#instants.get_unchecked_mut(usize::from(index)).as_mut_ptr().write(instant);))
+        // NOTE tasks always see the time they were scheduled at as a `Monotonic` instant
+        let instant = if wall {
+            quote!(rtfm::export::wall_to_instant::<#monotonic>(time))
+        } else {
+            quote!(instant)
+        };
+
+        Some(quote!(#instants.get_unchecked_mut(usize::from(index)).as_mut_ptr().write(#instant);))
     } else {
         None
     };
 
     let t = util::schedule_t_ident(sender);
+    let instant = if wall {
+        quote!(instant: time)
+    } else {
+        quote!(instant)
+    };
     quote!(
         unsafe {
             use rtfm::Mutex as _;
@@ -63,7 +88,7 @@ pub fn codegen(ctxt: Context, name: &Ident, app: &App, analysis: &Analysis) -> T
                 #inputs.get_unchecked_mut(usize::from(index)).as_mut_ptr().write(input);
 
                 let nr = rtfm::export::NotReady {
-                    instant,
+                    #instant,
                     index,
                     task: #t::#name,
                 };
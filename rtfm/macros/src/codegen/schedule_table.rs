@@ -0,0 +1,98 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use rtfm_syntax::ast::App;
+
+use crate::{analyze::Analysis, codegen::util};
+
+pub fn codegen(app: &App, analysis: &Analysis) -> Vec<TokenStream2> {
+    let monotonic = util::monotonic(analysis);
+    let mut items = vec![];
+
+    for (&core, table) in &analysis.tables {
+        let uses_schedule = app.uses_schedule(core);
+
+        let variants = table.tasks.values().flat_map(|tasks| tasks.iter());
+        let t = util::table_t_ident(core);
+        let doc = format!("Tasks released by the core #{} schedule table", core);
+        items.push(quote!(
+            #[doc = #doc]
+            #[allow(non_camel_case_types)]
+            #[derive(Clone, Copy)]
+            enum #t {
+                #(#variants,)*
+            }
+        ));
+
+        let table_ = util::table_ident(core);
+        let doc = format!("Core #{} schedule table", core);
+        items.push(quote!(
+            #[doc = #doc]
+            static mut #table_: core::mem::MaybeUninit<rtfm::export::ScheduleTable<#t, #monotonic>> =
+                core::mem::MaybeUninit::uninit();
+        ));
+
+        let tid = if !util::directed_signals(app) {
+            quote!(None)
+        } else {
+            let tid = util::tid_ident(core);
+            quote!(Some(#tid.get()))
+        };
+
+        let mut arms = vec![];
+        for (&priority, tasks) in &table.tasks {
+            let signo = analysis.signals[&core].map[&priority];
+            let pt = util::spawn_t_ident(core, priority);
+
+            for name in tasks {
+                let write_release = if uses_schedule {
+                    let release = util::release_ident(name);
+
+                    items.push(quote!(
+                        /// Release time of the last instance of this time-triggered task
+                        static mut #release: core::mem::MaybeUninit<#monotonic> =
+                            core::mem::MaybeUninit::uninit();
+                    ));
+
+                    Some(quote!(#release.as_mut_ptr().write(release);))
+                } else {
+                    None
+                };
+
+                let variant = util::table_task_ident(name);
+                arms.push(quote!(
+                    #t::#name => {
+                        #write_release
+
+                        rtfm::export::enqueue(TGID.get(), #tid, #signo, #pt::#variant as u8, 0);
+                    }
+                ));
+            }
+        }
+
+        let release = if uses_schedule {
+            quote!(release)
+        } else {
+            quote!(_)
+        };
+        let handler = util::rt_ident(analysis.signals[&core].map[&table.priority]);
+        items.push(quote!(
+            /// Schedule table handler
+            #[allow(non_snake_case)]
+            extern "C" fn #handler(
+                _: i32,
+                _: &mut rtfm::export::siginfo_t,
+                _: *mut rtfm::export::c_void,
+            ) {
+                unsafe {
+                    let (task, #release) = (*#table_.as_mut_ptr()).next();
+
+                    match task {
+                        #(#arms)*
+                    }
+                }
+            }
+        ));
+    }
+
+    items
+}
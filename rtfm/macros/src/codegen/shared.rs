@@ -0,0 +1,131 @@
+use std::collections::BTreeSet;
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+
+use crate::{analyze::Analysis, codegen::util, items::Items};
+
+pub fn codegen(
+    analysis: &Analysis,
+    items: &Items,
+) -> (
+    // const_app
+    Vec<TokenStream2>,
+    // mod_shared
+    TokenStream2,
+) {
+    let mut const_app = vec![];
+    let mut mod_shared = vec![];
+
+    let cores = analysis
+        .shared
+        .iter()
+        .flat_map(|ceilings| ceilings.keys().cloned())
+        .collect::<BTreeSet<_>>();
+    for core in cores {
+        let held = util::held_ident(core);
+        let doc = format!(
+            "Lock order of the innermost `#[shared]` resource held by core #{}",
+            core
+        );
+        const_app.push(quote!(
+            #[doc = #doc]
+            static #held: rtfm::export::Held = rtfm::export::Held::new();
+        ));
+    }
+
+    for (order, (res, ceilings)) in items.shared.iter().zip(&analysis.shared).enumerate() {
+        let attrs = &res.attrs;
+        let cfgs = &res.cfgs;
+        let expr = &res.expr;
+        let name = &res.name;
+        let ty = &res.ty;
+
+        const_app.push(quote!(
+            #(#attrs)*
+            #(#cfgs)*
+            static mut #name: #ty = #expr;
+        ));
+
+        let spinlock = util::spinlock_ident(name);
+        // NOTE the spinlock also records the position of the resource in the lock order
+        let order = order as u8;
+        const_app.push(quote!(
+            #(#cfgs)*
+            /// Serializes accesses to the previous resource across cores
+            static #spinlock: rtfm::export::Spinlock = rtfm::export::Spinlock::new(#order);
+        ));
+
+        mod_shared.push(quote!(
+            pub struct #name<'a> {
+                priority: &'a Priority,
+                core: u8,
+            }
+
+            impl<'a> #name<'a> {
+                #[inline(always)]
+                pub unsafe fn new(priority: &'a Priority, core: u8) -> Self {
+                    #name { priority, core }
+                }
+
+                #[inline(always)]
+                pub unsafe fn priority(&self) -> &Priority {
+                    self.priority
+                }
+
+                #[inline(always)]
+                pub fn core(&self) -> u8 {
+                    self.core
+                }
+            }
+        ));
+
+        // NOTE the local ceiling and the signal range depend on the core the proxy was created on
+        let arms = ceilings.iter().map(|(&core, &ceiling)| {
+            let held = util::held_ident(core);
+            let range = analysis.signals[&core].range();
+            let (start, end) = (range.start, range.end);
+
+            quote!(
+                #core => rtfm::export::lock_shared(
+                    &mut #name,
+                    self.priority(),
+                    #ceiling,
+                    #start..#end,
+                    &#spinlock,
+                    &#held,
+                    f,
+                ),
+            )
+        });
+
+        const_app.push(quote!(
+            #(#cfgs)*
+            impl<'a> rtfm::Mutex for shared::#name<'a> {
+                type T = #ty;
+
+                #[inline(always)]
+                fn lock<R>(&mut self, f: impl FnOnce(&mut #ty) -> R) -> R {
+                    unsafe {
+                        match self.core() {
+                            #(#arms)*
+                            _ => core::hint::unreachable_unchecked(),
+                        }
+                    }
+                }
+            }
+        ));
+    }
+
+    let mod_shared = if mod_shared.is_empty() {
+        quote!()
+    } else {
+        quote!(mod shared {
+            use rtfm::export::Priority;
+
+            #(#mod_shared)*
+        })
+    };
+
+    (const_app, mod_shared)
+}
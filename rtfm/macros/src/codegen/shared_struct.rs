@@ -0,0 +1,60 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use rtfm_syntax::{ast::App, Context};
+
+use crate::{codegen::util, items::Items};
+
+pub fn codegen(context: Context, app: &App, items: &Items) -> (TokenStream2, TokenStream2) {
+    let name = context.ident(app);
+    let core = context.core(app);
+
+    let mut fields = vec![];
+    let mut values = vec![];
+    let mut has_cfgs = false;
+    for (_, res) in items.shared_by(&name) {
+        let cfgs = &res.cfgs;
+        has_cfgs |= !cfgs.is_empty();
+
+        let name = &res.name;
+        fields.push(quote!(
+            #(#cfgs)*
+            pub #name: shared::#name<'a>
+        ));
+
+        values.push(quote!(
+            #(#cfgs)*
+            #name: shared::#name::new(priority, #core)
+        ));
+    }
+
+    // the struct could end up empty due to `cfg` leading to an error due to `'a` being unused
+    if has_cfgs {
+        fields.push(quote!(
+            #[doc(hidden)]
+            pub __marker__: core::marker::PhantomData<&'a ()>
+        ));
+
+        values.push(quote!(__marker__: core::marker::PhantomData))
+    }
+
+    let doc = format!("`#[shared]` resources `{}` has access to", name);
+    let ident = util::shared_ident(context, app);
+    let item = quote!(
+        #[allow(non_snake_case)]
+        #[doc = #doc]
+        pub struct #ident<'a> {
+            #(#fields,)*
+        }
+    );
+    let constructor = quote!(
+        impl<'a> #ident<'a> {
+            #[inline(always)]
+            unsafe fn new(priority: &'a rtfm::export::Priority) -> Self {
+                #ident {
+                    #(#values,)*
+                }
+            }
+        }
+    );
+    (item, constructor)
+}
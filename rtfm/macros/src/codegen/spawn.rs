@@ -10,6 +10,7 @@ use crate::{
 };
 
 pub fn codegen(app: &App, analysis: &Analysis) -> Vec<TokenStream2> {
+    let monotonic = util::monotonic(analysis);
     let mut items = vec![];
 
     let mut seen = BTreeSet::new();
@@ -31,7 +32,7 @@ pub fn codegen(app: &App, analysis: &Analysis) -> Vec<TokenStream2> {
                 let body = spawn_body::codegen(spawner, &name, app, analysis);
 
                 let let_instant = if app.uses_schedule(sender) {
-                    Some(quote!(let instant = rtfm::Instant::now();))
+                    Some(quote!(let instant = <#monotonic as rtfm::Monotonic>::now();))
                 } else {
                     None
                 };
@@ -50,7 +51,7 @@ pub fn codegen(app: &App, analysis: &Analysis) -> Vec<TokenStream2> {
                     seen.insert(name);
 
                     let instant = if app.uses_schedule(receiver) {
-                        Some(quote!(, instant: rtfm::Instant))
+                        Some(quote!(, instant: #monotonic))
                     } else {
                         None
                     };
@@ -71,7 +72,7 @@ pub fn codegen(app: &App, analysis: &Analysis) -> Vec<TokenStream2> {
                 let (let_instant, instant) = if app.uses_schedule(receiver) {
                     (
                         Some(if spawner.is_idle() {
-                            quote!(let instant = rtfm::Instant::now();)
+                            quote!(let instant = <#monotonic as rtfm::Monotonic>::now();)
                         } else {
                             quote!(let instant = self.instant();)
                         }),
@@ -36,7 +36,7 @@ pub fn codegen<'a>(
 
     let variant = util::task_ident(name, sender);
     let signo = analysis.signals[&receiver].map[&priority];
-    let enqueue = if app.args.cores == 1 {
+    let enqueue = if !util::directed_signals(app) {
         quote!(
             rtfm::export::enqueue(
                 TGID.get(),
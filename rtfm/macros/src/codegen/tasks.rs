@@ -4,12 +4,14 @@ use rtfm_syntax::{ast::App, Context};
 
 use crate::{
     analyze::Analysis,
-    codegen::{locals, module, resources_struct, util},
+    codegen::{channels_struct, locals, module, resources_struct, shared_struct, util},
+    items::Items,
 };
 
 pub fn codegen(
     app: &App,
     analysis: &Analysis,
+    items: &Items,
 ) -> (
     // const_app
     Vec<TokenStream2>,
@@ -22,6 +24,7 @@ pub fn codegen(
     // user_tasks
     Vec<TokenStream2>,
 ) {
+    let monotonic = util::monotonic(analysis);
     let mut const_app = vec![];
     let mut mods = vec![];
     let mut locals_structs = vec![];
@@ -48,7 +51,7 @@ pub fn codegen(
                 let elems = elems.clone();
                 const_app.push(quote!(
                     /// Buffer that holds the instants associated to the inputs of a task
-                    static mut #task_instants: [core::mem::MaybeUninit<rtfm::Instant>; #cap_lit] =
+                    static mut #task_instants: [core::mem::MaybeUninit<#monotonic>; #cap_lit] =
                         [#(#elems,)*];
                 ));
             }
@@ -110,13 +113,35 @@ pub fn codegen(
 
             const_app.push(constructor);
         }
+
+        let has_shared = items.shared_by(name).next().is_some();
+        if has_shared {
+            let (item, constructor) =
+                shared_struct::codegen(Context::SoftwareTask(name), app, items);
+
+            resources_structs.push(item);
+            const_app.push(constructor);
+        }
+
+        let has_channels = items.channels_of(name).next().is_some();
+        if has_channels {
+            let (item, constructor) =
+                channels_struct::codegen(Context::SoftwareTask(name), app, analysis, items);
+
+            resources_structs.push(item);
+            const_app.push(constructor);
+        }
+
         mods.push(module::codegen(
             Context::SoftwareTask(name),
             (!task.args.resources.is_empty(), needs_lt),
+            has_shared,
+            has_channels,
             !task.args.schedule.is_empty(),
             !task.args.spawn.is_empty(),
             false,
             app,
+            analysis,
         ));
 
         let attrs = &task.attrs;
@@ -19,7 +19,7 @@ pub fn codegen(sender: Core, timer_queue: &TimerQueue, app: &App, analysis: &Ana
             let pt = util::spawn_t_ident(receiver, task.args.priority);
             let pname = util::task_ident(name, sender);
 
-            let tid = if app.args.cores == 1 {
+            let tid = if !util::directed_signals(app) {
                 quote!(None)
             } else {
                 let tid = util::tid_ident(receiver);
@@ -35,6 +35,25 @@ pub fn codegen(sender: Core, timer_queue: &TimerQueue, app: &App, analysis: &Ana
         })
         .collect::<Vec<_>>();
 
+    let wall = if analysis.wall_clock {
+        let timer = util::wall_timer_ident(sender);
+        let tq = util::wall_tq_ident(sender);
+
+        Some(quote!(
+            let timer = #timer.get();
+
+            while let Some((task, index)) = (#tq {
+                priority: &rtfm::export::Priority::new(PRIORITY),
+            }).lock(|tq| tq.dequeue(timer)) {
+                match task {
+                    #(#arms)*
+                }
+            }
+        ))
+    } else {
+        None
+    };
+
     quote!(
         let tgid = TGID.get();
         let timer = #timer.get();
@@ -46,5 +65,7 @@ pub fn codegen(sender: Core, timer_queue: &TimerQueue, app: &App, analysis: &Ana
                 #(#arms)*
             }
         }
+
+        #wall
     )
 }
@@ -5,6 +5,7 @@ use rtfm_syntax::ast::App;
 use crate::{analyze::Analysis, codegen::util};
 
 pub fn codegen(app: &App, analysis: &Analysis) -> Vec<TokenStream2> {
+    let monotonic = util::monotonic(analysis);
     let mut items = vec![];
 
     for (&sender, timer_queue) in &analysis.timer_queues {
@@ -33,7 +34,7 @@ pub fn codegen(app: &App, analysis: &Analysis) -> Vec<TokenStream2> {
         ));
 
         let cap = util::typenum_capacity(timer_queue.capacity, false);
-        let ty = quote!(rtfm::export::TimerQueue<#t, #cap>);
+        let ty = quote!(rtfm::export::TimerQueue<#t, #cap, #monotonic>);
         let doc = format!("Core #{} timer queue", sender);
         let tq = util::tq_ident(sender);
         items.push(quote!(
@@ -63,9 +64,45 @@ pub fn codegen(app: &App, analysis: &Analysis) -> Vec<TokenStream2> {
             &tq,
             ty,
             timer_queue.ceiling,
-            range,
+            range.clone(),
             quote!(&mut #tq),
         ));
+
+        // the wall-clock timer queue shares the signal handler of the monotonic one
+        if analysis.wall_clock {
+            let ty = quote!(rtfm::export::TimerQueue<#t, #cap, rtfm::SystemTime>);
+            let doc = format!("Core #{} wall-clock timer queue", sender);
+            let tq = util::wall_tq_ident(sender);
+            items.push(quote!(
+                #[doc = #doc]
+                static mut #tq: #ty = rtfm::export::TimerQueue(
+                    rtfm::export::BinaryHeap(rtfm::export::iBinaryHeap::new())
+                );
+            ));
+
+            let timer = util::wall_timer_ident(sender);
+            let doc = format!("{} timer", tq.to_string());
+            items.push(quote!(
+                #[doc = #doc]
+                static #timer: rtfm::export::Timer = rtfm::export::Timer::uninit();
+            ));
+
+            items.push(quote!(
+                struct #tq<'a> {
+                    priority: &'a rtfm::export::Priority,
+                }
+            ));
+
+            items.push(util::impl_mutex(
+                &[],
+                false,
+                &tq,
+                ty,
+                timer_queue.ceiling,
+                range,
+                quote!(&mut #tq),
+            ));
+        }
     }
 
     items
@@ -5,6 +5,8 @@ use quote::quote;
 use rtfm_syntax::{ast::App, Context};
 use syn::{ArgCaptured, Attribute, Ident, IntSuffix, LitInt};
 
+use crate::{analyze::Analysis, args::Reservation};
+
 pub fn impl_mutex(
     cfgs: &[Attribute],
     resources_prefix: bool,
@@ -44,6 +46,31 @@ pub fn impl_mutex(
     )
 }
 
+/// Switches the calling thread to the `SCHED_DEADLINE` policy
+pub fn set_deadline(reservation: &Reservation) -> TokenStream2 {
+    let runtime = reservation.runtime;
+    let deadline = reservation.deadline;
+    let period = reservation.period;
+
+    quote!(rtfm::export::set_deadline(#runtime, #deadline, #period);)
+}
+
+/// The instant type of the clock that drives the `schedule` API
+pub fn monotonic(analysis: &Analysis) -> TokenStream2 {
+    match &analysis.monotonic {
+        Some(path) => quote!(#path),
+        None => quote!(rtfm::Instant),
+    }
+}
+
+/// Whether signals and timers must target a specific thread (`TID*`) rather than the process
+///
+/// This is the case in multi-core applications and with the `std` feature, where other threads
+/// (e.g. spawned by `std` code) don't block the signals used by the runtime
+pub fn directed_signals(app: &App) -> bool {
+    app.args.cores > 1 || cfg!(feature = "std")
+}
+
 // Regroups a task inputs
 //
 // e.g. &[`input: Foo`], &[`mut x: i32`, `ref y: i64`]
@@ -205,3 +232,107 @@ pub fn task_ident(task: &Ident, sender: u8) -> Ident {
 pub fn tq_ident(sender: u8) -> Ident {
     Ident::new(&format!("TQ{}", sender), Span::call_site())
 }
+
+/// e.g. `0` -> `TQW0`
+pub fn wall_tq_ident(sender: u8) -> Ident {
+    Ident::new(&format!("TQW{}", sender), Span::call_site())
+}
+
+/// e.g. `0` -> `TIMERW0`
+pub fn wall_timer_ident(sender: u8) -> Ident {
+    Ident::new(&format!("TIMERW{}", sender), Span::call_site())
+}
+
+/// e.g. `foo` -> `foo_at_wall`
+pub fn at_wall_ident(task: &Ident) -> Ident {
+    Ident::new(&format!("{}_at_wall", task), Span::call_site())
+}
+
+/// e.g. `foo` -> `schedule_foo_at_wall`
+pub fn schedule_at_wall_ident(task: &Ident) -> Ident {
+    Ident::new(&format!("schedule_{}_at_wall", task), Span::call_site())
+}
+
+/// e.g. `0` -> `TABLE0`
+pub fn table_ident(core: u8) -> Ident {
+    Ident::new(&format!("TABLE{}", core), Span::call_site())
+}
+
+/// e.g. `0` -> `TT0`
+pub fn table_t_ident(core: u8) -> Ident {
+    Ident::new(&format!("TT{}", core), Span::call_site())
+}
+
+/// e.g. `foo` -> `foo_TT`
+pub fn table_task_ident(task: &Ident) -> Ident {
+    Ident::new(&format!("{}_TT", task), Span::call_site())
+}
+
+/// e.g. `foo` -> `foo_RELEASE`
+pub fn release_ident(task: &Ident) -> Ident {
+    Ident::new(&format!("{}_RELEASE", task), Span::call_site())
+}
+
+/// e.g. `0` -> `HELD0`
+pub fn held_ident(core: u8) -> Ident {
+    Ident::new(&format!("HELD{}", core), Span::call_site())
+}
+
+pub fn shared_ident(ctxt: Context, app: &App) -> Ident {
+    let mut s = match ctxt {
+        Context::Init(core) => app.inits[&core].name.to_string(),
+        Context::Idle(core) => app.idles[&core].name.to_string(),
+        Context::HardwareTask(ident) | Context::SoftwareTask(ident) => ident.to_string(),
+    };
+
+    s.push_str("Shared");
+
+    Ident::new(&s, Span::call_site())
+}
+
+/// e.g. `FOO` -> `FOO_SPINLOCK`
+pub fn spinlock_ident(resource: &Ident) -> Ident {
+    Ident::new(&format!("{}_SPINLOCK", resource), Span::call_site())
+}
+
+pub fn channels_ident(ctxt: Context, app: &App) -> Ident {
+    let mut s = match ctxt {
+        Context::Init(core) => app.inits[&core].name.to_string(),
+        Context::Idle(core) => app.idles[&core].name.to_string(),
+        Context::HardwareTask(ident) | Context::SoftwareTask(ident) => ident.to_string(),
+    };
+
+    s.push_str("Channels");
+
+    Ident::new(&s, Span::call_site())
+}
+
+/// e.g. `FOO` -> `FOO_C`
+pub fn consumer_ident(channel: &Ident) -> Ident {
+    Ident::new(&format!("{}_C", channel), Span::call_site())
+}
+
+/// e.g. `FOO` -> `FOO_NOTIFY`
+pub fn notify_ident(channel: &Ident) -> Ident {
+    Ident::new(&format!("{}_NOTIFY", channel), Span::call_site())
+}
+
+/// e.g. `FOO` -> `FOO_PENDING`
+pub fn pending_ident(channel: &Ident) -> Ident {
+    Ident::new(&format!("{}_PENDING", channel), Span::call_site())
+}
+
+/// e.g. `FOO` -> `FOO_P`
+pub fn producer_ident(channel: &Ident) -> Ident {
+    Ident::new(&format!("{}_P", channel), Span::call_site())
+}
+
+/// e.g. `FOO` -> `FOO_NODES`
+pub fn nodes_ident(pool: &Ident) -> Ident {
+    Ident::new(&format!("{}_NODES", pool), Span::call_site())
+}
+
+/// e.g. `FOO` -> `FOO_POOL`
+pub fn pool_ident(pool: &Ident) -> Ident {
+    Ident::new(&format!("{}_POOL", pool), Span::call_site())
+}
@@ -0,0 +1,476 @@
+use proc_macro2::{Delimiter, Group, Spacing, TokenStream as TokenStream2, TokenTree};
+use syn::{
+    parse::{self, Parse, ParseStream},
+    punctuated::Punctuated,
+    spanned::Spanned,
+    Attribute, Expr, Ident, ItemStatic, Lit, Token, Type,
+};
+
+/// `#[app]` items that are handled by this crate rather than by `rtfm-syntax`
+pub struct Items {
+    /// `#[channel]`s in declaration order
+    pub channels: Vec<Channel>,
+
+    /// `#[pool]`s in declaration order
+    pub pools: Vec<Pool>,
+
+    /// `#[shared]` resources in declaration order
+    pub shared: Vec<Shared>,
+}
+
+/// A single-producer single-consumer queue that connects two tasks, possibly running on different
+/// cores
+pub struct Channel {
+    pub attrs: Vec<Attribute>,
+    pub capacity: u8,
+    pub consumer: Ident,
+    pub name: Ident,
+    /// Whether pushing an item activates the consumer task
+    pub notify: bool,
+    pub producer: Ident,
+    pub ty: Box<Type>,
+}
+
+/// A fixed-size memory pool
+pub struct Pool {
+    pub attrs: Vec<Attribute>,
+    /// Initial value of every block
+    pub expr: Box<Expr>,
+    pub name: Ident,
+    /// Number of blocks
+    pub size: u8,
+    /// Type of the blocks
+    pub ty: Box<Type>,
+}
+
+/// A resource that can be accessed from tasks that run on different cores
+pub struct Shared {
+    pub attrs: Vec<Attribute>,
+    pub cfgs: Vec<Attribute>,
+    pub expr: Box<Expr>,
+    pub name: Ident,
+    /// Contexts (software tasks and `#[idle]` functions) that can access this resource
+    pub tasks: Vec<Ident>,
+    pub ty: Box<syn::Type>,
+}
+
+/// Removes the items we handle from the `#[app]` input; the rest must be forwarded to `rtfm-syntax`
+pub fn parse(input: TokenStream2) -> parse::Result<(Items, TokenStream2)> {
+    let mut items = Items {
+        channels: vec![],
+        pools: vec![],
+        shared: vec![],
+    };
+
+    // NOTE we work at the token level because `rtfm-syntax` accepts items that are not valid Rust
+    // syntax (e.g. late resources)
+    let mut output = vec![];
+    for tt in input {
+        match tt {
+            TokenTree::Group(ref group) if group.delimiter() == Delimiter::Brace => {
+                let stream = extract(group.stream(), &mut items)?;
+                let mut new = Group::new(Delimiter::Brace, stream);
+                new.set_span(group.span());
+                output.push(TokenTree::Group(new));
+            }
+
+            tt => output.push(tt),
+        }
+    }
+
+    Ok((items, output.into_iter().collect()))
+}
+
+fn extract(stream: TokenStream2, items: &mut Items) -> parse::Result<TokenStream2> {
+    let mut output = vec![];
+    // outer attributes of the next item
+    let mut attrs = vec![];
+    let mut tokens = stream.into_iter().peekable();
+
+    while let Some(tt) = tokens.next() {
+        match tt {
+            TokenTree::Punct(ref punct) if punct.as_char() == '#' => {
+                attrs.push(tt.clone());
+
+                if let Some(TokenTree::Group(_)) = tokens.peek() {
+                    attrs.push(tokens.next().expect("UNREACHABLE"));
+                }
+            }
+
+            tt => {
+                let is_channel = attrs.chunks(2).any(|attr| is_attr(attr, "channel"));
+                let is_pool = attrs.chunks(2).any(|attr| is_attr(attr, "pool"));
+                let is_shared = attrs.chunks(2).any(|attr| is_attr(attr, "shared"));
+
+                if is_channel || is_pool || is_shared {
+                    // collect the rest of the item, up to the closing semicolon
+                    let mut item = attrs.drain(..).collect::<Vec<_>>();
+                    item.push(tt);
+                    let mut tt = tokens.next();
+                    while let Some(t) = tt {
+                        let end = match t {
+                            TokenTree::Punct(ref punct) => {
+                                punct.as_char() == ';' && punct.spacing() == Spacing::Alone
+                            }
+                            _ => false,
+                        };
+
+                        item.push(t);
+
+                        if end {
+                            break;
+                        }
+
+                        tt = tokens.next();
+                    }
+
+                    let item = item.into_iter().collect();
+                    if is_channel {
+                        items.channels.push(syn::parse2::<Channel>(item)?);
+                    } else if is_pool {
+                        items.pools.push(pool(syn::parse2::<ItemStatic>(item)?)?);
+                    } else {
+                        items.shared.push(shared(syn::parse2::<ItemStatic>(item)?)?);
+                    }
+                } else {
+                    output.extend(attrs.drain(..));
+                    output.push(tt);
+                }
+            }
+        }
+    }
+
+    output.extend(attrs);
+
+    Ok(output.into_iter().collect())
+}
+
+// `attr` = [`#`, `[..]`]
+fn is_attr(attr: &[TokenTree], name: &str) -> bool {
+    if let Some(TokenTree::Group(group)) = attr.get(1) {
+        if group.delimiter() == Delimiter::Bracket {
+            if let Some(TokenTree::Ident(ident)) = group.stream().into_iter().next() {
+                return ident == name;
+            }
+        }
+    }
+
+    false
+}
+
+fn pool(item: ItemStatic) -> parse::Result<Pool> {
+    if let Some(mut_) = item.mutability {
+        return Err(parse::Error::new(
+            mut_.span,
+            "`#[pool]`s must be declared as `static` variables",
+        ));
+    }
+
+    let mut attrs = vec![];
+    let mut size = None;
+    for attr in item.attrs {
+        if attr.path.is_ident("pool") {
+            if size.is_some() {
+                return Err(parse::Error::new(
+                    attr.span(),
+                    "this attribute can only be used once",
+                ));
+            }
+
+            size = Some(syn::parse2::<PoolArgs>(attr.tts)?.size);
+        } else if attr.path.is_ident("shared") || attr.path.is_ident("channel") {
+            return Err(parse::Error::new(
+                attr.span(),
+                "this attribute can't be used on a `#[pool]`",
+            ));
+        } else {
+            attrs.push(attr);
+        }
+    }
+
+    Ok(Pool {
+        attrs,
+        expr: item.expr,
+        name: item.ident,
+        size: size.expect("UNREACHABLE"),
+        ty: item.ty,
+    })
+}
+
+// e.g. `(size = 32)`
+struct PoolArgs {
+    size: u8,
+}
+
+impl Parse for PoolArgs {
+    fn parse(input: ParseStream<'_>) -> parse::Result<Self> {
+        let input = {
+            let content;
+            syn::parenthesized!(content in input);
+            content
+        };
+
+        let key: Ident = input.parse()?;
+        if key != "size" {
+            return Err(parse::Error::new(key.span(), "expected `size`"));
+        }
+        let _: Token![=] = input.parse()?;
+
+        let lit: Lit = input.parse()?;
+        match lit {
+            Lit::Int(ref int) if int.value() > 0 && int.value() <= 255 => Ok(PoolArgs {
+                size: int.value() as u8,
+            }),
+
+            _ => Err(parse::Error::new(
+                lit.span(),
+                "expected an integer literal in the range 1..=255",
+            )),
+        }
+    }
+}
+
+fn shared(item: ItemStatic) -> parse::Result<Shared> {
+    if item.mutability.is_none() {
+        return Err(parse::Error::new(
+            item.ident.span(),
+            "`#[shared]` resources must be `static mut` variables",
+        ));
+    }
+
+    let mut attrs = vec![];
+    let mut cfgs = vec![];
+    let mut tasks = None;
+    for attr in item.attrs {
+        if attr.path.is_ident("shared") {
+            if tasks.is_some() {
+                return Err(parse::Error::new(
+                    attr.span(),
+                    "this attribute can only be used once",
+                ));
+            }
+
+            tasks = Some(syn::parse2::<SharedArgs>(attr.tts)?.tasks);
+        } else if attr.path.is_ident("cfg") {
+            cfgs.push(attr);
+        } else {
+            attrs.push(attr);
+        }
+    }
+
+    Ok(Shared {
+        attrs,
+        cfgs,
+        expr: item.expr,
+        name: item.ident,
+        tasks: tasks.expect("UNREACHABLE"),
+        ty: item.ty,
+    })
+}
+
+// e.g. `#[channel(capacity = 4, producer = foo, consumer = bar)] static CHANNEL: u32;`
+impl Parse for Channel {
+    fn parse(input: ParseStream<'_>) -> parse::Result<Self> {
+        let mut attrs = vec![];
+        let mut args = None;
+        for attr in input.call(Attribute::parse_outer)? {
+            if attr.path.is_ident("channel") {
+                if args.is_some() {
+                    return Err(parse::Error::new(
+                        attr.span(),
+                        "this attribute can only be used once",
+                    ));
+                }
+
+                args = Some(syn::parse2::<ChannelArgs>(attr.tts)?);
+            } else if attr.path.is_ident("shared") || attr.path.is_ident("pool") {
+                return Err(parse::Error::new(
+                    attr.span(),
+                    "this attribute can't be used on a `#[channel]`",
+                ));
+            } else {
+                attrs.push(attr);
+            }
+        }
+
+        let _: Token![static] = input.parse()?;
+        if input.peek(Token![mut]) {
+            let mut_: Token![mut] = input.parse()?;
+            return Err(parse::Error::new(
+                mut_.span,
+                "`#[channel]`s must be declared as `static` variables",
+            ));
+        }
+        let name = input.parse()?;
+        let _: Token![:] = input.parse()?;
+        let ty = input.parse()?;
+        let _: Token![;] = input.parse()?;
+
+        let args = args.expect("UNREACHABLE");
+        Ok(Channel {
+            attrs,
+            capacity: args.capacity,
+            consumer: args.consumer,
+            name,
+            notify: args.notify,
+            producer: args.producer,
+            ty: Box::new(ty),
+        })
+    }
+}
+
+// e.g. `(capacity = 4, producer = foo, consumer = bar, notify = true)`
+struct ChannelArgs {
+    capacity: u8,
+    consumer: Ident,
+    notify: bool,
+    producer: Ident,
+}
+
+impl Parse for ChannelArgs {
+    fn parse(input: ParseStream<'_>) -> parse::Result<Self> {
+        let input = {
+            let content;
+            syn::parenthesized!(content in input);
+            content
+        };
+        let span = input.cursor().span();
+
+        let mut capacity = None;
+        let mut consumer = None;
+        let mut notify = None;
+        let mut producer = None;
+        loop {
+            if input.is_empty() {
+                break;
+            }
+
+            let key: Ident = input.parse()?;
+            let _: Token![=] = input.parse()?;
+
+            let ks = key.to_string();
+            let seen = match &*ks {
+                "capacity" => {
+                    let lit: Lit = input.parse()?;
+                    let seen = capacity.is_some();
+                    capacity = Some(match lit {
+                        // NOTE the queue uses `u8` indices and its capacity must be a power of two
+                        Lit::Int(ref int) if int.value() > 0 && int.value() <= 128 => {
+                            int.value() as u8
+                        }
+
+                        _ => {
+                            return Err(parse::Error::new(
+                                lit.span(),
+                                "expected an integer literal in the range 1..=128",
+                            ));
+                        }
+                    });
+                    seen
+                }
+
+                "consumer" => consumer.replace(input.parse()?).is_some(),
+
+                "notify" => {
+                    let lit: Lit = input.parse()?;
+                    let seen = notify.is_some();
+                    notify = Some(match lit {
+                        Lit::Bool(ref b) => b.value,
+
+                        _ => {
+                            return Err(parse::Error::new(
+                                lit.span(),
+                                "expected `true` or `false`",
+                            ));
+                        }
+                    });
+                    seen
+                }
+
+                "producer" => producer.replace(input.parse()?).is_some(),
+
+                _ => {
+                    return Err(parse::Error::new(
+                        key.span(),
+                        "expected one of: `capacity`, `consumer`, `notify` or `producer`",
+                    ));
+                }
+            };
+
+            if seen {
+                return Err(parse::Error::new(
+                    key.span(),
+                    "argument passed more than once",
+                ));
+            }
+
+            if input.is_empty() {
+                break;
+            }
+
+            let _: Token![,] = input.parse()?;
+        }
+
+        let missing = |what| parse::Error::new(span, format!("missing `{}` argument", what));
+        Ok(ChannelArgs {
+            capacity: capacity.ok_or_else(|| missing("capacity"))?,
+            consumer: consumer.ok_or_else(|| missing("consumer"))?,
+            notify: notify.unwrap_or(false),
+            producer: producer.ok_or_else(|| missing("producer"))?,
+        })
+    }
+}
+
+// e.g. `(tasks = [foo, bar])`
+struct SharedArgs {
+    tasks: Vec<Ident>,
+}
+
+impl Parse for SharedArgs {
+    fn parse(input: ParseStream<'_>) -> parse::Result<Self> {
+        let input = {
+            let content;
+            syn::parenthesized!(content in input);
+            content
+        };
+
+        let key: Ident = input.parse()?;
+        if key != "tasks" {
+            return Err(parse::Error::new(key.span(), "expected `tasks`"));
+        }
+        let _: Token![=] = input.parse()?;
+
+        let content;
+        syn::bracketed!(content in input);
+        let tasks = Punctuated::<Ident, Token![,]>::parse_terminated(&content)?;
+
+        if tasks.is_empty() {
+            return Err(parse::Error::new(
+                key.span(),
+                "a `#[shared]` resource must be accessed by at least one task",
+            ));
+        }
+
+        Ok(SharedArgs {
+            tasks: tasks.into_iter().collect(),
+        })
+    }
+}
+
+impl Items {
+    /// `#[channel]`s whose producer or consumer is the context `name`
+    pub fn channels_of<'a>(&'a self, name: &'a Ident) -> impl Iterator<Item = &'a Channel> {
+        self.channels
+            .iter()
+            .filter(move |ch| ch.producer == *name || ch.consumer == *name)
+    }
+
+    /// `#[shared]` resources, and their lock order, that the context `name` has access to
+    pub fn shared_by<'a>(&'a self, name: &'a Ident) -> impl Iterator<Item = (u8, &'a Shared)> {
+        self.shared
+            .iter()
+            .enumerate()
+            .filter(move |(_, res)| res.tasks.contains(name))
+            .map(|(i, res)| (i as u8, res))
+    }
+}
@@ -9,8 +9,10 @@ use std::{fs, path::Path};
 use rtfm_syntax::Settings;
 
 mod analyze;
+mod args;
 mod check;
 mod codegen;
+mod items;
 
 #[proc_macro_attribute]
 pub fn app(args: TokenStream, input: TokenStream) -> TokenStream {
@@ -18,19 +20,29 @@ pub fn app(args: TokenStream, input: TokenStream) -> TokenStream {
     settings.parse_cores = true;
     settings.parse_schedule = true;
 
-    let (app, analysis) = match rtfm_syntax::parse(args, input, settings) {
+    let (args, rest) = match args::parse(args.into()) {
         Err(e) => return e.to_compile_error().into(),
         Ok(x) => x,
     };
 
-    if let Err(e) = check::app(&app, &analysis) {
+    let (items, input) = match items::parse(input.into()) {
+        Err(e) => return e.to_compile_error().into(),
+        Ok(x) => x,
+    };
+
+    let (app, analysis) = match rtfm_syntax::parse(rest.into(), input.into(), settings) {
+        Err(e) => return e.to_compile_error().into(),
+        Ok(x) => x,
+    };
+
+    if let Err(e) = check::app(&app, &analysis, &args, &items) {
         return e.to_compile_error().into();
     }
 
-    let analysis = analyze::app(analysis, &app);
+    let analysis = analyze::app(analysis, &app, &args, &items);
 
     // Code generation
-    let ts = codegen::app(&app, &analysis);
+    let ts = codegen::app(&app, &analysis, &args, &items);
 
     // Try to write the expanded code to disk
     if Path::new("target").exists() {
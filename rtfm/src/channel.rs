@@ -0,0 +1,91 @@
+//! Inter-task channels
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use heapless::{spsc::Producer, ArrayLength};
+use linux_sys::pid_t;
+
+use crate::export;
+
+/// Producer endpoint of a `#[channel(notify = true)]`
+///
+/// Enqueuing an item activates the consumer task, unless an activation is already pending
+pub struct Sender<'a, T, N>
+where
+    N: ArrayLength<T>,
+{
+    inner: &'a mut Producer<'static, T, N, u8>,
+    pending: &'static Pending,
+    tgid: pid_t,
+    tid: Option<pid_t>,
+    signo: u8,
+    task: u8,
+}
+
+impl<'a, T, N> Sender<'a, T, N>
+where
+    N: ArrayLength<T>,
+{
+    #[doc(hidden)]
+    pub unsafe fn new(
+        inner: &'a mut Producer<'static, T, N, u8>,
+        pending: &'static Pending,
+        tgid: pid_t,
+        tid: Option<pid_t>,
+        signo: u8,
+        task: u8,
+    ) -> Self {
+        Sender {
+            inner,
+            pending,
+            tgid,
+            tid,
+            signo,
+            task,
+        }
+    }
+
+    /// Adds an `item` to the end of the channel and notifies the consumer task
+    ///
+    /// Returns back the `item` if the channel is full
+    pub fn enqueue(&mut self, item: T) -> Result<(), T> {
+        self.inner.enqueue(item)?;
+
+        if self.pending.set() {
+            unsafe { export::enqueue(self.tgid, self.tid, self.signo, self.task, 0) }
+        }
+
+        Ok(())
+    }
+
+    /// Returns whether the channel can accept a new item
+    pub fn ready(&self) -> bool {
+        self.inner.ready()
+    }
+}
+
+/// Coalesces the activations of a task notified by a channel
+#[doc(hidden)]
+pub struct Pending {
+    inner: AtomicBool,
+}
+
+impl Pending {
+    pub const fn new() -> Self {
+        Pending {
+            inner: AtomicBool::new(false),
+        }
+    }
+
+    // returns `true` if the consumer task must be activated
+    fn set(&self) -> bool {
+        // NOTE `Release` publishes the item enqueued before this call to the consumer
+        !self.inner.swap(true, Ordering::AcqRel)
+    }
+
+    /// Must be called right before running the notified task
+    pub fn clear(&self) {
+        // NOTE `Acquire` makes all the items enqueued before the last `set` visible
+        self.inner.swap(false, Ordering::AcqRel);
+    }
+}
@@ -1,63 +1,86 @@
+#[cfg(not(feature = "sim"))]
+use core::ptr;
 use core::{
     cell::Cell,
-    mem::MaybeUninit,
+    mem,
     ops::Range,
-    ptr,
-    sync::atomic::{AtomicBool, AtomicI32, Ordering},
+    sync::atomic::{self, AtomicBool, AtomicI32, AtomicU32, AtomicU8, Ordering},
 };
 
 use heapless::spsc::SingleCore;
 pub use heapless::{
     consts,
     i::{BinaryHeap as iBinaryHeap, Queue as iQueue},
-    spsc::Queue,
+    spsc::{Consumer, Producer, Queue},
     BinaryHeap,
 };
-use linux_io::Stderr;
+pub(crate) use linux_io::export::fatal;
+use linux_io::time::SystemTime;
+use linux_rt::sync;
+#[cfg(not(feature = "sim"))]
+pub use linux_sys::pause;
 pub use linux_sys::{
-    cty::c_void, exit, getpid, pause, pid_t, sched_yield, siginfo_t, timer_t, SI_QUEUE,
+    cty::c_void, exit, getpid, gettid, pid_t, sched_yield, siginfo_t, timer_t, CLOCK_MONOTONIC,
+    CLOCK_REALTIME, SI_QUEUE,
+};
+use linux_sys::{sched_attr, Error};
+// NOTE the simulation backend (see `sim`) replaces the functions that use these
+#[cfg(not(feature = "sim"))]
+use linux_sys::{
+    clockid_t, itimerspec, sched_param, sigaction, sigevent, sighandler_t, sigval_t, timespec,
+    SigSet, SIGRTMIN, TIMER_ABSTIME,
 };
-use linux_sys::{sched_param, sigaction, sigevent, sighandler_t, sigval_t, SIGRTMIN};
 
-pub use crate::tq::{NotReady, TimerQueue};
+#[cfg(feature = "sim")]
+pub use crate::sim::{arm, enqueue, init_runtime, mask, pause, raise, register, timer_create};
+pub use crate::{
+    channel::Pending,
+    tq::{Clock, NotReady, TimerQueue},
+    tt::ScheduleTable,
+    Monotonic,
+};
 
 pub struct Barrier {
-    inner: AtomicBool,
+    inner: AtomicU32,
 }
 
 impl Barrier {
     pub const fn new() -> Self {
         Self {
-            inner: AtomicBool::new(false),
+            inner: AtomicU32::new(0),
         }
     }
 
     pub fn release(&self) {
-        self.inner.store(true, Ordering::Release)
+        self.inner.store(1, Ordering::Release);
+        sync::wake(&self.inner, i32::max_value());
     }
 
     pub fn wait(&self) {
-        while !self.inner.load(Ordering::Acquire) {}
+        while self.inner.load(Ordering::Acquire) == 0 {
+            sync::wait(&self.inner, 0);
+        }
     }
 }
 
 pub struct Pid {
-    inner: AtomicI32,
+    inner: AtomicU32,
 }
 
 impl Pid {
     pub const fn uninit() -> Self {
         Self {
-            inner: AtomicI32::new(0),
+            inner: AtomicU32::new(0),
         }
     }
 
     pub fn get(&self) -> pid_t {
-        self.inner.load(Ordering::Relaxed)
+        self.inner.load(Ordering::Relaxed) as pid_t
     }
 
     pub fn init(&self, pid: pid_t) {
-        self.inner.store(pid, Ordering::Relaxed)
+        self.inner.store(pid as u32, Ordering::Relaxed);
+        sync::wake(&self.inner, i32::max_value());
     }
 
     pub fn wait(&self) -> pid_t {
@@ -65,9 +88,9 @@ impl Pid {
             let pid = self.inner.load(Ordering::Relaxed);
 
             if pid == 0 {
-                linux_sys::sched_yield()
+                sync::wait(&self.inner, 0);
             } else {
-                break pid;
+                break pid as pid_t;
             }
         }
     }
@@ -93,21 +116,105 @@ impl Timer {
     }
 }
 
+/// Cross-core lock used by `#[shared]` resources
+pub struct Spinlock {
+    locked: AtomicBool,
+    // position of the resource in the lock order
+    order: u8,
+}
+
+impl Spinlock {
+    pub const fn new(order: u8) -> Self {
+        Self {
+            locked: AtomicBool::new(false),
+            order,
+        }
+    }
+
+    fn acquire(&self) {
+        while self
+            .locked
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            // wait until the lock looks free before retrying the (more expensive) RMW operation
+            while self.locked.load(Ordering::Relaxed) {
+                atomic::spin_loop_hint();
+            }
+        }
+    }
+
+    fn release(&self) {
+        self.locked.store(false, Ordering::Release)
+    }
+}
+
+/// Tracks the innermost `#[shared]` resource held by a core
+pub struct Held {
+    // lock order of the resource plus one; `0` means that no resource is held
+    inner: AtomicU8,
+}
+
+impl Held {
+    pub const fn new() -> Self {
+        Self {
+            inner: AtomicU8::new(0),
+        }
+    }
+
+    // returns the previous state, which must be passed to `exit`
+    fn enter(&self, order: u8) -> u8 {
+        let outer = self.inner.load(Ordering::Relaxed);
+
+        // NOTE `#[app]` rejects out of order nested `lock` calls; this catches the ones it can't
+        // see (e.g. made through a reference to the resource proxy)
+        debug_assert!(
+            outer <= order,
+            "`#[shared]` resources must be locked in declaration order"
+        );
+
+        self.inner.store(order + 1, Ordering::Relaxed);
+        outer
+    }
+
+    fn exit(&self, outer: u8) {
+        self.inner.store(outer, Ordering::Relaxed)
+    }
+}
+
 pub type FreeQueue<N> = Queue<u8, N, u8, SingleCore>;
 
 // The PID `0` represents the current process
 const OURSELVES: pid_t = 0;
 
-pub unsafe fn init_runtime(signo_max: Option<u8>) {
+// NOTE the signal number `signo` used throughout the runtime is relative to `SIGRTMIN`; the
+// `#[app]` macro allocates signal numbers within its `signals` range
+#[cfg(not(feature = "sim"))]
+fn signal(signo: u8) -> i32 {
+    SIGRTMIN + i32::from(signo)
+}
+
+/// Initializes the runtime; `signals` are the real-time signals used by the application
+#[cfg(not(feature = "sim"))]
+pub unsafe fn init_runtime(signals: Option<Range<u8>>, pin: bool) {
     // NOTE all threads spawned (`sys_clone`) from this one will inherit these settings
 
     // start by running all threads on a single core
-    set_affinity(OURSELVES, 0);
+    if pin {
+        set_affinity(OURSELVES, 0);
+    }
+
+    // threads spawned by `std` code shouldn't compete with the runtime at a real-time priority
+    let reset_on_fork = if cfg!(feature = "std") {
+        linux_sys::SCHED_RESET_ON_FORK
+    } else {
+        0
+    };
 
     // raise the priority to the minimal real-time priority
     linux_sys::sched_setscheduler(
         OURSELVES,
-        linux_sys::SCHED_FIFO,
+        linux_sys::SCHED_FIFO | reset_on_fork,
         &sched_param { sched_priority: 1 },
     )
     .unwrap_or_else(|_| {
@@ -117,43 +224,71 @@ pub unsafe fn init_runtime(signo_max: Option<u8>) {
         )
     });
 
-    // block all the used real-time signals; this is equivalent to `interrupt::disable`
-    if let Some(signo) = signo_max {
-        let mask = ((1 << (signo + 1)) - 1) << (SIGRTMIN - 1);
-        linux_sys::rt_sigprocmask(linux_sys::SIG_BLOCK, &mask, ptr::null_mut())
+    if let Some(signals) = signals {
+        #[cfg(feature = "std")]
+        {
+            extern "C" {
+                fn __libc_current_sigrtmin() -> i32;
+            }
+
+            if __libc_current_sigrtmin() > signal(signals.start) {
+                fatal("error: the C library reserves the real-time signals used by the runtime\n")
+            }
+        }
+
+        // block all the used real-time signals; this is equivalent to `interrupt::disable`
+        linux_sys::rt_sigprocmask(linux_sys::SIG_BLOCK, &SigSet::rt(signals), ptr::null_mut())
             .unwrap_or_else(|_| fatal("error: couldn't change the signal mask\n"));
     }
 }
 
-pub unsafe fn spawn(child: extern "C" fn() -> !) -> pid_t {
-    const PAGE_SIZE: u64 = 4 * 1024; // 4 KiB (output of `getconf PAGESIZE`)
-    const STACK_SIZE: u64 = 2 * 1024 * PAGE_SIZE; // 8 MiB (output of `ulimit -s`)
-
-    linux_sys::mmap(
-        0,          // address; 0 means any page-aligned address
-        STACK_SIZE, // length of mapping
-        linux_sys::PROT_READ | // read access
-        linux_sys::PROT_WRITE, // write access
-        linux_sys::MAP_ANONYMOUS | // mapping is not backed by any file
-        linux_sys::MAP_PRIVATE | // mapping is private to other threads / processes
-        linux_sys::MAP_GROWSDOWN | // mapping suitable for stacks
-        linux_sys::MAP_UNINITIALIZED, // leave memory uninitialized
-        !0,         // file descriptor; needs to be `-1` because of MAP_ANONYMOUS
-        0,          // offset; ignored because of MAP_ANONYMOUS
+/// Switches the calling thread to the `SCHED_DEADLINE` policy
+///
+/// `runtime`, `deadline` and `period` are in microseconds
+pub unsafe fn set_deadline(runtime: u32, deadline: u32, period: u32) {
+    const NANOS_IN_ONE_MICRO: u64 = 1_000;
+
+    linux_sys::sched_setattr(
+        OURSELVES,
+        &sched_attr {
+            size: mem::size_of::<sched_attr>() as u32,
+            sched_policy: linux_sys::SCHED_DEADLINE as u32,
+            sched_flags: 0,
+            sched_nice: 0,
+            sched_priority: 0,
+            sched_runtime: u64::from(runtime) * NANOS_IN_ONE_MICRO,
+            sched_deadline: u64::from(deadline) * NANOS_IN_ONE_MICRO,
+            sched_period: u64::from(period) * NANOS_IN_ONE_MICRO,
+        },
+        0,
     )
-    .and_then(|stack_low| {
-        // the stack grows downwards so we must pass the highest address of the mapping to `clone`
-        let stack_high = (stack_low as u64 + STACK_SIZE) as *mut _;
-
-        // spin a new thread
-        linux_sys::x86_64_clone(
-            linux_sys::CLONE_VM | // new thread shares memory with the parent
-            linux_sys::CLONE_THREAD | // share thread group
-            linux_sys::CLONE_SIGHAND, // shared signal handlers; required by `CLONE_THREAD`
-            stack_high,
-            child,
-        )
+    .unwrap_or_else(|e| match e {
+        Error::EBUSY => fatal(
+            "error: SCHED_DEADLINE admission control rejected the reservation; the total \
+             bandwidth (runtime / period) requested by all cores exceeds the bandwidth available \
+             to real-time tasks (see `/proc/sys/kernel/sched_rt_runtime_us`)\n",
+        ),
+
+        Error::EINVAL => fatal("error: invalid SCHED_DEADLINE parameters\n"),
+
+        Error::EPERM => fatal(
+            "error: couldn't switch to SCHED_DEADLINE; run `sudo setcap cap_sys_nice+ep $binary` \
+             first and check that the process CPU affinity spans all CPUs\n",
+        ),
+
+        _ => fatal("error: couldn't change scheduling policy to SCHED_DEADLINE\n"),
+    });
+}
+
+pub unsafe fn spawn(child: extern "C" fn() -> !) -> pid_t {
+    const PAGE_SIZE: usize = 4 * 1024; // 4 KiB (output of `getconf PAGESIZE`)
+    const STACK_SIZE: usize = 2 * 1024 * PAGE_SIZE; // 8 MiB (output of `ulimit -s`)
+
+    // NOTE the handle is dropped (the thread is detached) because `child` never returns
+    linux_rt::thread::spawn(STACK_SIZE, move || {
+        child();
     })
+    .map(|handle| handle.tid())
     .unwrap_or_else(|_| fatal("error: couldn't spawn a new thread\n"))
 }
 
@@ -162,7 +297,8 @@ pub unsafe fn set_affinity(tid: pid_t, core: u8) {
         .unwrap_or_else(|_| fatal("error: couldn't change CPU affinity\n"));
 }
 
-pub unsafe fn timer_create(tid: Option<pid_t>, signo: u8) -> timer_t {
+#[cfg(not(feature = "sim"))]
+pub unsafe fn timer_create(clock: clockid_t, tid: Option<pid_t>, signo: u8) -> timer_t {
     let (sigev_notify, sigev_tid) = if let Some(tid) = tid {
         // multi-core application
         (linux_sys::SIGEV_THREAD_ID, tid)
@@ -171,10 +307,10 @@ pub unsafe fn timer_create(tid: Option<pid_t>, signo: u8) -> timer_t {
         (linux_sys::SIGEV_SIGNAL, 0)
     };
     linux_sys::timer_create(
-        linux_sys::CLOCK_MONOTONIC,
+        clock,
         &sigevent {
             sigev_value: sigval_t { sival_int: 0 },
-            sigev_signo: SIGRTMIN + i32::from(signo),
+            sigev_signo: signal(signo),
             sigev_notify,
             sigev_tid,
         },
@@ -182,6 +318,51 @@ pub unsafe fn timer_create(tid: Option<pid_t>, signo: u8) -> timer_t {
     .unwrap_or_else(|_| fatal("error: couldn't create a timer\n"))
 }
 
+/// Arms `timer` to fire at the absolute time `it_value`
+#[cfg(not(feature = "sim"))]
+pub unsafe fn arm(timer: timer_t, it_value: timespec) {
+    linux_sys::timer_settime(
+        timer,
+        TIMER_ABSTIME,
+        &itimerspec {
+            it_interval: timespec {
+                tv_sec: 0,
+                tv_nsec: 0,
+            },
+            it_value,
+        },
+        ptr::null_mut(),
+    )
+    .unwrap_or_else(|_| fatal("error: couldn't set timeout\n"));
+}
+
+/// Sends the (timer queue) signal `signo` to the thread `tid` of the process `tgid`, or to the
+/// whole process if `tgid_tid` is `None`
+#[cfg(not(feature = "sim"))]
+pub unsafe fn raise(tgid_tid: Option<(pid_t, pid_t)>, signo: u8) {
+    if let Some((tgid, tid)) = tgid_tid {
+        // multi-core application
+        linux_sys::tgkill(tgid, tid, signal(signo))
+            .unwrap_or_else(|_| fatal("error: couldn't send a signal\n"));
+    } else {
+        // single core application
+        linux_sys::kill(0, signal(signo))
+            .unwrap_or_else(|_| fatal("error: couldn't send a signal\n"));
+    }
+}
+
+/// Estimates the `M` instant that corresponds to the wall-clock `time`, or returns "now" if `time`
+/// is in the past
+///
+/// NOTE the estimate is only valid as long as the system time is not set
+pub fn wall_to_instant<M>(time: SystemTime) -> M
+where
+    M: Monotonic,
+{
+    let (now, instant) = (SystemTime::now(), M::now());
+    instant + time.duration_since(now).unwrap_or_default()
+}
+
 pub unsafe fn lock<T, R>(
     ptr: *mut T,
     priority: &Priority,
@@ -203,10 +384,37 @@ pub unsafe fn lock<T, R>(
     }
 }
 
+/// `lock` for resources shared between cores
+///
+/// `ceiling` is the local ceiling of the resource, i.e. the ceiling computed from the tasks that run
+/// on the calling core. Raising it keeps the local tasks that use the resource from preempting the
+/// critical section; the spinlock keeps the tasks that run on the other cores out.
+pub unsafe fn lock_shared<T, R>(
+    ptr: *mut T,
+    priority: &Priority,
+    ceiling: u8,
+    range: Range<u8>,
+    spinlock: &Spinlock,
+    held: &Held,
+    f: impl FnOnce(&mut T) -> R,
+) -> R {
+    lock(ptr, priority, ceiling, range, |data| {
+        // NOTE locks must be taken in the same (declaration) order on all cores to avoid deadlocks
+        let outer = held.enter(spinlock.order);
+        spinlock.acquire();
+        let r = f(data);
+        spinlock.release();
+        held.exit(outer);
+        r
+    })
+}
+
+#[cfg(not(feature = "sim"))]
 pub unsafe fn mask(Range { start, end }: Range<u8>, current: u8, ceiling: u8, block: bool) {
-    let len = end.wrapping_sub(start);
-    let mask =
-        ((1 << (ceiling - current)) - 1) << (SIGRTMIN - 1 + i32::from(start + len - ceiling));
+    debug_assert!(current <= ceiling && ceiling <= end.wrapping_sub(start));
+
+    // the signals of the priority levels `current + 1 ..= ceiling`
+    let mask = SigSet::rt(end - ceiling..end - current);
     linux_sys::rt_sigprocmask(
         if block {
             linux_sys::SIG_BLOCK
@@ -219,20 +427,22 @@ pub unsafe fn mask(Range { start, end }: Range<u8>, current: u8, ceiling: u8, bl
     .unwrap_or_else(|_| fatal("error: couldn't change the signal mask\n"));
 }
 
+#[cfg(not(feature = "sim"))]
 pub unsafe fn enqueue(tgid: i32, tid: Option<i32>, signo: u8, task: u8, index: u8) {
-    let mut si: siginfo_t = MaybeUninit::uninit().assume_init();
-    si.si_code = linux_sys::SI_QUEUE;
-    si.si_value = (usize::from(task) << 8) + usize::from(index);
+    let si = siginfo_t::queue(sigval_t {
+        sival_int: (i32::from(task) << 8) + i32::from(index),
+    });
 
     if let Some(tid) = tid {
-        linux_sys::rt_tgsigqueueinfo(tgid, tid, SIGRTMIN + i32::from(signo), &si)
+        linux_sys::rt_tgsigqueueinfo(tgid, tid, signal(signo), &si)
             .unwrap_or_else(|_| fatal("error: couldn't enqueue signal\n"));
     } else {
-        linux_sys::rt_sigqueueinfo(tgid, SIGRTMIN + i32::from(signo), &si)
+        linux_sys::rt_sigqueueinfo(tgid, signal(signo), &si)
             .unwrap_or_else(|_| fatal("error: couldn't enqueue signal\n"));
     }
 }
 
+#[cfg(not(feature = "sim"))]
 pub unsafe fn register(
     Range { start, end }: Range<u8>,
     priority: u8,
@@ -242,26 +452,22 @@ pub unsafe fn register(
         fn __restorer() -> !;
     }
 
-    let len = end.wrapping_sub(start);
-    let mask = (1 << len) - 1;
+    debug_assert!(priority >= 1 && priority <= end.wrapping_sub(start));
+
     linux_sys::rt_sigaction(
-        SIGRTMIN + i32::from(end.wrapping_sub(priority)),
+        signal(end.wrapping_sub(priority)),
         &sigaction {
             sa_: sighandler_t { sigaction },
             sa_flags: linux_sys::SA_RESTORER | linux_sys::SA_SIGINFO,
             sa_restorer: Some(__restorer),
-            sa_mask: (mask ^ (mask >> (priority - 1))) << (i32::from(start) + SIGRTMIN - 1),
+            // block the signals of the lower priority levels `1..priority`
+            sa_mask: SigSet::rt(end - priority + 1..end),
         },
         ptr::null_mut(),
     )
     .unwrap_or_else(|_| fatal("error: couldn't register signal handler\n"));
 }
 
-pub(crate) fn fatal(s: &str) -> ! {
-    Stderr.write(s.as_bytes()).ok();
-    linux_sys::exit_group(101)
-}
-
 // Newtype over `Cell` that forbids mutation through a shared reference
 pub struct Priority {
     inner: Cell<u8>,
@@ -1,10 +1,50 @@
 #![deny(warnings)]
 #![no_std]
 
+pub mod channel;
 pub mod export;
+mod monotonic;
+pub mod pool;
+#[cfg(feature = "sim")]
+pub mod sim;
 mod tq;
+mod tt;
 
+#[cfg(not(feature = "sim"))]
 pub use linux_io::time::Instant;
-use linux_rt as _;
+pub use linux_io::time::{BootInstant, SystemTime, TaiInstant, UNIX_EPOCH};
+pub use linux_rt::{args, auxv, env};
 pub use linux_rtfm_macros::app;
 pub use rtfm_core::Mutex;
+
+pub use crate::monotonic::Monotonic;
+#[cfg(feature = "sim")]
+pub use crate::sim::Instant;
+
+/// Runs the `#[app]`: `init` and then `idle`
+///
+/// With the `std` feature the program has an ordinary `fn main`, which must call this function
+/// once, after any set up that needs to happen before `init`
+#[cfg(feature = "std")]
+pub fn start() -> ! {
+    use core::sync::atomic::{AtomicBool, Ordering};
+
+    extern "Rust" {
+        // generated by `#[app]`
+        fn __rtfm_main() -> u8;
+    }
+
+    static STARTED: AtomicBool = AtomicBool::new(false);
+
+    if STARTED.swap(true, Ordering::Relaxed) {
+        export::fatal("error: `rtfm::start` must be called only once\n")
+    }
+
+    // NOTE this also makes the linker keep `linux-rt`'s constructor, which sets up `args`, `env`,
+    // `auxv` and the vDSO
+    linux_rt::export::assert_init();
+
+    let code = unsafe { __rtfm_main() };
+
+    linux_io::process::exit(code)
+}
@@ -0,0 +1,47 @@
+use core::{ops, time::Duration};
+
+use linux_io::time::{BootInstant, Instant, TaiInstant};
+use linux_sys::{clockid_t, timespec, CLOCK_BOOTTIME, CLOCK_MONOTONIC, CLOCK_TAI};
+
+/// A clock that drives the `schedule` API, selected with `#[app(monotonic = ..)]`
+///
+/// This trait is implemented by the instant type of the clock; the framework uses that type
+/// wherever the default `rtfm::Instant` would be used (e.g. `schedule.foo(instant)` and the
+/// `scheduled` field of task contexts)
+pub trait Monotonic: Copy + Ord + ops::Add<Duration, Output = Self> + Into<timespec> {
+    /// The kernel clock the timer queue timer is created on (see `man 2 timer_create`)
+    ///
+    /// NOTE the kernel doesn't support timers on every clock; `CLOCK_MONOTONIC_RAW`, for example,
+    /// can't be used
+    const CLOCK: clockid_t;
+
+    /// Returns the current time
+    ///
+    /// A fake clock may return any value here; the timer is then armed, on `CLOCK`, with the
+    /// values this clock produces
+    fn now() -> Self;
+}
+
+impl Monotonic for Instant {
+    const CLOCK: clockid_t = CLOCK_MONOTONIC;
+
+    fn now() -> Self {
+        Instant::now()
+    }
+}
+
+impl Monotonic for BootInstant {
+    const CLOCK: clockid_t = CLOCK_BOOTTIME;
+
+    fn now() -> Self {
+        BootInstant::now()
+    }
+}
+
+impl Monotonic for TaiInstant {
+    const CLOCK: clockid_t = CLOCK_TAI;
+
+    fn now() -> Self {
+        TaiInstant::now()
+    }
+}
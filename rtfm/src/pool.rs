@@ -0,0 +1,160 @@
+//! Fixed-size memory pools
+//!
+//! Handles to pool blocks are pointer-sized (plus an index) so moving them around, e.g. through
+//! `spawn`, doesn't copy the block contents.
+
+use core::{
+    cell::UnsafeCell,
+    ops::{Deref, DerefMut},
+    ptr,
+    sync::atomic::{AtomicPtr, AtomicU32, AtomicU64, Ordering},
+};
+
+use ufmt::{uDebug, uWrite, Formatter};
+
+// marks the end of the free list
+const NIL: u32 = u32::max_value();
+
+/// A memory block
+pub struct Node<T> {
+    // index of the next free block; only meaningful while the block is in the free list
+    next: AtomicU32,
+    data: UnsafeCell<T>,
+}
+
+impl<T> Node<T> {
+    pub const fn new(data: T) -> Self {
+        Node {
+            next: AtomicU32::new(NIL),
+            data: UnsafeCell::new(data),
+        }
+    }
+}
+
+/// A lock-free pool of memory blocks that can be used from any core
+pub struct Pool<T> {
+    // free list (Treiber stack): (ABA tag << 32) | index of the first free block
+    head: AtomicU64,
+    nodes: AtomicPtr<Node<T>>,
+}
+
+unsafe impl<T> Sync for Pool<T> where T: Send {}
+
+impl<T> Pool<T> {
+    pub const fn new() -> Self {
+        Pool {
+            head: AtomicU64::new(NIL as u64),
+            nodes: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    /// Hands the memory `nodes` over to the pool
+    ///
+    /// # Safety
+    ///
+    /// Must be called once, before any call to `alloc`
+    pub unsafe fn init(&self, nodes: &'static mut [Node<T>]) {
+        let len = nodes.len() as u32;
+        for (i, node) in nodes.iter_mut().enumerate() {
+            let i = i as u32;
+            *node.next.get_mut() = if i + 1 == len { NIL } else { i + 1 };
+        }
+
+        self.nodes.store(nodes.as_mut_ptr(), Ordering::Relaxed);
+        self.head
+            .store(if len == 0 { u64::from(NIL) } else { 0 }, Ordering::Release);
+    }
+
+    /// Takes a block from the pool; returns `None` if the pool is exhausted
+    ///
+    /// NOTE the block holds the value it had when it was last returned to the pool
+    pub fn alloc(&'static self) -> Option<Box<T>> {
+        let mut head = self.head.load(Ordering::Acquire);
+
+        loop {
+            let index = head as u32;
+            if index == NIL {
+                return None;
+            }
+
+            let next = self.node(index).next.load(Ordering::Relaxed);
+            // NOTE bumping the tag on each update prevents the ABA problem
+            let new = (((head >> 32) + 1) << 32) | u64::from(next);
+
+            match self
+                .head
+                .compare_exchange_weak(head, new, Ordering::Acquire, Ordering::Acquire)
+            {
+                Ok(_) => return Some(Box { pool: self, index }),
+                Err(current) => head = current,
+            }
+        }
+    }
+
+    fn free(&self, index: u32) {
+        let mut head = self.head.load(Ordering::Relaxed);
+
+        loop {
+            self.node(index).next.store(head as u32, Ordering::Relaxed);
+            let new = (((head >> 32) + 1) << 32) | u64::from(index);
+
+            match self
+                .head
+                .compare_exchange_weak(head, new, Ordering::Release, Ordering::Relaxed)
+            {
+                Ok(_) => return,
+                Err(current) => head = current,
+            }
+        }
+    }
+
+    fn node(&self, index: u32) -> &Node<T> {
+        unsafe { &*self.nodes.load(Ordering::Relaxed).add(index as usize) }
+    }
+}
+
+/// Owning handle to a pool block; the block returns to its pool when the handle is dropped
+pub struct Box<T>
+where
+    T: 'static,
+{
+    pool: &'static Pool<T>,
+    index: u32,
+}
+
+unsafe impl<T> Send for Box<T> where T: Send {}
+
+unsafe impl<T> Sync for Box<T> where T: Sync {}
+
+impl<T> Deref for Box<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.pool.node(self.index).data.get() }
+    }
+}
+
+impl<T> DerefMut for Box<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.pool.node(self.index).data.get() }
+    }
+}
+
+impl<T> Drop for Box<T> {
+    fn drop(&mut self) {
+        // NOTE the value is *not* dropped; blocks always hold a valid value
+        self.pool.free(self.index)
+    }
+}
+
+impl<T> uDebug for Box<T>
+where
+    T: uDebug,
+{
+    fn fmt<W>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: uWrite + ?Sized,
+    {
+        T::fmt(self, f)
+    }
+}
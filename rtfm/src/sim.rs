@@ -0,0 +1,282 @@
+//! Deterministic simulation backend (`sim` feature)
+//!
+//! In this mode the application doesn't use real-time signals nor POSIX timers. Instead:
+//!
+//! - `spawn`, `schedule` and timer expirations push events into an in-process queue. Events are
+//!   delivered, synchronously, as soon as they are not masked, which is when a real signal would
+//!   have been delivered; this preserves the preemption and `lock` semantics of the real backend.
+//!
+//! - `rtfm::Instant` reads a virtual clock that starts at zero and only moves forward when the
+//!   application is idle: `pause` (the default `idle` loop) advances the clock to the earliest timer
+//!   deadline and fires that timer.
+//!
+//! - The simulation ends, with `process::exit(0)`, when `pause` is called and no event is pending
+//!   and no timer is armed.
+//!
+//! As a result a whole application runs in (real) microseconds regardless of its timeouts, and
+//! every run produces the same interleaving of tasks.
+//!
+//! NOTE only single-core applications that use the default clock can be simulated; the `#[app]`
+//! macro rejects the `monotonic` and `wall_clock` arguments, whose clocks can't be simulated. An
+//! `idle` function that never calls `pause` stops the clock.
+
+use core::{ops::Range, ptr, time::Duration};
+
+use linux_io::process;
+use linux_sys::{
+    clockid_t, cty::c_void, pid_t, siginfo_t, sigval_t, timer_t, timespec, CLOCK_MONOTONIC,
+    SIGRTMIN,
+};
+
+use crate::{export::fatal, Monotonic};
+
+const NANOS_IN_ONE_SEC: u64 = 1_000_000_000;
+
+/// A measurement of the virtual clock
+#[derive(Clone, Copy, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Instant {
+    nanos: u64,
+}
+
+impl Instant {
+    /// Returns the current virtual time
+    pub fn now() -> Self {
+        Instant {
+            nanos: unsafe { NOW },
+        }
+    }
+
+    /// Returns the amount of virtual time elapsed since the simulation started
+    pub fn since_start(&self) -> Duration {
+        Duration::from_nanos(self.nanos)
+    }
+
+    /// Returns `Some(t)` where t is the time `self + duration` if t can be represented as
+    /// `Instant`, `None` otherwise
+    pub fn checked_add(&self, dur: Duration) -> Option<Instant> {
+        let nanos = dur
+            .as_secs()
+            .checked_mul(NANOS_IN_ONE_SEC)?
+            .checked_add(u64::from(dur.subsec_nanos()))?;
+
+        self.nanos.checked_add(nanos).map(|nanos| Instant { nanos })
+    }
+
+    /// Returns the amount of time elapsed from another instant to this one, or `None` if that
+    /// instant is earlier than this one
+    pub fn checked_duration_since(&self, earlier: Self) -> Option<Duration> {
+        self.nanos
+            .checked_sub(earlier.nanos)
+            .map(Duration::from_nanos)
+    }
+
+    /// Returns the amount of time elapsed from another instant to this one, or zero duration if
+    /// that instant is earlier than this one
+    pub fn saturating_duration_since(&self, earlier: Self) -> Duration {
+        self.checked_duration_since(earlier)
+            .unwrap_or(Duration::new(0, 0))
+    }
+}
+
+impl core::ops::Add<Duration> for Instant {
+    type Output = Self;
+
+    fn add(self, dur: Duration) -> Self {
+        self.checked_add(dur).unwrap()
+    }
+}
+
+impl From<Instant> for timespec {
+    fn from(i: Instant) -> timespec {
+        timespec {
+            tv_sec: (i.nanos / NANOS_IN_ONE_SEC) as i64,
+            tv_nsec: (i.nanos % NANOS_IN_ONE_SEC) as i64,
+        }
+    }
+}
+
+impl Monotonic for Instant {
+    // NOTE not used; simulated timers ignore the clock
+    const CLOCK: clockid_t = CLOCK_MONOTONIC;
+
+    fn now() -> Self {
+        Instant::now()
+    }
+}
+
+type Handler = extern "C" fn(i32, &mut siginfo_t, *mut c_void);
+
+// NOTE the simulation is single-threaded; signal "handlers" run nested on the same stack so all
+// this state is only ever accessed from a single thread and never across a call to a handler
+
+/// Virtual time, in nanoseconds
+static mut NOW: u64 = 0;
+
+/// Blocked signals; bit `n` is the real-time signal `SIGRTMIN + n`
+static mut MASK: u64 = 0;
+
+/// Registered handlers and the signals they block while they run
+static mut HANDLERS: [Option<(Handler, u64)>; 33] = [None; 33];
+
+/// Pending events: (sequence number, signal, `sigval` or `None` for timer expirations)
+static mut PENDING: [Option<(u64, u8, Option<i32>)>; 64] = [None; 64];
+static mut SEQ: u64 = 0;
+
+/// Simulated timers: (signal, deadline in nanoseconds if armed)
+static mut TIMERS: [Option<(u8, Option<u64>)>; 8] = [None; 8];
+
+// bit mask for the real-time signals `start..end`
+fn bits(Range { start, end }: Range<u8>) -> u64 {
+    if start >= end {
+        0
+    } else {
+        ((1 << (end - start)) - 1) << start
+    }
+}
+
+fn nanos(ts: timespec) -> u64 {
+    ts.tv_sec as u64 * NANOS_IN_ONE_SEC + ts.tv_nsec as u64
+}
+
+// queues an event and delivers it right away if it's not masked
+unsafe fn raise_(signo: u8, value: Option<i32>) {
+    let slot = PENDING
+        .iter_mut()
+        .find(|slot| slot.is_none())
+        .unwrap_or_else(|| fatal("error: the simulated signal queue is full\n"));
+
+    *slot = Some((SEQ, signo, value));
+    SEQ += 1;
+
+    deliver();
+}
+
+// runs the handlers of all the pending, unmasked events; like the kernel, lower signal numbers
+// (higher priorities) first and, within a signal, in FIFO order
+unsafe fn deliver() {
+    loop {
+        let mask = MASK;
+        let next = PENDING
+            .iter()
+            .enumerate()
+            .filter_map(|(i, slot)| slot.map(|(seq, signo, _)| (signo, seq, i)))
+            .filter(|(signo, _, _)| mask & (1 << signo) == 0)
+            .min();
+
+        let i = if let Some((_, _, i)) = next {
+            i
+        } else {
+            break;
+        };
+
+        let (_, signo, value) = PENDING[i].take().unwrap();
+        let (handler, sa_mask) = HANDLERS[usize::from(signo)]
+            .unwrap_or_else(|| fatal("error: simulated signal has no handler\n"));
+
+        let mut si = match value {
+            Some(sival_int) => siginfo_t::queue(sigval_t { sival_int }),
+            // NOTE all-zeros is a valid bit pattern for this struct; it's treated as a timer
+            // expiration
+            None => core::mem::zeroed(),
+        };
+
+        MASK = mask | sa_mask | (1 << signo);
+        handler(SIGRTMIN + i32::from(signo), &mut si, ptr::null_mut());
+        MASK = mask;
+    }
+}
+
+pub unsafe fn init_runtime(signals: Option<Range<u8>>, _pin: bool) {
+    // NOTE no need to change the scheduling policy; the simulation is not real-time
+    if let Some(signals) = signals {
+        MASK |= bits(signals);
+    }
+}
+
+pub unsafe fn timer_create(_clock: clockid_t, _tid: Option<pid_t>, signo: u8) -> timer_t {
+    let i = TIMERS
+        .iter()
+        .position(|timer| timer.is_none())
+        .unwrap_or_else(|| fatal("error: couldn't create a timer\n"));
+
+    TIMERS[i] = Some((signo, None));
+    i as timer_t
+}
+
+pub unsafe fn arm(timer: timer_t, it_value: timespec) {
+    let (signo, deadline) = TIMERS[timer as usize]
+        .as_mut()
+        .unwrap_or_else(|| fatal("error: couldn't set timeout\n"));
+
+    let at = nanos(it_value);
+    if at <= NOW {
+        // NOTE if the deadline is already in the past the timer fires right away
+        *deadline = None;
+        let signo = *signo;
+        raise_(signo, None);
+    } else {
+        *deadline = Some(at);
+    }
+}
+
+pub unsafe fn raise(_tgid_tid: Option<(pid_t, pid_t)>, signo: u8) {
+    raise_(signo, None)
+}
+
+pub unsafe fn enqueue(_tgid: i32, _tid: Option<i32>, signo: u8, task: u8, index: u8) {
+    raise_(signo, Some((i32::from(task) << 8) + i32::from(index)))
+}
+
+pub unsafe fn register(
+    Range { start, end }: Range<u8>,
+    priority: u8,
+    sigaction: extern "C" fn(i32, &mut siginfo_t, *mut c_void),
+) {
+    debug_assert!(priority >= 1 && priority <= end.wrapping_sub(start));
+
+    HANDLERS[usize::from(end.wrapping_sub(priority))] =
+        Some((sigaction, bits(end - priority + 1..end)));
+}
+
+pub unsafe fn mask(Range { start, end }: Range<u8>, current: u8, ceiling: u8, block: bool) {
+    debug_assert!(current <= ceiling && ceiling <= end.wrapping_sub(start));
+
+    let mask = bits(end - ceiling..end - current);
+    if block {
+        MASK |= mask;
+    } else {
+        MASK &= !mask;
+
+        // unblocking may make pending events deliverable
+        deliver();
+    }
+}
+
+/// Advances the virtual clock to the earliest timer deadline and fires that timer
+///
+/// Ends the simulation if there's nothing left to do
+pub fn pause() {
+    unsafe {
+        deliver();
+
+        let next = TIMERS
+            .iter()
+            .enumerate()
+            .filter_map(|(i, timer)| timer.and_then(|(_, deadline)| deadline.map(|at| (at, i))))
+            .min();
+
+        if let Some((at, i)) = next {
+            let (signo, deadline) = TIMERS[i].as_mut().unwrap();
+            *deadline = None;
+            let signo = *signo;
+
+            if at > NOW {
+                NOW = at;
+            }
+
+            raise_(signo, None);
+        } else {
+            process::exit(0)
+        }
+    }
+}
@@ -1,24 +1,52 @@
-use core::{cmp::Ordering, ptr};
+use core::cmp::Ordering;
 
 use heapless::{binary_heap::Min, ArrayLength, BinaryHeap};
-use linux_io::time::Instant;
-use linux_sys::{itimerspec, pid_t, timer_t, timespec, SIGRTMIN, TIMER_ABSTIME};
+use linux_io::time::{Instant, SystemTime};
+use linux_sys::{pid_t, timer_t, timespec};
 
-use crate::export::fatal;
+use crate::{export, Monotonic};
 
-pub struct TimerQueue<T, N>(pub BinaryHeap<NotReady<T>, N, Min>)
+/// The clock a `TimerQueue` is sorted by
+///
+/// The timer that drives the queue must have been created with the same clock
+pub trait Clock: Copy + Ord + Into<timespec> {
+    fn now() -> Self;
+}
+
+impl<M> Clock for M
+where
+    M: Monotonic,
+{
+    fn now() -> Self {
+        M::now()
+    }
+}
+
+// NOTE the kernel re-evaluates `TIMER_ABSTIME` timers on `CLOCK_REALTIME` when the system time is
+// set: a forward jump fires the timer right away and a backward jump postpones it. The handler then
+// compares the head of the queue against the new wall-clock time, so pending entries are never
+// released early nor lost
+impl Clock for SystemTime {
+    fn now() -> Self {
+        SystemTime::now()
+    }
+}
+
+pub struct TimerQueue<T, N, C = Instant>(pub BinaryHeap<NotReady<T, C>, N, Min>)
 where
     T: Copy,
-    N: ArrayLength<NotReady<T>>;
+    N: ArrayLength<NotReady<T, C>>,
+    C: Clock;
 
-impl<T, N> TimerQueue<T, N>
+impl<T, N, C> TimerQueue<T, N, C>
 where
     T: Copy,
-    N: ArrayLength<NotReady<T>>,
+    N: ArrayLength<NotReady<T, C>>,
+    C: Clock,
 {
     pub unsafe fn enqueue_unchecked(
         &mut self,
-        nr: NotReady<T>,
+        nr: NotReady<T, C>,
         tgid_tid: Option<(pid_t, pid_t)>,
         signo: u8,
     ) {
@@ -29,15 +57,7 @@ where
             .unwrap_or(true)
         {
             // new entry has earlier deadline; signal the timer queue
-            if let Some((tgid, tid)) = tgid_tid {
-                // multi-core application
-                linux_sys::tgkill(tgid, tid, SIGRTMIN + i32::from(signo))
-                    .unwrap_or_else(|_| fatal("error: couldn't send a signal\n"));
-            } else {
-                // single core application
-                linux_sys::kill(0, SIGRTMIN + i32::from(signo))
-                    .unwrap_or_else(|_| fatal("error: couldn't send a signal\n"));
-            }
+            export::raise(tgid_tid, signo);
         }
 
         self.0.push_unchecked(nr);
@@ -45,7 +65,7 @@ where
 
     pub fn dequeue(&mut self, timer_id: timer_t) -> Option<(T, u8)> {
         if let Some(instant) = self.0.peek().map(|p| p.instant) {
-            let now = Instant::now();
+            let now = C::now();
             if now >= instant {
                 // task became ready
                 let nr = unsafe { self.0.pop_unchecked() };
@@ -53,19 +73,7 @@ where
                 Some((nr.task, nr.index))
             } else {
                 // set a new timeout
-                linux_sys::timer_settime(
-                    timer_id,
-                    TIMER_ABSTIME,
-                    &itimerspec {
-                        it_interval: timespec {
-                            tv_sec: 0,
-                            tv_nsec: 0,
-                        },
-                        it_value: instant.into(),
-                    },
-                    ptr::null_mut(),
-                )
-                .unwrap_or_else(|_| fatal("error: couldn't set timeout\n"));
+                unsafe { export::arm(timer_id, instant.into()) }
 
                 None
             }
@@ -76,38 +84,47 @@ where
     }
 }
 
-pub struct NotReady<T>
+pub struct NotReady<T, C = Instant>
 where
     T: Copy,
+    C: Clock,
 {
     pub index: u8,
-    pub instant: Instant,
+    pub instant: C,
     pub task: T,
 }
 
-impl<T> Eq for NotReady<T> where T: Copy {}
+impl<T, C> Eq for NotReady<T, C>
+where
+    T: Copy,
+    C: Clock,
+{
+}
 
-impl<T> Ord for NotReady<T>
+impl<T, C> Ord for NotReady<T, C>
 where
     T: Copy,
+    C: Clock,
 {
     fn cmp(&self, other: &Self) -> Ordering {
         self.instant.cmp(&other.instant)
     }
 }
 
-impl<T> PartialEq for NotReady<T>
+impl<T, C> PartialEq for NotReady<T, C>
 where
     T: Copy,
+    C: Clock,
 {
     fn eq(&self, other: &Self) -> bool {
         self.instant == other.instant
     }
 }
 
-impl<T> PartialOrd for NotReady<T>
+impl<T, C> PartialOrd for NotReady<T, C>
 where
     T: Copy,
+    C: Clock,
 {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(&other))
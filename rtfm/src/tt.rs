@@ -0,0 +1,75 @@
+use core::time::Duration;
+
+use linux_sys::timer_t;
+
+use crate::{export, Monotonic};
+
+/// Cyclic executive: a static list of task releases that repeats every hyperperiod
+pub struct ScheduleTable<T, M>
+where
+    T: Copy + 'static,
+    M: Monotonic,
+{
+    hyperperiod: Duration,
+    // index into `slots` of the next release
+    next: usize,
+    // (offset in microseconds, task) sorted by offset
+    slots: &'static [(u32, T)],
+    // start of the current hyperperiod
+    start: M,
+    timer: timer_t,
+}
+
+impl<T, M> ScheduleTable<T, M>
+where
+    T: Copy,
+    M: Monotonic,
+{
+    /// Creates a new table whose first hyperperiod begins at `start` and arms `timer` for the first
+    /// release
+    ///
+    /// NOTE `slots` must be non-empty and sorted by offset
+    pub fn new(start: M, hyperperiod: u32, slots: &'static [(u32, T)], timer: timer_t) -> Self {
+        let table = ScheduleTable {
+            hyperperiod: Duration::from_micros(u64::from(hyperperiod)),
+            next: 0,
+            slots,
+            start,
+            timer,
+        };
+
+        table.arm();
+
+        table
+    }
+
+    /// Returns the task whose release time just elapsed, and that release time, and arms the timer
+    /// for the next release
+    pub fn next(&mut self) -> (T, M) {
+        let (offset, task) = self.slots[self.next];
+        let release = self.start + Duration::from_micros(u64::from(offset));
+
+        self.next += 1;
+        if self.next == self.slots.len() {
+            // wrap around: start a new cycle
+            self.next = 0;
+            self.start = self.start + self.hyperperiod;
+        }
+
+        self.arm();
+
+        (task, release)
+    }
+
+    fn arm(&self) {
+        let offset = self.slots[self.next].0;
+
+        // NOTE if the release time is already in the past the timer fires right away
+        unsafe {
+            export::arm(
+                self.timer,
+                (self.start + Duration::from_micros(u64::from(offset))).into(),
+            )
+        }
+    }
+}